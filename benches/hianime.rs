@@ -0,0 +1,44 @@
+// cargo bench --features bench --bench hianime
+use criterion::{criterion_group, criterion_main, Criterion};
+use enma::anime::hianime::{Scraper, SelectorTable};
+use std::{hint::black_box, path::PathBuf};
+
+fn fixture(parser: &str) -> String {
+    let name = parser.trim_start_matches("hianime:get_");
+    let path =
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(format!("fixtures/hianime/{name}.html"));
+    return std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {e}", path.display()));
+}
+
+/// runs every page parser over its fixture, once with the selectors the
+/// scraper compiled up front and once also parsing the selectors the parser
+/// selects with, the way the parsers did before they shared a selector table
+fn bench_page_parsers(c: &mut Criterion) {
+    let hianime = Scraper::new();
+
+    for parser in Scraper::bench_page_parsers() {
+        let page = fixture(parser);
+        assert!(hianime.bench_parse_page(parser, &page), "{parser} failed");
+
+        let mut group = c.benchmark_group(parser);
+        group.bench_function("precompiled", |b| {
+            b.iter(|| hianime.bench_parse_page(parser, black_box(&page)));
+        });
+        group.bench_function("inline", |b| {
+            b.iter(|| {
+                Scraper::bench_parse_inline_selectors(parser);
+                hianime.bench_parse_page(parser, black_box(&page))
+            });
+        });
+        group.finish();
+    }
+}
+
+fn bench_selector_table(c: &mut Criterion) {
+    c.bench_function("hianime:compile_selectors", |b| {
+        b.iter(|| black_box(SelectorTable::default()).validate().unwrap());
+    });
+}
+
+criterion_group!(benches, bench_page_parsers, bench_selector_table);
+criterion_main!(benches);
@@ -0,0 +1,26 @@
+#!/bin/sh
+# refreshes the goldens from a running aniwatch-api instance, keeping the
+# `data` part of each response and the first item of every list:
+#
+#   ANIWATCH_API=http://localhost:4000 fixtures/aniwatch/update.sh
+set -eu
+
+api="${ANIWATCH_API:?set ANIWATCH_API to the url of an aniwatch-api instance}/api/v2/hianime"
+dir="$(dirname "$0")"
+
+fetch() {
+    curl -fsS "$api$2" | jq 'def first_items: walk(if type == "array" then .[:1] else . end); .data | first_items' >"$dir/$1.json"
+}
+
+fetch home "/home"
+fetch az_list "/azlist/a?page=1"
+fetch qtip "/qtip/one-piece-100"
+fetch info "/anime/one-piece-100"
+fetch search "/search?q=titan&type=tv&start_date=2013-4-7"
+fetch search_suggestion "/search/suggestion?q=titan"
+fetch producer "/producer/toei-animation?page=1"
+fetch genre "/genre/shounen?page=1"
+fetch category "/category/most-popular?page=1"
+fetch schedule "/schedule?date=$(date +%Y-%m-%d)"
+fetch episodes "/anime/one-piece-100/episodes"
+fetch episode_servers "/episode/servers?animeEpisodeId=one-piece-100?ep=2142"
@@ -0,0 +1,9 @@
+// cargo +nightly fuzz run hianime_inputs
+#![no_main]
+
+use enma::anime::hianime::Scraper;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|input: &str| {
+    Scraper::fuzz_parse_input(input);
+});
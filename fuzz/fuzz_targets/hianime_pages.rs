@@ -0,0 +1,12 @@
+// cargo +nightly fuzz run hianime_pages
+#![no_main]
+
+use enma::anime::hianime::Scraper;
+use libfuzzer_sys::fuzz_target;
+use std::sync::LazyLock;
+
+static HIANIME: LazyLock<Scraper> = LazyLock::new(Scraper::new);
+
+fuzz_target!(|data: &[u8]| {
+    HIANIME.fuzz_parse_page(&String::from_utf8_lossy(data));
+});
@@ -1,2 +1,31 @@
-mod extractors;
+#[cfg(any(feature = "extractors", feature = "subtitles"))]
+use serde::{Deserialize, Serialize};
+
+/// downloads of HLS episode streams to local files
+#[cfg(feature = "download")]
+pub mod download;
+
+/// stream extractors of the anime providers' video servers
+#[cfg(feature = "extractors")]
+pub mod extractors;
+
+#[cfg(feature = "hianime")]
 pub mod hianime;
+
+/// subtitle tracks parsing, conversion and selection
+#[cfg(feature = "subtitles")]
+pub mod subtitles;
+
+/// subtitle track of a stream, what the extractors find and the subtitles
+/// client fetches
+#[cfg(any(feature = "extractors", feature = "subtitles"))]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SubtitleTrack {
+    pub url: String,
+    /// language or label of the track, e.g. `English` or `pt-BR`
+    pub lang: String,
+    /// whether the server marks it as the track to show by default
+    #[serde(default)]
+    pub default: bool,
+}
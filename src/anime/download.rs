@@ -0,0 +1,763 @@
+use crate::{
+    anime::{
+        extractors::hls::{self, KeyMethod, MediaPlaylist, Playlist, VariantPreference},
+        SubtitleTrack,
+    },
+    error::{EnmaError, EnmaResult},
+    utils::{EnmaClient, EnmaUtils},
+};
+use cbc::cipher::{block_padding::Pkcs7, BlockDecryptMut, KeyIvInit};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, RANGE},
+    Client,
+};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, AtomicUsize, Ordering},
+        Arc,
+    },
+    time::Duration,
+};
+use tokio::{
+    fs,
+    sync::{mpsc, Semaphore},
+    task::JoinSet,
+};
+
+const PROVIDER_PARSER: &'static str = "anime:download";
+
+/// name of the file, inside the parts directory, holding the url of the
+/// media playlist the parts were downloaded from
+const SOURCE_FILE: &'static str = "source";
+
+type Aes128CbcDec = cbc::Decryptor<aes::Aes128>;
+type ProgressCallback = Arc<dyn Fn(Progress) + Send + Sync>;
+type RemuxHook = Arc<dyn Fn(PathBuf) -> EnmaResult<PathBuf> + Send + Sync>;
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Progress {
+    pub segments_done: usize,
+    pub segments_total: usize,
+    /// segments a previous, interrupted, download had already saved
+    pub segments_resumed: usize,
+    /// bytes downloaded by this run
+    pub bytes: u64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Download {
+    /// the concatenated `.ts` file, or whatever the remux hook returned
+    pub video: PathBuf,
+    pub subtitles: Vec<PathBuf>,
+    pub segments: usize,
+    /// in seconds
+    pub duration: f64,
+}
+
+/// downloads HLS streams into a single local file
+///
+/// segments are downloaded concurrently into a `<output>.parts` directory
+/// next to the output, which is only removed once they've all been
+/// concatenated, so that downloading to the same output again after an
+/// interruption resumes where it stopped
+#[derive(Clone)]
+pub struct Downloader {
+    client: Client,
+    concurrency: usize,
+    retries: u32,
+    retry_delay: Duration,
+    preference: VariantPreference,
+    on_progress: Option<ProgressCallback>,
+    remux: Option<RemuxHook>,
+}
+
+/// one file of the parts directory
+#[derive(Debug)]
+struct Part {
+    url: String,
+    /// offset and length of the part within `url`
+    range: Option<(u64, u64)>,
+    /// aes-128 key and iv to decrypt the part with
+    key: Option<([u8; 16], [u8; 16])>,
+}
+
+impl Downloader {
+    /// `headers` are sent with every request, the hianime embeds e.g. expect
+    /// a `Referer`
+    pub fn new(headers: Option<HeaderMap>) -> Self {
+        return Self {
+            client: EnmaUtils::new_http_client(headers),
+            concurrency: 4,
+            retries: 3,
+            retry_delay: Duration::from_millis(500),
+            preference: VariantPreference::Best,
+            on_progress: None,
+            remux: None,
+        };
+    }
+
+    /// how many segments are downloaded at once, 4 by default
+    pub fn with_concurrency(mut self, concurrency: usize) -> Self {
+        self.concurrency = concurrency.max(1);
+        return self;
+    }
+
+    /// how many times a failed request is retried, waiting `delay` times the
+    /// attempt number in between, 3 times and 500ms by default
+    pub fn with_retries(mut self, retries: u32, delay: Duration) -> Self {
+        self.retries = retries;
+        self.retry_delay = delay;
+        return self;
+    }
+
+    /// variant picked when the url is a master playlist, the best by default
+    pub fn with_variant(mut self, preference: VariantPreference) -> Self {
+        self.preference = preference;
+        return self;
+    }
+
+    /// calls `on_progress` every time a segment is saved
+    pub fn with_progress(mut self, on_progress: impl Fn(Progress) + Send + Sync + 'static) -> Self {
+        self.on_progress = Some(Arc::new(on_progress));
+        return self;
+    }
+
+    /// same as [`Downloader::with_progress`] but as a stream of updates
+    pub fn progress_stream(self) -> (Self, mpsc::UnboundedReceiver<Progress>) {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let downloader = self.with_progress(move |progress| {
+            let _ = tx.send(progress);
+        });
+
+        return (downloader, rx);
+    }
+
+    /// runs `remux` over the concatenated `.ts` file, e.g. to shell out to
+    /// ffmpeg to turn it into an mp4, the path it returns becomes
+    /// [`Download::video`]
+    pub fn with_remux(
+        mut self,
+        remux: impl Fn(PathBuf) -> EnmaResult<PathBuf> + Send + Sync + 'static,
+    ) -> Self {
+        self.remux = Some(Arc::new(remux));
+        return self;
+    }
+
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::{download::Downloader, SubtitleTrack};
+    ///     use reqwest::header::{HeaderMap, HeaderValue, REFERER};
+    ///
+    ///     let mut headers = HeaderMap::new();
+    ///     headers.insert(REFERER, HeaderValue::from_static("https://megacloud.blog/"));
+    ///
+    ///     let (downloader, mut progress) = Downloader::new(Some(headers)).progress_stream();
+    ///     tokio::spawn(async move {
+    ///         while let Some(p) = progress.recv().await {
+    ///             println!("{}/{}", p.segments_done, p.segments_total);
+    ///         }
+    ///     });
+    ///
+    ///     let url = "https://cdn.example.com/hls/episode/master.m3u8";
+    ///     let subtitles = [SubtitleTrack {
+    ///         url: String::from("https://cdn.example.com/subs/eng-2.vtt"),
+    ///         lang: String::from("English"),
+    ///         default: true,
+    ///     }];
+    ///
+    ///     match downloader.download(url, "episode.ts", &subtitles).await {
+    ///         Ok(download) => println!("{:#?}", download),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "anime:download", skip(self, output, subtitles))]
+    pub async fn download(
+        &self,
+        playlist_url: &str,
+        output: impl AsRef<Path>,
+        subtitles: &[SubtitleTrack],
+    ) -> EnmaResult<Download> {
+        let output = output.as_ref();
+        let Some(file_name) = output.file_name() else {
+            return Err(EnmaError::invalid_data_error(
+                PROVIDER_PARSER,
+                Some(format!("invalid output path {output:?}")),
+                None,
+            ));
+        };
+
+        let (media_url, media) = self.fetch_media_playlist(playlist_url).await?;
+        let parts = self.plan_parts(&media).await?;
+
+        let mut parts_dir_name = file_name.to_os_string();
+        parts_dir_name.push(".parts");
+        let parts_dir = output.with_file_name(parts_dir_name);
+        prepare_parts_dir(&parts_dir, &media_url).await?;
+
+        self.download_parts(&parts_dir, parts).await?;
+        let segments = concat_parts(&parts_dir, output).await?;
+        fs::remove_dir_all(&parts_dir)
+            .await
+            .map_err(|e| io_error(&parts_dir, e))?;
+
+        let mut saved_subtitles = Vec::with_capacity(subtitles.len());
+        for track in subtitles {
+            let path = subtitle_path(output, track);
+            let body = self.fetch(&track.url, None).await?;
+            fs::write(&path, body)
+                .await
+                .map_err(|e| io_error(&path, e))?;
+            saved_subtitles.push(path);
+        }
+
+        let video = match self.remux.clone() {
+            Some(remux) => {
+                let ts = output.to_path_buf();
+                tokio::task::spawn_blocking(move || remux(ts))
+                    .await
+                    .map_err(|e| {
+                        EnmaError::misc_error(
+                            PROVIDER_PARSER,
+                            Some(format!("remux hook panicked: {e}")),
+                            None,
+                        )
+                    })??
+            }
+            None => output.to_path_buf(),
+        };
+
+        return Ok(Download {
+            video,
+            subtitles: saved_subtitles,
+            segments,
+            duration: media.duration(),
+        });
+    }
+
+    /// the media playlist at `url`, or the one of its preferred variant when
+    /// it's a master playlist
+    async fn fetch_media_playlist(&self, url: &str) -> EnmaResult<(String, MediaPlaylist)> {
+        let playlist = hls::get_playlist(&self.client, url, PROVIDER_PARSER).await?;
+
+        let master = match Playlist::parse(url, &playlist)? {
+            Playlist::Media(media) => return Ok((url.to_string(), media)),
+            Playlist::Master(master) => master,
+        };
+        let Some(variant) = master.select_variant(self.preference) else {
+            return Err(EnmaError::invalid_data_error(
+                PROVIDER_PARSER,
+                Some(String::from("master playlist lists no variant")),
+                None,
+            ));
+        };
+
+        let playlist = hls::get_playlist(&self.client, &variant.uri, PROVIDER_PARSER).await?;
+
+        return Ok((
+            variant.uri.clone(),
+            MediaPlaylist::parse(&variant.uri, &playlist)?,
+        ));
+    }
+
+    /// what to download, in order, fetching the keys the segments are
+    /// encrypted with along the way
+    async fn plan_parts(&self, media: &MediaPlaylist) -> EnmaResult<Vec<Part>> {
+        let mut keys: HashMap<String, [u8; 16]> = HashMap::new();
+        // where the previous byte range of each resource ended
+        let mut range_ends: HashMap<&str, u64> = HashMap::new();
+
+        let mut parts = Vec::with_capacity(media.segments.len() + 1);
+        if let Some(init_segment) = &media.init_segment {
+            parts.push(Part {
+                url: init_segment.clone(),
+                range: None,
+                key: None,
+            });
+        }
+
+        for segment in media.segments.iter() {
+            let key = match (&segment.key, segment.iv()) {
+                (Some(key), Some(iv)) => {
+                    let uri = match (&key.method, &key.uri) {
+                        (KeyMethod::Aes128, Some(uri)) => uri,
+                        _ => {
+                            return Err(EnmaError::invalid_data_error(
+                                PROVIDER_PARSER,
+                                Some(format!("unsupported encryption {:?}", key.method)),
+                                None,
+                            ))
+                        }
+                    };
+
+                    if !keys.contains_key(uri) {
+                        let key = self.fetch(uri, None).await?;
+                        let key = <[u8; 16]>::try_from(key.as_slice()).map_err(|_| {
+                            EnmaError::invalid_data_error(
+                                PROVIDER_PARSER,
+                                Some(format!("aes-128 key of {} bytes", key.len())),
+                                None,
+                            )
+                        })?;
+                        keys.insert(uri.clone(), key);
+                    }
+
+                    Some((keys[uri], iv))
+                }
+                _ => None,
+            };
+
+            let range = match segment.byte_range {
+                Some(range) => {
+                    let offset = range
+                        .offset
+                        .unwrap_or(range_ends.get(segment.uri.as_str()).copied().unwrap_or(0));
+                    let end = offset.checked_add(range.length).ok_or_else(|| {
+                        EnmaError::parsing_error(
+                            PROVIDER_PARSER,
+                            Some(format!("byte range of {} overflows", segment.uri)),
+                            None,
+                        )
+                    })?;
+                    range_ends.insert(&segment.uri, end);
+                    Some((offset, range.length))
+                }
+                None => None,
+            };
+
+            parts.push(Part {
+                url: segment.uri.clone(),
+                range,
+                key,
+            });
+        }
+
+        return Ok(parts);
+    }
+
+    async fn download_parts(&self, parts_dir: &Path, parts: Vec<Part>) -> EnmaResult<()> {
+        let total = parts.len();
+        let done = Arc::new(AtomicUsize::new(0));
+        let bytes = Arc::new(AtomicU64::new(0));
+
+        let mut pending = Vec::with_capacity(total);
+        for (index, part) in parts.into_iter().enumerate() {
+            let path = part_path(parts_dir, index);
+            match fs::try_exists(&path).await {
+                Ok(true) => {
+                    done.fetch_add(1, Ordering::Relaxed);
+                }
+                _ => pending.push((path, part)),
+            }
+        }
+
+        let resumed = done.load(Ordering::Relaxed);
+        let report = {
+            let on_progress = self.on_progress.clone();
+            let (done, bytes) = (done.clone(), bytes.clone());
+            move || {
+                if let Some(on_progress) = &on_progress {
+                    on_progress(Progress {
+                        segments_done: done.load(Ordering::Relaxed),
+                        segments_total: total,
+                        segments_resumed: resumed,
+                        bytes: bytes.load(Ordering::Relaxed),
+                    });
+                }
+            }
+        };
+        report();
+
+        let semaphore = Arc::new(Semaphore::new(self.concurrency));
+        let mut tasks = JoinSet::new();
+
+        for (path, part) in pending {
+            // acquiring before spawning keeps the segments in order and stops
+            // queueing new ones as soon as one of them fails for good
+            let permit = semaphore.clone().acquire_owned().await.map_err(|_| {
+                EnmaError::misc_error(PROVIDER_PARSER, Some(String::from("closed")), None)
+            })?;
+            while let Some(res) = tasks.try_join_next() {
+                join_result(res)?;
+            }
+
+            let downloader = self.clone();
+            let (done, bytes, report) = (done.clone(), bytes.clone(), report.clone());
+            tasks.spawn(async move {
+                let body = downloader.fetch_part(&part).await?;
+                bytes.fetch_add(body.len() as u64, Ordering::Relaxed);
+
+                // written to a temporary file first so that an interrupted
+                // write never passes for a saved part
+                let tmp = path.with_extension("tmp");
+                fs::write(&tmp, body).await.map_err(|e| io_error(&tmp, e))?;
+                fs::rename(&tmp, &path)
+                    .await
+                    .map_err(|e| io_error(&path, e))?;
+
+                done.fetch_add(1, Ordering::Relaxed);
+                report();
+                drop(permit);
+                return Ok(());
+            });
+        }
+
+        while let Some(res) = tasks.join_next().await {
+            join_result(res)?;
+        }
+
+        return Ok(());
+    }
+
+    async fn fetch_part(&self, part: &Part) -> EnmaResult<Vec<u8>> {
+        let headers = match part.range {
+            Some((offset, length)) => {
+                // ranges hold at least a byte and don't overflow, see `plan_parts`
+                let range = format!("bytes={}-{}", offset, offset + length - 1);
+                let mut headers = HeaderMap::new();
+                // the range only ever holds digits
+                headers.insert(RANGE, HeaderValue::from_str(&range).unwrap());
+                Some(headers)
+            }
+            None => None,
+        };
+
+        let body = self.fetch(&part.url, headers).await?;
+
+        return match part.key {
+            Some((key, iv)) => Aes128CbcDec::new(&key.into(), &iv.into())
+                .decrypt_padded_vec_mut::<Pkcs7>(&body)
+                .map_err(|_| {
+                    EnmaError::src_parse_error(
+                        PROVIDER_PARSER,
+                        Some(format!("failed to decrypt {}", part.url)),
+                        None,
+                    )
+                }),
+            None => Ok(body),
+        };
+    }
+
+    /// gets `url`, retrying on failures
+    async fn fetch(&self, url: &str, headers: Option<HeaderMap>) -> EnmaResult<Vec<u8>> {
+        let mut attempt = 0;
+        loop {
+            match self
+                .client
+                .get_bytes(url.to_string(), headers.clone(), PROVIDER_PARSER)
+                .await
+            {
+                Ok(body) => return Ok(body),
+                Err(e) if attempt >= self.retries => return Err(e),
+                Err(_) => {
+                    attempt += 1;
+                    tracing::debug!(url, attempt, "retrying");
+                    tokio::time::sleep(self.retry_delay * attempt).await;
+                }
+            }
+        }
+    }
+}
+
+/// creates the parts directory, emptying it when it holds the parts of
+/// another playlist
+async fn prepare_parts_dir(parts_dir: &Path, media_url: &str) -> EnmaResult<()> {
+    let source = parts_dir.join(SOURCE_FILE);
+    match fs::read_to_string(&source).await {
+        Ok(url) if url == media_url => return Ok(()),
+        Ok(_) => fs::remove_dir_all(parts_dir)
+            .await
+            .map_err(|e| io_error(parts_dir, e))?,
+        Err(_) => (),
+    }
+
+    fs::create_dir_all(parts_dir)
+        .await
+        .map_err(|e| io_error(parts_dir, e))?;
+    fs::write(&source, media_url)
+        .await
+        .map_err(|e| io_error(&source, e))?;
+
+    return Ok(());
+}
+
+/// concatenates the parts into `output`, returns how many there were
+async fn concat_parts(parts_dir: &Path, output: &Path) -> EnmaResult<usize> {
+    let mut file = fs::File::create(output)
+        .await
+        .map_err(|e| io_error(output, e))?;
+
+    let mut index = 0;
+    loop {
+        let path = part_path(parts_dir, index);
+        let mut part = match fs::File::open(&path).await {
+            Ok(part) => part,
+            Err(_) => break,
+        };
+        tokio::io::copy(&mut part, &mut file)
+            .await
+            .map_err(|e| io_error(output, e))?;
+        index += 1;
+    }
+
+    file.sync_all().await.map_err(|e| io_error(output, e))?;
+    return Ok(index);
+}
+
+fn part_path(parts_dir: &Path, index: usize) -> PathBuf {
+    return parts_dir.join(format!("{index:05}.ts"));
+}
+
+/// `<output stem>.<lang>.<ext>`, next to the output
+fn subtitle_path(output: &Path, track: &SubtitleTrack) -> PathBuf {
+    let stem = output.file_stem().unwrap_or_default().to_string_lossy();
+    let lang = track
+        .lang
+        .chars()
+        .map(|c| match c.is_alphanumeric() || c == '-' {
+            true => c,
+            false => '_',
+        })
+        .collect::<String>();
+    let ext = reqwest::Url::parse(&track.url)
+        .ok()
+        .and_then(|url| {
+            let ext = Path::new(url.path()).extension()?.to_str()?.to_string();
+            return Some(ext);
+        })
+        .unwrap_or(String::from("vtt"));
+
+    return output.with_file_name(format!("{stem}.{lang}.{ext}"));
+}
+
+fn join_result(res: Result<EnmaResult<()>, tokio::task::JoinError>) -> EnmaResult<()> {
+    return res.map_err(|e| {
+        EnmaError::misc_error(
+            PROVIDER_PARSER,
+            Some(format!("segment task failed: {e}")),
+            None,
+        )
+    })?;
+}
+
+fn io_error(path: &Path, err: std::io::Error) -> EnmaError {
+    return EnmaError::misc_error(
+        PROVIDER_PARSER,
+        Some(format!("{}: {err}", path.display())),
+        None,
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::{part_path, Downloader, Progress};
+    use crate::{
+        anime::{extractors::hls::MediaPlaylist, SubtitleTrack},
+        test_utils::{fixture_bytes, Routes, TestServer},
+        EnmaError,
+    };
+    use cbc::cipher::{block_padding::Pkcs7, BlockEncryptMut, KeyIvInit};
+    use std::{
+        path::{Path, PathBuf},
+        sync::{Arc, Mutex},
+        time::Duration,
+    };
+
+    const KEY: [u8; 16] = *b"enma-episode-key";
+    const VTT: &'static str = "WEBVTT\n\n00:00:01.000 --> 00:00:02.000\nhello\n";
+
+    fn plain_segment(sequence: u8) -> Vec<u8> {
+        return format!("segment-{sequence}|").repeat(100).into_bytes();
+    }
+
+    fn encrypt(plain: &[u8], iv: [u8; 16]) -> Vec<u8> {
+        return cbc::Encryptor::<aes::Aes128>::new(&KEY.into(), &iv.into())
+            .encrypt_padded_vec_mut::<Pkcs7>(plain);
+    }
+
+    fn expected_video() -> Vec<u8> {
+        return [plain_segment(3), plain_segment(4), b"six001six002".to_vec()].concat();
+    }
+
+    /// serves the download fixtures, `/ep/high/seg-4.ts` fails `flaky` times
+    /// before succeeding
+    async fn serve(flaky: usize) -> TestServer {
+        return routes(flaky).serve().await;
+    }
+
+    fn routes(flaky: usize) -> Routes {
+        let fixture = |name: &str| fixture_bytes(&format!("hls/download/{name}"));
+
+        let iv: [u8; 16] = core::array::from_fn(|i| 15 - i as u8);
+        return Routes::new()
+            .route("/ep/master.m3u8", fixture("master.m3u8"))
+            .route("/ep/high/media.m3u8", fixture("media.m3u8"))
+            .route("/ep/high/keys/episode.key", KEY)
+            .route("/ep/high/seg-3.ts", encrypt(&plain_segment(3), iv))
+            .route(
+                "/ep/high/seg-4.ts",
+                encrypt(&plain_segment(4), 4u128.to_be_bytes()),
+            )
+            .route("/ep/high/tail.ts", b"JUNKsix001six002JUNK".to_vec())
+            .route("/subs/eng-2.vtt", VTT)
+            .failing("/ep/high/seg-4.ts", flaky);
+    }
+
+    fn output_path(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("enma-download-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        return dir.join(name);
+    }
+
+    // cargo test --features download --lib -- anime::download::test --show-output
+    #[tokio::test]
+    async fn test_download() {
+        let server = serve(1).await;
+        let output = output_path("episode.ts");
+
+        let (downloader, mut progress) = Downloader::new(None)
+            .with_retries(2, Duration::from_millis(10))
+            .progress_stream();
+        let remuxed = Arc::new(Mutex::new(None));
+        let downloader = downloader.with_remux({
+            let remuxed = remuxed.clone();
+            move |ts| {
+                *remuxed.lock().unwrap() = Some(ts.clone());
+                return Ok(ts.with_extension("mp4"));
+            }
+        });
+
+        let subtitles = [SubtitleTrack {
+            url: server.url("/subs/eng-2.vtt"),
+            lang: String::from("English (CC)"),
+            default: true,
+        }];
+        let download = downloader
+            .download(&server.url("/ep/master.m3u8"), &output, &subtitles)
+            .await
+            .unwrap();
+        println!("{download:#?}");
+
+        assert_eq!(std::fs::read(&output).unwrap(), expected_video());
+        assert_eq!(*remuxed.lock().unwrap(), Some(output.clone()));
+        assert_eq!(download.video, output.with_extension("mp4"));
+        assert_eq!(download.segments, 4);
+        assert_eq!(download.duration, 12.0);
+
+        assert_eq!(
+            download.subtitles,
+            vec![output.with_file_name("episode.English__CC_.vtt")]
+        );
+        assert_eq!(
+            std::fs::read_to_string(&download.subtitles[0]).unwrap(),
+            VTT
+        );
+
+        // the flaky segment was retried, the key fetched once
+        assert_eq!(server.hits("/ep/high/seg-4.ts"), 2);
+        assert_eq!(server.hits("/ep/high/keys/episode.key"), 1);
+        assert_eq!(server.hits("/ep/low/media.m3u8"), 0);
+        assert!(!output.with_file_name("episode.ts.parts").exists());
+
+        let mut last = Progress::default();
+        while let Ok(p) = progress.try_recv() {
+            assert!(p.segments_done >= last.segments_done);
+            last = p;
+        }
+        assert_eq!(last.segments_done, 4);
+        assert_eq!(last.segments_total, 4);
+        assert_eq!(last.segments_resumed, 0);
+        assert_eq!(last.bytes, expected_video().len() as u64);
+    }
+
+    #[tokio::test]
+    async fn test_resume_download() {
+        let server = serve(1).await;
+        let output = output_path("resumed.ts");
+        let url = server.url("/ep/master.m3u8");
+        let parts_dir = output.with_file_name("resumed.ts.parts");
+
+        // no retries, the flaky segment interrupts the download
+        let err = Downloader::new(None)
+            .with_concurrency(1)
+            .with_retries(0, Duration::ZERO)
+            .download(&url, &output, &[])
+            .await
+            .unwrap_err();
+        println!("{err}");
+        assert!(!output.exists());
+        assert!(part_path(&parts_dir, 0).exists());
+        assert!(!part_path(&parts_dir, 1).exists());
+
+        let (downloader, mut progress) = Downloader::new(None).progress_stream();
+        let download = downloader.download(&url, &output, &[]).await.unwrap();
+
+        assert_eq!(std::fs::read(&output).unwrap(), expected_video());
+        assert_eq!(download.segments, 4);
+        assert_eq!(server.hits("/ep/high/seg-3.ts"), 1);
+        assert_eq!(server.hits("/ep/high/seg-4.ts"), 2);
+        assert!(progress.try_recv().unwrap().segments_resumed >= 1);
+        assert!(!parts_dir.exists());
+    }
+
+    #[tokio::test]
+    async fn test_parts_of_another_playlist_are_discarded() {
+        let server = serve(0).await;
+        let output = output_path("replaced.ts");
+        let parts_dir = output.with_file_name("replaced.ts.parts");
+
+        std::fs::create_dir_all(&parts_dir).unwrap();
+        std::fs::write(
+            parts_dir.join(super::SOURCE_FILE),
+            "http://elsewhere/media.m3u8",
+        )
+        .unwrap();
+        std::fs::write(part_path(&parts_dir, 0), "stale").unwrap();
+
+        Downloader::new(None)
+            .download(&server.url("/ep/master.m3u8"), &output, &[])
+            .await
+            .unwrap();
+        assert_eq!(std::fs::read(&output).unwrap(), expected_video());
+        assert!(!Path::new(&parts_dir).exists());
+    }
+
+    #[tokio::test]
+    async fn test_ignored_range_is_rejected() {
+        let server = routes(0).ignoring_ranges().serve().await;
+        let output = output_path("unranged.ts");
+
+        let err = Downloader::new(None)
+            .with_retries(0, Duration::ZERO)
+            .download(&server.url("/ep/master.m3u8"), &output, &[])
+            .await
+            .unwrap_err();
+        match err {
+            EnmaError::SrcFetchError { details } => assert_eq!(details.status, 200),
+            other => panic!("expected a fetch error, got {other:?}"),
+        }
+        assert!(!output.exists());
+    }
+
+    #[tokio::test]
+    async fn test_overflowing_byte_range() {
+        let media = MediaPlaylist::parse(
+            "https://cdn.example.com/media.m3u8",
+            "#EXTM3U\n#EXTINF:1,\n#EXT-X-BYTERANGE:18446744073709551615@0\nseg.ts\n#EXTINF:1,\n#EXT-X-BYTERANGE:1\nseg.ts",
+        )
+        .unwrap();
+
+        match Downloader::new(None).plan_parts(&media).await {
+            Err(EnmaError::ParsingError { details }) => {
+                assert!(details.message.contains("overflows"))
+            }
+            other => panic!("expected a parsing error, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use super::{
+    origin, parse_url, source_kind, ExtractFuture, ExtractedStream, Source, StreamExtractor,
+};
+use crate::{
+    anime::SubtitleTrack,
+    error::{EnmaError, EnmaResult},
+    utils::{EnmaClient, EnmaUtils},
+};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, REFERER},
+    Client, Url,
+};
+
+const PROVIDER_PARSER: &'static str = "extractors:filemoon";
+
+/// digits of the bases packed scripts encode their words in, up to 62
+const PACKER_DIGITS: &'static str =
+    "0123456789abcdefghijklmnopqrstuvwxyzABCDEFGHIJKLMNOPQRSTUVWXYZ";
+
+/// the player setup, packed as in `eval(function(p,a,c,k,e,d){..}('payload',base,count,'words'.split('|')))`
+static PACKED_SCRIPT_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(
+        r"(?s)eval\(function\(p,a,c,k,e,d\).*?\}\('(.*?)',\s*(\d+),\s*(\d+),\s*'(.*?)'\.split\('\|'\)",
+    )
+    .unwrap();
+});
+
+static WORD_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(r"\b\w+\b").unwrap();
+});
+
+static IFRAME_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(r#"(?i)<iframe\b[^>]*\bsrc\s*=\s*["']([^"']+)["']"#).unwrap();
+});
+
+static SOURCES_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(r#"sources\s*:\s*\[\s*\{\s*file\s*:\s*"([^"]+)""#).unwrap();
+});
+
+static TRACKS_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(r"tracks\s*:\s*\[(.*?)\]").unwrap();
+});
+
+static TRACK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(r"\{([^}]*)\}").unwrap();
+});
+
+static TRACK_FIELD_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(r#"["']?(file|label|kind|default)["']?\s*:\s*(?:"([^"]*)"|(\w+))"#).unwrap();
+});
+
+/// extractor of the filemoon embeds
+#[derive(Debug, Clone)]
+pub struct FileMoon {
+    client: Client,
+}
+
+impl Default for FileMoon {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl StreamExtractor for FileMoon {
+    fn servers(&self) -> &'static [&'static str] {
+        return &["filemoon"];
+    }
+
+    fn extract<'a>(&'a self, embed_url: &'a str) -> ExtractFuture<'a> {
+        return Box::pin(self.extract_stream(embed_url));
+    }
+}
+
+impl FileMoon {
+    pub fn new() -> Self {
+        return Self {
+            client: EnmaUtils::new_http_client(None),
+        };
+    }
+
+    #[tracing::instrument(name = "extractors:filemoon", skip(self))]
+    async fn extract_stream(&self, embed_url: &str) -> EnmaResult<ExtractedStream> {
+        let mut url = parse_url(embed_url, PROVIDER_PARSER)?;
+        let mut page = self
+            .client
+            .get_html(embed_url.to_string(), None, PROVIDER_PARSER)
+            .await?;
+
+        // the embed page often only frames the player page
+        if !PACKED_SCRIPT_REGEX.is_match(&page) {
+            let player_url = IFRAME_REGEX
+                .captures(&page)
+                .and_then(|c| url.join(&c[1]).ok());
+
+            if let Some(player_url) = player_url {
+                let mut headers = HeaderMap::new();
+                if let Ok(referer) = HeaderValue::from_str(embed_url) {
+                    headers.insert(REFERER, referer);
+                }
+                page = self
+                    .client
+                    .get_html(player_url.to_string(), Some(headers), PROVIDER_PARSER)
+                    .await?;
+                url = player_url;
+            }
+        }
+
+        return Self::parse_player_page(&url, &page);
+    }
+
+    fn parse_player_page(page_url: &Url, page: &str) -> EnmaResult<ExtractedStream> {
+        let Some(script) = unpack(page) else {
+            return Err(EnmaError::src_parse_error(
+                PROVIDER_PARSER,
+                Some(String::from("packed player script not found in the page")),
+                None,
+            ));
+        };
+        let Some(file) = SOURCES_REGEX.captures(&script).map(|c| c[1].to_string()) else {
+            return Err(EnmaError::src_parse_error(
+                PROVIDER_PARSER,
+                Some(String::from("no source in the player script")),
+                None,
+            ));
+        };
+
+        let mut res = ExtractedStream {
+            sources: vec![Source {
+                kind: source_kind(None, &file),
+                url: file,
+                quality: None,
+            }],
+            ..Default::default()
+        };
+
+        let tracks = TRACKS_REGEX.captures(&script);
+        for track in tracks
+            .iter()
+            .flat_map(|c| TRACK_REGEX.captures_iter(c.get(1).unwrap().as_str()))
+        {
+            let field = |name: &str| {
+                TRACK_FIELD_REGEX
+                    .captures_iter(&track[1])
+                    .find(|c| &c[1] == name)
+                    .and_then(|c| c.get(2).or(c.get(3)))
+                    .map(|m| m.as_str().to_string())
+            };
+
+            let Some(url) = field("file").and_then(|file| page_url.join(&file).ok()) else {
+                continue;
+            };
+            match field("kind").as_deref() {
+                Some("thumbnails") => res.thumbnails = Some(url.to_string()),
+                _ => res.subtitles.push(SubtitleTrack {
+                    url: url.to_string(),
+                    lang: field("label").unwrap_or_default(),
+                    default: field("default").as_deref() == Some("true"),
+                }),
+            }
+        }
+
+        res.headers
+            .insert(String::from("Referer"), origin(page_url));
+
+        return Ok(res);
+    }
+}
+
+/// unpacks the first packed script of `page`, every word of the payload is
+/// the index, in the script's base, of the word it stands for
+fn unpack(page: &str) -> Option<String> {
+    let captures = PACKED_SCRIPT_REGEX.captures(page)?;
+    let payload = captures[1].replace("\\'", "'").replace("\\\\", "\\");
+    let base = captures[2]
+        .parse::<usize>()
+        .ok()
+        .filter(|b| (2..=62).contains(b))?;
+    let words = captures[4].split('|').collect::<Vec<_>>();
+
+    let decode = |word: &str| {
+        return word.chars().try_fold(0usize, |n, c| {
+            let digit = PACKER_DIGITS[..base].find(c)?;
+            return n.checked_mul(base)?.checked_add(digit);
+        });
+    };
+
+    let res = WORD_REGEX.replace_all(&payload, |c: &Captures| {
+        let word = &c[0];
+        return match decode(word).and_then(|i| words.get(i)) {
+            Some(replacement) if !replacement.is_empty() => replacement.to_string(),
+            _ => word.to_string(),
+        };
+    });
+
+    return Some(res.into_owned());
+}
+
+#[cfg(test)]
+mod test {
+    use super::{unpack, FileMoon};
+    use crate::{anime::extractors::SourceKind, test_utils::fixture};
+    use reqwest::Url;
+
+    // cargo test --features extractors --lib -- anime::extractors::filemoon::test --show-output
+    #[test]
+    fn test_unpack() {
+        let script = unpack(&fixture("extractors/filemoon_player.html")).unwrap();
+        println!("{script}");
+
+        assert!(script.starts_with(r#"jwplayer("vplayer").setup({sources:[{file:"https://"#));
+        assert!(script.ends_with("playbackRateControls:true});"));
+        assert_eq!(unpack("<html></html>"), None);
+    }
+
+    #[test]
+    fn test_parse_player_page() {
+        let url = Url::parse("https://filemoon.example/e2/abc").unwrap();
+        let stream =
+            FileMoon::parse_player_page(&url, &fixture("extractors/filemoon_player.html")).unwrap();
+        println!("{stream:#?}");
+
+        assert_eq!(
+            stream.sources[0].url,
+            "https://be2719.rcr22.ams01.example.com/hls2/01/05000/abc_h/master.m3u8?t=tokenXYZ&s=1700000000"
+        );
+        assert_eq!(stream.sources[0].kind, SourceKind::Hls);
+
+        assert_eq!(stream.subtitles.len(), 2);
+        assert_eq!(stream.subtitles[0].lang, "English");
+        assert_eq!(
+            stream.subtitles[1].url,
+            "https://filemoon.example/subs/abc_spa.vtt"
+        );
+        assert!(stream.subtitles[0].default && !stream.subtitles[1].default);
+        assert_eq!(
+            stream.thumbnails.as_deref(),
+            Some("https://filemoon.example/dl?op=get_slides&length=1420&url=https://filemoon.example/abc0000.jpg")
+        );
+        assert_eq!(stream.headers["Referer"], "https://filemoon.example/");
+
+        assert!(
+            FileMoon::parse_player_page(&url, &fixture("extractors/filemoon_embed.html")).is_err()
+        );
+    }
+}
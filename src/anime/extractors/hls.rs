@@ -0,0 +1,875 @@
+use crate::{
+    error::{EnmaError, EnmaResult},
+    utils::{EnmaClient, EnmaUtils},
+};
+use reqwest::{header::HeaderMap, Client, Url};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+const PROVIDER_PARSER: &'static str = "extractors:hls";
+
+/// either kind of HLS playlist, a master playlist lists the renditions of a
+/// stream while a media playlist lists the segments of one of them
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Playlist {
+    Master(MasterPlaylist),
+    Media(MediaPlaylist),
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MasterPlaylist {
+    pub variants: Vec<Variant>,
+    /// alternative audio, subtitle and video renditions the variants refer
+    /// to by their group id
+    pub renditions: Vec<Rendition>,
+    pub independent_segments: bool,
+}
+
+/// one `#EXT-X-STREAM-INF` entry of a master playlist
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Variant {
+    /// absolute url of the variant's media playlist
+    pub uri: String,
+    /// peak bitrate, in bits per second
+    pub bandwidth: u64,
+    pub average_bandwidth: Option<u64>,
+    pub resolution: Option<Resolution>,
+    pub frame_rate: Option<f32>,
+    pub codecs: Vec<String>,
+    /// group id of the audio renditions to play it with
+    pub audio: Option<String>,
+    /// group id of the subtitle renditions to play it with
+    pub subtitles: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Resolution {
+    pub width: u32,
+    pub height: u32,
+}
+
+/// one `#EXT-X-MEDIA` entry of a master playlist
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Rendition {
+    pub media_type: MediaType,
+    pub group_id: String,
+    pub name: String,
+    pub language: Option<String>,
+    /// absolute url of the rendition's media playlist, missing when the
+    /// rendition is muxed into the variants
+    pub uri: Option<String>,
+    pub default: bool,
+    pub autoselect: bool,
+    pub forced: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum MediaType {
+    Audio,
+    Video,
+    Subtitles,
+    ClosedCaptions,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct MediaPlaylist {
+    /// upper bound of the segments' duration, in seconds
+    pub target_duration: u64,
+    /// sequence number of the first segment
+    pub media_sequence: u64,
+    /// `VOD` or `EVENT`
+    pub playlist_type: Option<String>,
+    /// absolute url of the `#EXT-X-MAP` initialization segment
+    pub init_segment: Option<String>,
+    pub segments: Vec<Segment>,
+    /// whether `#EXT-X-ENDLIST` closed the playlist, i.e. no segment will be
+    /// appended to it anymore
+    pub end_list: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Segment {
+    /// absolute url of the segment
+    pub uri: String,
+    /// in seconds
+    pub duration: f64,
+    pub title: Option<String>,
+    pub sequence: u64,
+    /// key the segment is encrypted with, `None` for clear segments
+    pub key: Option<Key>,
+    pub byte_range: Option<ByteRange>,
+    /// whether the encoding changes from the previous segment on
+    pub discontinuity: bool,
+}
+
+impl Segment {
+    /// iv to decrypt the segment with, the key's explicit iv or else the
+    /// segment's sequence number as the spec mandates
+    pub fn iv(&self) -> Option<[u8; 16]> {
+        return self
+            .key
+            .as_ref()
+            .map(|key| key.iv.unwrap_or((self.sequence as u128).to_be_bytes()));
+    }
+}
+
+/// one `#EXT-X-KEY` entry of a media playlist
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Key {
+    pub method: KeyMethod,
+    /// absolute url of the key
+    pub uri: Option<String>,
+    pub iv: Option<[u8; 16]>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub enum KeyMethod {
+    #[serde(rename = "AES-128")]
+    Aes128,
+    #[serde(rename = "SAMPLE-AES")]
+    SampleAes,
+    #[serde(untagged)]
+    Other(String),
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ByteRange {
+    pub length: u64,
+    /// defaults to right after the previous range of the same resource
+    pub offset: Option<u64>,
+}
+
+/// which variant [`MasterPlaylist::select_variant`] picks
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum VariantPreference {
+    /// highest resolution, then highest bandwidth
+    Best,
+    /// lowest resolution, then lowest bandwidth
+    Worst,
+    /// best variant at most this many pixels tall, the smallest one when
+    /// they're all taller
+    MaxHeight(u32),
+    /// variant whose bandwidth is the closest to this many bits per second
+    ClosestBandwidth(u64),
+}
+
+impl Playlist {
+    /// parses a playlist fetched from `url`, relative uris are resolved
+    /// against it
+    pub fn parse(url: &str, playlist: &str) -> EnmaResult<Self> {
+        let is_master = playlist
+            .lines()
+            .any(|line| line.trim_start().starts_with("#EXT-X-STREAM-INF"));
+
+        return match is_master {
+            true => MasterPlaylist::parse(url, playlist).map(Playlist::Master),
+            false => MediaPlaylist::parse(url, playlist).map(Playlist::Media),
+        };
+    }
+}
+
+impl MasterPlaylist {
+    /// parses a master playlist fetched from `url`, relative uris are
+    /// resolved against it
+    pub fn parse(url: &str, playlist: &str) -> EnmaResult<Self> {
+        let base = base_url(url)?;
+        let mut res = MasterPlaylist::default();
+        let mut pending: Option<Variant> = None;
+
+        for line in lines(playlist)? {
+            match line {
+                Line::Tag("EXT-X-INDEPENDENT-SEGMENTS", _) => res.independent_segments = true,
+                Line::Tag("EXT-X-MEDIA", Some(attributes)) => {
+                    let attributes = parse_attributes(attributes);
+                    let media_type = match attributes.get("TYPE").map(|s| s.as_str()) {
+                        Some("AUDIO") => MediaType::Audio,
+                        Some("VIDEO") => MediaType::Video,
+                        Some("SUBTITLES") => MediaType::Subtitles,
+                        Some("CLOSED-CAPTIONS") => MediaType::ClosedCaptions,
+                        _ => continue,
+                    };
+
+                    res.renditions.push(Rendition {
+                        media_type,
+                        group_id: attributes.get("GROUP-ID").cloned().unwrap_or_default(),
+                        name: attributes.get("NAME").cloned().unwrap_or_default(),
+                        language: attributes.get("LANGUAGE").cloned(),
+                        uri: match attributes.get("URI") {
+                            Some(uri) => Some(resolve(&base, uri)?),
+                            None => None,
+                        },
+                        default: is_yes(attributes.get("DEFAULT")),
+                        autoselect: is_yes(attributes.get("AUTOSELECT")),
+                        forced: is_yes(attributes.get("FORCED")),
+                    });
+                }
+                Line::Tag("EXT-X-STREAM-INF", attributes) => {
+                    let attributes = parse_attributes(attributes.unwrap_or_default());
+
+                    pending = Some(Variant {
+                        bandwidth: attributes
+                            .get("BANDWIDTH")
+                            .and_then(|s| s.parse().ok())
+                            .unwrap_or_default(),
+                        average_bandwidth: attributes
+                            .get("AVERAGE-BANDWIDTH")
+                            .and_then(|s| s.parse().ok()),
+                        resolution: attributes.get("RESOLUTION").and_then(|s| {
+                            let (width, height) = s.split_once(['x', 'X'])?;
+                            return Some(Resolution {
+                                width: width.trim().parse().ok()?,
+                                height: height.trim().parse().ok()?,
+                            });
+                        }),
+                        frame_rate: attributes.get("FRAME-RATE").and_then(|s| s.parse().ok()),
+                        codecs: attributes
+                            .get("CODECS")
+                            .map(|s| {
+                                s.split(',')
+                                    .map(|s| s.trim().to_string())
+                                    .filter(|s| !s.is_empty())
+                                    .collect()
+                            })
+                            .unwrap_or_default(),
+                        audio: attributes.get("AUDIO").cloned(),
+                        subtitles: attributes.get("SUBTITLES").cloned(),
+                        ..Default::default()
+                    });
+                }
+                Line::Uri(uri) => {
+                    if let Some(mut variant) = pending.take() {
+                        variant.uri = resolve(&base, uri)?;
+                        res.variants.push(variant);
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        if res.variants.is_empty() {
+            return Err(EnmaError::parsing_error(
+                PROVIDER_PARSER,
+                Some(String::from("master playlist lists no variant")),
+                None,
+            ));
+        }
+
+        return Ok(res);
+    }
+
+    /// picks a variant by `preference`, `None` only when there's none
+    pub fn select_variant(&self, preference: VariantPreference) -> Option<&Variant> {
+        // variants without a resolution, e.g. audio only ones, rank lowest
+        let rank = |v: &&Variant| {
+            (
+                v.resolution.map(|r| r.height).unwrap_or_default(),
+                v.bandwidth,
+            )
+        };
+
+        return match preference {
+            VariantPreference::Best => self.variants.iter().max_by_key(rank),
+            VariantPreference::Worst => self.variants.iter().min_by_key(rank),
+            VariantPreference::MaxHeight(height) => self
+                .variants
+                .iter()
+                .filter(|v| v.resolution.is_some_and(|r| r.height <= height))
+                .max_by_key(rank)
+                .or_else(|| self.select_variant(VariantPreference::Worst)),
+            VariantPreference::ClosestBandwidth(bandwidth) => self
+                .variants
+                .iter()
+                .min_by_key(|v| v.bandwidth.abs_diff(bandwidth)),
+        };
+    }
+
+    /// renditions of the `group_id` group, e.g. the ones of a variant's
+    /// [`Variant::audio`]
+    pub fn renditions<'a>(&'a self, group_id: &'a str) -> impl Iterator<Item = &'a Rendition> {
+        return self
+            .renditions
+            .iter()
+            .filter(move |r| r.group_id == group_id);
+    }
+}
+
+impl MediaPlaylist {
+    /// parses a media playlist fetched from `url`, relative uris are
+    /// resolved against it
+    pub fn parse(url: &str, playlist: &str) -> EnmaResult<Self> {
+        let base = base_url(url)?;
+        let mut res = MediaPlaylist::default();
+
+        let mut key: Option<Key> = None;
+        let mut pending = Segment::default();
+        let mut has_pending = false;
+
+        for line in lines(playlist)? {
+            match line {
+                Line::Tag("EXT-X-TARGETDURATION", Some(value)) => {
+                    res.target_duration = value.trim().parse().unwrap_or_default();
+                }
+                Line::Tag("EXT-X-MEDIA-SEQUENCE", Some(value)) => {
+                    res.media_sequence = value.trim().parse().unwrap_or_default();
+                }
+                Line::Tag("EXT-X-PLAYLIST-TYPE", Some(value)) => {
+                    res.playlist_type = Some(value.trim().to_string());
+                }
+                Line::Tag("EXT-X-ENDLIST", _) => res.end_list = true,
+                Line::Tag("EXT-X-MAP", Some(attributes)) => {
+                    if let Some(uri) = parse_attributes(attributes).get("URI") {
+                        res.init_segment = Some(resolve(&base, uri)?);
+                    }
+                }
+                Line::Tag("EXT-X-KEY", Some(attributes)) => {
+                    let attributes = parse_attributes(attributes);
+                    let method = match attributes.get("METHOD").map(|s| s.as_str()) {
+                        None | Some("NONE") => {
+                            key = None;
+                            continue;
+                        }
+                        Some("AES-128") => KeyMethod::Aes128,
+                        Some("SAMPLE-AES") => KeyMethod::SampleAes,
+                        Some(other) => KeyMethod::Other(other.to_string()),
+                    };
+
+                    key = Some(Key {
+                        method,
+                        uri: match attributes.get("URI") {
+                            Some(uri) => Some(resolve(&base, uri)?),
+                            None => None,
+                        },
+                        iv: match attributes.get("IV") {
+                            Some(iv) => Some(parse_iv(iv)?),
+                            None => None,
+                        },
+                    });
+                }
+                Line::Tag("EXTINF", Some(value)) => {
+                    let (duration, title) = value.split_once(',').unwrap_or((value, ""));
+                    pending.duration = duration.trim().parse().unwrap_or_default();
+                    pending.title = Some(title.trim().to_string()).filter(|s| !s.is_empty());
+                    has_pending = true;
+                }
+                Line::Tag("EXT-X-BYTERANGE", Some(value)) => {
+                    pending.byte_range = Some(parse_byte_range(value)?);
+                }
+                Line::Tag("EXT-X-DISCONTINUITY", _) => pending.discontinuity = true,
+                Line::Uri(uri) if has_pending => {
+                    let mut segment = std::mem::take(&mut pending);
+                    segment.uri = resolve(&base, uri)?;
+                    segment.sequence = res
+                        .media_sequence
+                        .checked_add(res.segments.len() as u64)
+                        .ok_or_else(|| {
+                            EnmaError::parsing_error(
+                                PROVIDER_PARSER,
+                                Some(String::from("media sequence number overflows")),
+                                None,
+                            )
+                        })?;
+                    segment.key = key.clone();
+
+                    res.segments.push(segment);
+                    has_pending = false;
+                }
+                _ => (),
+            }
+        }
+
+        return Ok(res);
+    }
+
+    /// summed duration of the segments, in seconds
+    pub fn duration(&self) -> f64 {
+        return self.segments.iter().map(|s| s.duration).sum();
+    }
+}
+
+/// fetches HLS playlists
+#[derive(Debug, Clone)]
+pub struct HlsClient {
+    client: Client,
+}
+
+impl Default for HlsClient {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl HlsClient {
+    pub fn new() -> Self {
+        return Self {
+            client: EnmaUtils::new_http_client(None),
+        };
+    }
+
+    /// same as [`HlsClient::new`] but every request carries `headers`, e.g.
+    /// the `Referer` the stream's server expects
+    pub fn with_headers(headers: HeaderMap) -> Self {
+        return Self {
+            client: EnmaUtils::new_http_client(Some(headers)),
+        };
+    }
+
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::extractors::hls::{HlsClient, Playlist, VariantPreference};
+    ///     let hls = HlsClient::new();
+    ///
+    ///     let url = "https://cdn.example.com/hls/episode/master.m3u8";
+    ///
+    ///     match hls.fetch(url).await {
+    ///         Ok(Playlist::Master(master)) => {
+    ///             println!("{:#?}", master.select_variant(VariantPreference::MaxHeight(720)))
+    ///         }
+    ///         Ok(Playlist::Media(media)) => println!("{} segments", media.segments.len()),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "extractors:hls:fetch", skip(self))]
+    pub async fn fetch(&self, url: &str) -> EnmaResult<Playlist> {
+        let playlist = get_playlist(&self.client, url, PROVIDER_PARSER).await?;
+
+        return Playlist::parse(url, &playlist);
+    }
+
+    /// fetches the media playlist of `variant`
+    pub async fn fetch_media(&self, variant: &Variant) -> EnmaResult<MediaPlaylist> {
+        return match self.fetch(&variant.uri).await? {
+            Playlist::Media(media) => Ok(media),
+            Playlist::Master(_) => Err(EnmaError::invalid_data_error(
+                PROVIDER_PARSER,
+                Some(String::from(
+                    "expected a media playlist, got a master playlist",
+                )),
+                None,
+            )),
+        };
+    }
+}
+
+/// text of the playlist at `url`, an error status is reported as such
+/// instead of as a body that isn't a playlist
+pub(crate) async fn get_playlist(
+    client: &Client,
+    url: &str,
+    provider_parser: &'static str,
+) -> EnmaResult<String> {
+    let playlist = client
+        .get_bytes(url.to_string(), None, provider_parser)
+        .await?;
+
+    return String::from_utf8(playlist).map_err(|_| {
+        EnmaError::parsing_error(
+            provider_parser,
+            Some(String::from("playlist isn't valid utf-8")),
+            None,
+        )
+    });
+}
+
+enum Line<'a> {
+    /// tag name without its `#`, and its value
+    Tag(&'a str, Option<&'a str>),
+    Uri(&'a str),
+}
+
+fn lines(playlist: &str) -> EnmaResult<impl Iterator<Item = Line<'_>>> {
+    let mut lines = playlist
+        .lines()
+        .map(|line| line.trim())
+        .filter(|line| !line.is_empty());
+
+    if lines.next().map(|line| line.trim_start_matches('\u{feff}')) != Some("#EXTM3U") {
+        return Err(EnmaError::parsing_error(
+            PROVIDER_PARSER,
+            Some(String::from("playlist doesn't start with #EXTM3U")),
+            None,
+        ));
+    }
+
+    return Ok(lines.filter_map(|line| match line.strip_prefix('#') {
+        Some(tag) if tag.starts_with("EXT") => Some(match tag.split_once(':') {
+            Some((name, value)) => Line::Tag(name, Some(value)),
+            None => Line::Tag(tag, None),
+        }),
+        // comments
+        Some(_) => None,
+        None => Some(Line::Uri(line)),
+    }));
+}
+
+/// parses a `KEY=VALUE,KEY="quoted, value"` attribute list
+fn parse_attributes(attributes: &str) -> HashMap<String, String> {
+    let mut res = HashMap::new();
+    let mut rest = attributes.trim();
+
+    while let Some((key, after)) = rest.split_once('=') {
+        let (value, after) = match after.strip_prefix('"') {
+            Some(quoted) => quoted.split_once('"').unwrap_or((quoted, "")),
+            None => after.split_once(',').unwrap_or((after, "")),
+        };
+
+        res.insert(key.trim().to_uppercase(), value.to_string());
+        rest = after.trim_start_matches([',', ' ']);
+    }
+
+    return res;
+}
+
+/// `<length>[@<offset>]`, a range has to hold at least a byte and end
+/// within a `u64`
+fn parse_byte_range(value: &str) -> EnmaResult<ByteRange> {
+    let invalid = || {
+        EnmaError::parsing_error(
+            PROVIDER_PARSER,
+            Some(format!("invalid EXT-X-BYTERANGE {value:?}")),
+            None,
+        )
+    };
+
+    let (length, offset) = match value.split_once('@') {
+        Some((length, offset)) => (length, Some(offset)),
+        None => (value, None),
+    };
+    let length = length
+        .trim()
+        .parse::<u64>()
+        .ok()
+        .filter(|length| *length > 0)
+        .ok_or_else(invalid)?;
+    let offset = match offset {
+        Some(offset) => Some(offset.trim().parse::<u64>().map_err(|_| invalid())?),
+        None => None,
+    };
+
+    if offset.is_some_and(|offset| offset.checked_add(length).is_none()) {
+        return Err(invalid());
+    }
+
+    return Ok(ByteRange { length, offset });
+}
+
+/// a `0x` prefixed hexadecimal of at most 128 bits, shorter ones are left
+/// padded with zeros
+fn parse_iv(iv: &str) -> EnmaResult<[u8; 16]> {
+    let iv = iv.trim();
+    let hex = iv
+        .strip_prefix("0x")
+        .or(iv.strip_prefix("0X"))
+        .filter(|hex| (1..=32).contains(&hex.len()))
+        .filter(|hex| hex.chars().all(|c| c.is_ascii_hexdigit()));
+
+    return hex
+        .and_then(|hex| u128::from_str_radix(hex, 16).ok())
+        .map(u128::to_be_bytes)
+        .ok_or_else(|| {
+            EnmaError::parsing_error(
+                PROVIDER_PARSER,
+                Some(format!("invalid EXT-X-KEY IV {iv:?}")),
+                None,
+            )
+        });
+}
+
+fn is_yes(value: Option<&String>) -> bool {
+    return value.is_some_and(|s| s == "YES");
+}
+
+fn base_url(url: &str) -> EnmaResult<Url> {
+    return Url::parse(url).map_err(|_| {
+        EnmaError::invalid_data_error(
+            PROVIDER_PARSER,
+            Some(format!("invalid playlist url {url:?}")),
+            None,
+        )
+    });
+}
+
+fn resolve(base: &Url, uri: &str) -> EnmaResult<String> {
+    return base.join(uri).map(|url| url.to_string()).map_err(|_| {
+        EnmaError::parsing_error(PROVIDER_PARSER, Some(format!("invalid uri {uri:?}")), None)
+    });
+}
+
+#[cfg(test)]
+mod test {
+    use super::{
+        ByteRange, HlsClient, KeyMethod, MasterPlaylist, MediaPlaylist, MediaType, Playlist,
+        Resolution, VariantPreference,
+    };
+    use crate::{
+        test_utils::{fixture, Routes},
+        EnmaError,
+    };
+    use reqwest::StatusCode;
+
+    const MASTER_URL: &'static str = "https://cdn.example.com/hls/episode/master.m3u8";
+    const MEDIA_URL: &'static str = "https://cdn.example.com/hls/episode/index-f2-v1-a1.m3u8";
+
+    // cargo test --lib -- anime::extractors::hls::test --show-output
+    #[test]
+    fn test_parse_master_playlist() {
+        let master = MasterPlaylist::parse(MASTER_URL, &fixture("hls/master.m3u8")).unwrap();
+        println!("{master:#?}");
+
+        assert!(master.independent_segments);
+        assert_eq!(master.variants.len(), 3);
+
+        let best = &master.variants[0];
+        assert_eq!(
+            best.uri,
+            "https://cdn.example.com/hls/episode/index-f1-v1-a1.m3u8"
+        );
+        assert_eq!(best.bandwidth, 2487712);
+        assert_eq!(best.average_bandwidth, Some(2100000));
+        assert_eq!(
+            best.resolution,
+            Some(Resolution {
+                width: 1920,
+                height: 1080
+            })
+        );
+        assert_eq!(best.frame_rate, Some(23.976));
+        assert_eq!(best.codecs, vec!["avc1.640028", "mp4a.40.2"]);
+        assert_eq!(best.audio.as_deref(), Some("aud"));
+        assert_eq!(best.subtitles.as_deref(), Some("subs"));
+
+        // absolute uris are kept as is
+        assert_eq!(
+            master.variants[2].uri,
+            "https://cdn2.example.com/hls/episode/index-f3-v1-a1.m3u8"
+        );
+
+        let audio = master.renditions("aud").collect::<Vec<_>>();
+        assert_eq!(audio.len(), 2);
+        assert_eq!(audio[0].media_type, MediaType::Audio);
+        assert_eq!(audio[0].language.as_deref(), Some("ja"));
+        assert!(audio[0].default && !audio[1].default);
+        assert_eq!(
+            audio[0].uri.as_deref(),
+            Some("https://cdn.example.com/hls/episode/audio/ja/index.m3u8")
+        );
+
+        let subtitles = master.renditions("subs").next().unwrap();
+        assert_eq!(subtitles.media_type, MediaType::Subtitles);
+        assert_eq!(
+            subtitles.uri.as_deref(),
+            Some("https://cdn.example.com/subs/en/index.m3u8")
+        );
+    }
+
+    #[test]
+    fn test_select_variant() {
+        let master = MasterPlaylist::parse(MASTER_URL, &fixture("hls/master.m3u8")).unwrap();
+        let height = |preference| {
+            master
+                .select_variant(preference)
+                .and_then(|v| v.resolution)
+                .map(|r| r.height)
+        };
+
+        assert_eq!(height(VariantPreference::Best), Some(1080));
+        assert_eq!(height(VariantPreference::Worst), Some(360));
+        assert_eq!(height(VariantPreference::MaxHeight(720)), Some(720));
+        assert_eq!(height(VariantPreference::MaxHeight(1000)), Some(720));
+        // every variant is taller, falls back to the smallest
+        assert_eq!(height(VariantPreference::MaxHeight(240)), Some(360));
+        assert_eq!(
+            height(VariantPreference::ClosestBandwidth(1_000_000)),
+            Some(720)
+        );
+        assert_eq!(height(VariantPreference::ClosestBandwidth(0)), Some(360));
+
+        assert_eq!(
+            MasterPlaylist::default().select_variant(VariantPreference::Best),
+            None
+        );
+    }
+
+    #[test]
+    fn test_parse_media_playlist() {
+        let media = MediaPlaylist::parse(MEDIA_URL, &fixture("hls/media.m3u8")).unwrap();
+        println!("{media:#?}");
+
+        assert_eq!(media.target_duration, 6);
+        assert_eq!(media.media_sequence, 7);
+        assert_eq!(media.playlist_type.as_deref(), Some("VOD"));
+        assert!(media.end_list);
+        assert_eq!(media.segments.len(), 4);
+        assert!((media.duration() - 16.183).abs() < 1e-9);
+
+        let first = &media.segments[0];
+        assert_eq!(
+            first.uri,
+            "https://cdn.example.com/hls/episode/seg-7-v1-a1.ts"
+        );
+        assert_eq!(first.sequence, 7);
+        assert_eq!(first.duration, 5.005);
+        let key = first.key.as_ref().unwrap();
+        assert_eq!(key.method, KeyMethod::Aes128);
+        assert_eq!(
+            key.uri.as_deref(),
+            Some("https://cdn.example.com/hls/keys/episode.key")
+        );
+        assert_eq!(first.iv(), Some(core::array::from_fn(|i| i as u8)));
+
+        // the rotated key has no iv, the sequence number stands in for it
+        let recap = &media.segments[2];
+        assert_eq!(recap.title.as_deref(), Some("recap"));
+        assert!(recap.discontinuity && !media.segments[1].discontinuity);
+        assert_eq!(
+            recap.key.as_ref().and_then(|k| k.uri.as_deref()),
+            Some("https://keys.example.com/rotated.key")
+        );
+        assert_eq!(recap.iv(), Some(9u128.to_be_bytes()));
+
+        let tail = &media.segments[3];
+        assert_eq!(tail.uri, "https://cdn.example.com/hls/episode/tail.ts");
+        assert_eq!(tail.key, None);
+        assert_eq!(tail.iv(), None);
+        assert_eq!(
+            tail.byte_range,
+            Some(ByteRange {
+                length: 75232,
+                offset: Some(0)
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_invalid_playlists() {
+        assert!(Playlist::parse(MASTER_URL, "").is_err());
+        assert!(Playlist::parse(MASTER_URL, "<html></html>").is_err());
+        assert!(Playlist::parse("master.m3u8", &fixture("hls/master.m3u8")).is_err());
+        // a stream inf without any uri after it
+        assert!(
+            MasterPlaylist::parse(MASTER_URL, "#EXTM3U\n#EXT-X-STREAM-INF:BANDWIDTH=1").is_err()
+        );
+        // the second segment's sequence number doesn't fit a u64
+        assert!(MediaPlaylist::parse(
+            MEDIA_URL,
+            "#EXTM3U\n#EXT-X-MEDIA-SEQUENCE:18446744073709551615\n#EXTINF:1,\na.ts\n#EXTINF:1,\nb.ts"
+        )
+        .is_err());
+        assert_eq!(
+            Playlist::parse(MEDIA_URL, "#EXTM3U\n#EXTINF:oops\n#EXT-X-KEY:IV=0x1").unwrap(),
+            Playlist::Media(MediaPlaylist::default())
+        );
+    }
+
+    #[test]
+    fn test_parse_key_iv() {
+        let media = |iv: &str| {
+            let playlist = format!(
+                "#EXTM3U\n#EXT-X-KEY:METHOD=AES-128,URI=\"k.key\",IV={iv}\n#EXTINF:1,\nseg.ts"
+            );
+            MediaPlaylist::parse(MEDIA_URL, &playlist)
+        };
+
+        let iv = |iv: &str| media(iv).unwrap().segments[0].iv();
+        assert_eq!(iv("0x1"), Some(1u128.to_be_bytes()));
+        assert_eq!(iv("0XaBc"), Some(0xabcu128.to_be_bytes()));
+        assert_eq!(
+            iv("0x000102030405060708090a0b0c0d0e0f"),
+            Some(core::array::from_fn(|i| i as u8))
+        );
+
+        for invalid in [
+            "1",
+            "0x",
+            "0xzz",
+            "0x+1",
+            "0x000102030405060708090a0b0c0d0e0f00",
+        ] {
+            match media(invalid) {
+                Err(EnmaError::ParsingError { details }) => {
+                    assert!(details.message.contains("IV"), "{details:?}")
+                }
+                other => panic!("expected {invalid} to be rejected, got {other:?}"),
+            }
+        }
+    }
+
+    #[test]
+    fn test_parse_byte_range() {
+        let range = |value: &str| super::parse_byte_range(value).ok();
+
+        assert_eq!(
+            range("75232@0"),
+            Some(ByteRange {
+                length: 75232,
+                offset: Some(0)
+            })
+        );
+        assert_eq!(
+            range(" 6 "),
+            Some(ByteRange {
+                length: 6,
+                offset: None
+            })
+        );
+        for invalid in ["0@0", "0", "", "-1", "6@", "6@x", "2@18446744073709551615"] {
+            assert_eq!(range(invalid), None, "{invalid}");
+        }
+        assert!(MediaPlaylist::parse(
+            MEDIA_URL,
+            "#EXTM3U\n#EXTINF:1,\n#EXT-X-BYTERANGE:0@0\nseg.ts"
+        )
+        .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_fetch_error_status() {
+        let server = Routes::new()
+            .route_with_status("/hls/master.m3u8", StatusCode::FORBIDDEN, "#EXTM3U")
+            .serve()
+            .await;
+
+        match HlsClient::new()
+            .fetch(&server.url("/hls/master.m3u8"))
+            .await
+        {
+            Err(EnmaError::SrcFetchError { details }) => assert_eq!(details.status, 403),
+            other => panic!("expected a fetch error, got {other:?}"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fetch_playlists() {
+        let server = Routes::new()
+            .route("/hls/master.m3u8", fixture("hls/master.m3u8"))
+            .route("/hls/index-f1-v1-a1.m3u8", fixture("hls/media.m3u8"))
+            .serve()
+            .await;
+
+        let hls = HlsClient::new();
+        let master = match hls.fetch(&server.url("/hls/master.m3u8")).await {
+            Ok(Playlist::Master(master)) => master,
+            other => panic!("expected a master playlist, got {other:?}"),
+        };
+        let variant = master.select_variant(VariantPreference::Best).unwrap();
+        assert_eq!(variant.uri, server.url("/hls/index-f1-v1-a1.m3u8"));
+
+        let media = hls.fetch_media(variant).await.unwrap();
+        assert_eq!(media.segments.len(), 4);
+        assert_eq!(media.segments[0].uri, server.url("/hls/seg-7-v1-a1.ts"));
+    }
+}
@@ -1 +1,297 @@
+use super::{
+    origin, parse_url, source_kind, ExtractFuture, ExtractedStream, Source, StreamExtractor,
+    TimeRange,
+};
+use crate::{
+    anime::SubtitleTrack,
+    error::{EnmaError, EnmaResult},
+    utils::{EnmaClient, EnmaUtils},
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, REFERER},
+    Client, Url,
+};
+use serde::Deserialize;
 
+const PROVIDER_PARSER: &'static str = "extractors:megacloud";
+
+/// the places the embed page hides the client key `getSources` expects in,
+/// it moves between them every few weeks
+static CLIENT_KEY_REGEXES: Lazy<Vec<Regex>> = Lazy::new(|| {
+    return [
+        r#"<meta\s+name="_gg_fb"\s+content="([0-9a-zA-Z]+)""#,
+        r"<!--\s*_is_th:([0-9a-zA-Z]+)\s*-->",
+        r#"data-dpi="([0-9a-zA-Z]+)""#,
+        r#"<script\s+nonce="([0-9a-zA-Z]+)""#,
+        r#"_xy_ws\s*=\s*["']([0-9a-zA-Z]+)["']"#,
+    ]
+    .into_iter()
+    .map(|re| Regex::new(re).unwrap())
+    .collect();
+});
+
+/// the client key split in three, `window._lk_db = {x: "..", y: "..", z: ".."}`
+static SPLIT_CLIENT_KEY_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(
+        r#"_lk_db\s*=\s*\{\s*x:\s*["']([0-9a-zA-Z]+)["'],\s*y:\s*["']([0-9a-zA-Z]+)["'],\s*z:\s*["']([0-9a-zA-Z]+)["']"#,
+    )
+    .unwrap();
+});
+
+// represents the raw json data we get from the source
+#[derive(Deserialize, Debug)]
+struct RawSources {
+    /// a list of sources, or a string when they're encrypted
+    sources: serde_json::Value,
+    #[serde(default)]
+    tracks: Vec<RawTrack>,
+    intro: Option<TimeRange>,
+    outro: Option<TimeRange>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawSource {
+    file: String,
+    #[serde(rename = "type")]
+    kind: Option<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawTrack {
+    file: String,
+    label: Option<String>,
+    kind: Option<String>,
+    #[serde(default)]
+    default: bool,
+}
+
+/// extractor of the megacloud embeds behind hianime's `hd-1` and `hd-2`
+/// servers
+#[derive(Debug, Clone)]
+pub struct MegaCloud {
+    client: Client,
+}
+
+impl Default for MegaCloud {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl StreamExtractor for MegaCloud {
+    fn servers(&self) -> &'static [&'static str] {
+        return &["hd-1", "hd-2", "megacloud"];
+    }
+
+    fn extract<'a>(&'a self, embed_url: &'a str) -> ExtractFuture<'a> {
+        return Box::pin(self.extract_stream(embed_url));
+    }
+}
+
+impl MegaCloud {
+    pub fn new() -> Self {
+        return Self {
+            client: EnmaUtils::new_http_client(None),
+        };
+    }
+
+    #[tracing::instrument(name = "extractors:megacloud", skip(self))]
+    async fn extract_stream(&self, embed_url: &str) -> EnmaResult<ExtractedStream> {
+        let url = parse_url(embed_url, PROVIDER_PARSER)?;
+        let referer = HeaderValue::from_str(embed_url).map_err(|_| {
+            EnmaError::invalid_data_error(
+                PROVIDER_PARSER,
+                Some(format!("invalid embed url {embed_url:?}")),
+                None,
+            )
+        })?;
+        let headers: HeaderMap = [
+            (REFERER, referer),
+            (
+                HeaderName::from_static("x-requested-with"),
+                HeaderValue::from_static(EnmaUtils::XRequestedWithHeader.value()),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let page = self
+            .client
+            .get_html(
+                embed_url.to_string(),
+                Some(headers.clone()),
+                PROVIDER_PARSER,
+            )
+            .await?;
+        let Some(client_key) = Self::parse_client_key(&page) else {
+            return Err(EnmaError::src_parse_error(
+                PROVIDER_PARSER,
+                Some(String::from("client key not found in the embed page")),
+                None,
+            ));
+        };
+
+        let sources = self
+            .client
+            .get_json::<RawSources>(
+                Self::sources_url(&url, &client_key)?,
+                Some(headers),
+                PROVIDER_PARSER,
+            )
+            .await?;
+
+        return Self::parse_sources(&url, sources);
+    }
+
+    fn parse_client_key(page: &str) -> Option<String> {
+        if let Some(key) = CLIENT_KEY_REGEXES
+            .iter()
+            .find_map(|re| re.captures(page).map(|c| c[1].to_string()))
+        {
+            return Some(key);
+        }
+
+        return SPLIT_CLIENT_KEY_REGEX
+            .captures(page)
+            .map(|c| format!("{}{}{}", &c[1], &c[2], &c[3]));
+    }
+
+    /// `getSources` sits next to the embed, e.g. `/embed-2/v3/e-1/getSources`
+    /// for `/embed-2/v3/e-1/{id}`
+    fn sources_url(embed_url: &Url, client_key: &str) -> EnmaResult<String> {
+        let Some((prefix, id)) = embed_url
+            .path()
+            .trim_end_matches('/')
+            .rsplit_once('/')
+            .filter(|(_, id)| !id.is_empty())
+        else {
+            return Err(EnmaError::invalid_data_error(
+                PROVIDER_PARSER,
+                Some(format!("no source id in {embed_url}")),
+                None,
+            ));
+        };
+
+        return Ok(format!(
+            "{}{prefix}/getSources?id={id}&_k={client_key}",
+            embed_url.origin().ascii_serialization()
+        ));
+    }
+
+    fn parse_sources(embed_url: &Url, raw: RawSources) -> EnmaResult<ExtractedStream> {
+        let sources = match raw.sources {
+            serde_json::Value::String(_) => {
+                return Err(EnmaError::invalid_data_error(
+                    PROVIDER_PARSER,
+                    Some(String::from("encrypted sources aren't supported")),
+                    None,
+                ))
+            }
+            sources => serde_json::from_value::<Vec<RawSource>>(sources)
+                .map_err(|_| EnmaError::src_parse_error(PROVIDER_PARSER, None, None))?,
+        };
+
+        let mut res = ExtractedStream {
+            sources: sources
+                .into_iter()
+                .map(|source| Source {
+                    kind: source_kind(source.kind.as_deref(), &source.file),
+                    url: source.file,
+                    quality: None,
+                })
+                .collect(),
+            // ranges the server doesn't know are all zeros
+            intro: raw.intro.filter(|range| range.end > 0),
+            outro: raw.outro.filter(|range| range.end > 0),
+            ..Default::default()
+        };
+
+        for track in raw.tracks {
+            match track.kind.as_deref() {
+                Some("thumbnails") => res.thumbnails = Some(track.file),
+                _ => res.subtitles.push(SubtitleTrack {
+                    url: track.file,
+                    lang: track.label.unwrap_or_default(),
+                    default: track.default,
+                }),
+            }
+        }
+
+        res.headers
+            .insert(String::from("Referer"), origin(embed_url));
+
+        return Ok(res);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{MegaCloud, RawSources};
+    use crate::{
+        anime::extractors::{SourceKind, TimeRange},
+        test_utils::fixture,
+    };
+    use reqwest::Url;
+
+    // cargo test --features extractors --lib -- anime::extractors::megacloud::test --show-output
+    #[test]
+    fn test_parse_embed_page() {
+        let page = fixture("extractors/megacloud_embed.html");
+        let key = MegaCloud::parse_client_key(&page).unwrap();
+        assert_eq!(key, "Vx3pQ9sLk2Wm8Rt5Yb7Nc4Hd6Jf1Gz0Ua2Ei9Ko3Pl8Mn5Bq");
+
+        let split = r#"<script>window._lk_db = {x: "Vx3pQ9sLk2Wm8Rt5", y: "Yb7Nc4Hd6Jf1Gz0U", z: "a2Ei9Ko3Pl8Mn5Bq"};</script>"#;
+        assert_eq!(MegaCloud::parse_client_key(split), Some(key.clone()));
+        assert_eq!(MegaCloud::parse_client_key("<html></html>"), None);
+
+        let url = Url::parse("https://megacloud.blog/embed-2/v3/e-1/AbCdEf123?k=1").unwrap();
+        assert_eq!(
+            MegaCloud::sources_url(&url, &key).unwrap(),
+            format!("https://megacloud.blog/embed-2/v3/e-1/getSources?id=AbCdEf123&_k={key}")
+        );
+        let url = Url::parse("https://megacloud.blog/").unwrap();
+        assert!(MegaCloud::sources_url(&url, &key).is_err());
+    }
+
+    #[test]
+    fn test_parse_sources() {
+        let url = Url::parse("https://megacloud.blog/embed-2/v3/e-1/AbCdEf123?k=1").unwrap();
+        let raw = serde_json::from_str::<RawSources>(&fixture("extractors/megacloud_sources.json"))
+            .unwrap();
+        let stream = MegaCloud::parse_sources(&url, raw).unwrap();
+        println!("{stream:#?}");
+
+        assert_eq!(stream.sources.len(), 1);
+        assert_eq!(
+            stream.sources[0].url,
+            "https://cdn.example.com/_v7/b1a2c3/master.m3u8"
+        );
+        assert_eq!(stream.sources[0].kind, SourceKind::Hls);
+
+        assert_eq!(stream.subtitles.len(), 2);
+        assert_eq!(stream.subtitles[0].lang, "English");
+        assert!(stream.subtitles[0].default && !stream.subtitles[1].default);
+        assert_eq!(
+            stream.thumbnails.as_deref(),
+            Some("https://s.megastatics.com/thumbnails/ab12/thumbnails.vtt")
+        );
+
+        assert_eq!(
+            stream.intro,
+            Some(TimeRange {
+                start: 31,
+                end: 121
+            })
+        );
+        assert_eq!(stream.outro, None);
+        assert_eq!(stream.headers["Referer"], "https://megacloud.blog/");
+
+        let encrypted = serde_json::from_str::<RawSources>(
+            r#"{"sources": "U2FsdGVkX1+abc", "tracks": [], "encrypted": true}"#,
+        )
+        .unwrap();
+        assert!(MegaCloud::parse_sources(&url, encrypted).is_err());
+    }
+}
@@ -1 +1,350 @@
+use crate::{
+    anime::SubtitleTrack,
+    error::{EnmaError, EnmaResult},
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap},
+    future::Future,
+    pin::Pin,
+    sync::Arc,
+};
+
+mod filemoon;
+/// HLS playlists parsing and rendition selection
+pub mod hls;
 mod megacloud;
+mod streamtape;
+
+pub use filemoon::FileMoon;
+pub use megacloud::MegaCloud;
+pub use streamtape::StreamTape;
+
+const PROVIDER_PARSER: &'static str = "extractors";
+
+static TRACK_TAG_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(r"(?i)<track\b[^>]*>").unwrap();
+});
+
+static TAG_ATTRIBUTE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(r#"(?i)([a-z-]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s>]+)))?"#).unwrap();
+});
+
+/// what every extractor returns, whatever the server
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ExtractedStream {
+    pub sources: Vec<Source>,
+    pub subtitles: Vec<SubtitleTrack>,
+    /// WebVTT track of the seek bar thumbnails
+    pub thumbnails: Option<String>,
+    pub intro: Option<TimeRange>,
+    pub outro: Option<TimeRange>,
+    /// headers the sources must be fetched with, usually a `Referer`
+    pub headers: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Source {
+    pub url: String,
+    pub kind: SourceKind,
+    /// e.g. `1080p`, when the server tells
+    pub quality: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum SourceKind {
+    /// an HLS playlist, see [`hls`]
+    Hls,
+    Mp4,
+}
+
+/// in seconds
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TimeRange {
+    pub start: u64,
+    pub end: u64,
+}
+
+pub type ExtractFuture<'a> = Pin<Box<dyn Future<Output = EnmaResult<ExtractedStream>> + Send + 'a>>;
+
+/// turns the embed url of a video server into the stream it plays
+pub trait StreamExtractor: Send + Sync {
+    /// values of the servers it extracts from, as in
+    /// `enma::anime::hianime::Server::value`
+    fn servers(&self) -> &'static [&'static str];
+
+    fn extract<'a>(&'a self, embed_url: &'a str) -> ExtractFuture<'a>;
+}
+
+/// the extractors, by the servers they extract from
+#[derive(Clone)]
+pub struct Extractors {
+    extractors: HashMap<&'static str, Arc<dyn StreamExtractor>>,
+}
+
+impl Default for Extractors {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl Extractors {
+    /// registers the built-in [`MegaCloud`], for the `hd-1`, `hd-2` and
+    /// `megacloud` servers, [`StreamTape`] and [`FileMoon`]
+    pub fn new() -> Self {
+        let mut res = Self {
+            extractors: HashMap::new(),
+        };
+        res.register(MegaCloud::new());
+        res.register(StreamTape::new());
+        res.register(FileMoon::new());
+
+        return res;
+    }
+
+    /// registers `extractor` for its servers, replacing the ones registered
+    /// for the same servers
+    pub fn register(&mut self, extractor: impl StreamExtractor + 'static) {
+        let extractor: Arc<dyn StreamExtractor> = Arc::new(extractor);
+        for server in extractor.servers() {
+            self.extractors.insert(server, extractor.clone());
+        }
+    }
+
+    pub fn get(&self, server: &str) -> Option<&dyn StreamExtractor> {
+        return self.extractors.get(server).map(|e| e.as_ref());
+    }
+
+    /// values of the servers an extractor is registered for
+    pub fn servers(&self) -> impl Iterator<Item = &'static str> + '_ {
+        return self.extractors.keys().copied();
+    }
+
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::extractors::Extractors;
+    ///     let extractors = Extractors::new();
+    ///
+    ///     let embed_url = "https://megacloud.blog/embed-2/v3/e-1/AbCdEf123?k=1";
+    ///
+    ///     match extractors.extract("hd-1", embed_url).await {
+    ///         Ok(stream) => println!("{stream:#?}"),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    pub async fn extract(&self, server: &str, embed_url: &str) -> EnmaResult<ExtractedStream> {
+        let Some(extractor) = self.get(server) else {
+            return Err(EnmaError::invalid_data_error(
+                PROVIDER_PARSER,
+                Some(format!("no extractor for the {server:?} server")),
+                None,
+            ));
+        };
+
+        return extractor.extract(embed_url).await;
+    }
+}
+
+#[cfg(feature = "hianime")]
+impl Extractors {
+    /// same as [`Extractors::extract`], for one of the servers hianime lists
+    pub async fn extract_server(
+        &self,
+        server: crate::anime::hianime::Server,
+        embed_url: &str,
+    ) -> EnmaResult<ExtractedStream> {
+        return self.extract(server.value(), embed_url).await;
+    }
+}
+
+fn parse_url(url: &str, provider_parser: &'static str) -> EnmaResult<Url> {
+    return Url::parse(url).map_err(|_| {
+        EnmaError::invalid_data_error(
+            provider_parser,
+            Some(format!("invalid embed url {url:?}")),
+            None,
+        )
+    });
+}
+
+/// `scheme://host[:port]/` of `url`, the referer the servers expect
+fn origin(url: &Url) -> String {
+    return format!("{}/", url.origin().ascii_serialization());
+}
+
+fn source_kind(kind: Option<&str>, url: &str) -> SourceKind {
+    let is_hls = kind.is_some_and(|k| k.eq_ignore_ascii_case("hls"))
+        || url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .ends_with(".m3u8");
+
+    return if is_hls {
+        SourceKind::Hls
+    } else {
+        SourceKind::Mp4
+    };
+}
+
+/// the `<track>` tags of a page, relative urls are resolved against `base`
+fn html_tracks(page: &str, base: &Url) -> (Vec<SubtitleTrack>, Option<String>) {
+    let mut subtitles = vec![];
+    let mut thumbnails = None;
+
+    for tag in TRACK_TAG_REGEX.find_iter(page) {
+        // skips the `<track` the regex matched from
+        let attributes = TAG_ATTRIBUTE_REGEX
+            .captures_iter(&tag.as_str()[6..])
+            .map(|c| {
+                let value = c.get(2).or(c.get(3)).or(c.get(4));
+                (
+                    c[1].to_lowercase(),
+                    value.map(|v| v.as_str()).unwrap_or_default().to_string(),
+                )
+            })
+            .collect::<HashMap<_, _>>();
+
+        let Some(url) = attributes.get("src").and_then(|src| base.join(src).ok()) else {
+            continue;
+        };
+        match attributes.get("kind").map(|k| k.as_str()) {
+            Some("thumbnails") => thumbnails = Some(url.to_string()),
+            Some("captions" | "subtitles") | None => subtitles.push(SubtitleTrack {
+                url: url.to_string(),
+                lang: attributes
+                    .get("label")
+                    .or(attributes.get("srclang"))
+                    .cloned()
+                    .unwrap_or_default(),
+                default: attributes.contains_key("default"),
+            }),
+            _ => (),
+        }
+    }
+
+    return (subtitles, thumbnails);
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Extractors, SourceKind};
+    use crate::test_utils::{fixture, Routes, TestServer};
+
+    /// serves the extractor fixtures at the paths the servers use, the
+    /// sources are only handed out with the page's client key
+    async fn serve() -> TestServer {
+        return Routes::new()
+            .route(
+                "/embed-2/v3/e-1/AbCdEf123",
+                fixture("extractors/megacloud_embed.html"),
+            )
+            .route(
+                "/embed-2/v3/e-1/getSources?id=AbCdEf123&_k=Vx3pQ9sLk2Wm8Rt5Yb7Nc4Hd6Jf1Gz0Ua2Ei9Ko3Pl8Mn5Bq",
+                fixture("extractors/megacloud_sources.json"),
+            )
+            .route("/e/Kx3mPq", fixture("extractors/streamtape_embed.html"))
+            .route("/e/abc", fixture("extractors/filemoon_embed.html"))
+            .route("/e2/abc", fixture("extractors/filemoon_player.html"))
+            .serve()
+            .await;
+    }
+
+    // cargo test --features extractors --lib -- anime::extractors::test --show-output
+    #[tokio::test]
+    async fn test_extract() {
+        let base = serve().await.base();
+        let extractors = Extractors::new();
+
+        for server in ["hd-1", "hd-2", "megacloud"] {
+            let stream = extractors
+                .extract(server, &format!("{base}/embed-2/v3/e-1/AbCdEf123?k=1"))
+                .await
+                .unwrap();
+            assert_eq!(stream.sources[0].kind, SourceKind::Hls);
+            assert_eq!(stream.subtitles.len(), 2);
+            assert_eq!(stream.headers["Referer"], format!("{base}/"));
+        }
+
+        let stream = extractors
+            .extract("streamtape", &format!("{base}/e/Kx3mPq"))
+            .await
+            .unwrap();
+        println!("{stream:#?}");
+        assert_eq!(stream.sources[0].kind, SourceKind::Mp4);
+        assert_eq!(stream.subtitles[0].lang, "English");
+
+        let stream = extractors
+            .extract("filemoon", &format!("{base}/e/abc"))
+            .await
+            .unwrap();
+        println!("{stream:#?}");
+        assert_eq!(stream.sources[0].kind, SourceKind::Hls);
+        assert_eq!(stream.subtitles.len(), 2);
+        assert_eq!(
+            stream.thumbnails,
+            Some(format!(
+                "{base}/dl?op=get_slides&length=1420&url=https://filemoon.example/abc0000.jpg"
+            ))
+        );
+    }
+
+    #[tokio::test]
+    async fn test_extract_errors() {
+        let base = serve().await.base();
+        let extractors = Extractors::new();
+
+        assert!(extractors
+            .extract("mixdrop", "https://mixdrop.ag/e/x")
+            .await
+            .is_err());
+        assert!(extractors.extract("hd-1", "not an url").await.is_err());
+        // a page without the client key
+        assert!(extractors
+            .extract("hd-1", &format!("{base}/e/Kx3mPq"))
+            .await
+            .is_err());
+        assert!(extractors
+            .extract("streamtape", &format!("{base}/e/abc"))
+            .await
+            .is_err());
+    }
+
+    #[cfg(feature = "hianime")]
+    #[test]
+    fn test_registered_by_server_value() {
+        use crate::anime::hianime::{Server, ANIME_SERVERS};
+
+        let extractors = Extractors::new();
+        for server in [
+            Server::VidStreaming,
+            Server::VidCloud,
+            Server::MegaCloud,
+            Server::StreamTape,
+            Server::FileMoon,
+        ] {
+            assert!(extractors.get(server.value()).is_some(), "{server:?}");
+        }
+        for server in extractors.servers() {
+            assert!(Server::from_value(server).is_some(), "{server}");
+        }
+
+        // every server hianime lists has an extractor but the defunct streamsb
+        let mut missing = ANIME_SERVERS
+            .iter()
+            .filter(|server| extractors.get(server).is_none())
+            .collect::<Vec<_>>();
+        missing.sort();
+        assert_eq!(missing, vec![&"streamsb"]);
+    }
+}
@@ -0,0 +1,150 @@
+use super::{
+    html_tracks, parse_url, ExtractFuture, ExtractedStream, Source, SourceKind, StreamExtractor,
+};
+use crate::{
+    error::{EnmaError, EnmaResult},
+    utils::{EnmaClient, EnmaUtils},
+};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use reqwest::{Client, Url};
+
+const PROVIDER_PARSER: &'static str = "extractors:streamtape";
+
+/// the script assembling the video url, e.g.
+/// `getElementById('robotlink').innerHTML = '//streamtape.com/get_video?..&token=' + ('xcdtoken').substring(1).substring(2);`
+static ROBOTLINK_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(
+        r#"getElementById\(\s*'robotlink'\s*\)\.innerHTML\s*=\s*['"]([^'"]*)['"]\s*\+\s*\(\s*['"]([^'"]*)['"]\s*\)((?:\.substring\(\d+\))*)"#,
+    )
+    .unwrap();
+});
+
+static SUBSTRING_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(r"\.substring\((\d+)\)").unwrap();
+});
+
+/// extractor of the streamtape embeds
+#[derive(Debug, Clone)]
+pub struct StreamTape {
+    client: Client,
+}
+
+impl Default for StreamTape {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl StreamExtractor for StreamTape {
+    fn servers(&self) -> &'static [&'static str] {
+        return &["streamtape"];
+    }
+
+    fn extract<'a>(&'a self, embed_url: &'a str) -> ExtractFuture<'a> {
+        return Box::pin(self.extract_stream(embed_url));
+    }
+}
+
+impl StreamTape {
+    pub fn new() -> Self {
+        return Self {
+            client: EnmaUtils::new_http_client(None),
+        };
+    }
+
+    #[tracing::instrument(name = "extractors:streamtape", skip(self))]
+    async fn extract_stream(&self, embed_url: &str) -> EnmaResult<ExtractedStream> {
+        let url = parse_url(embed_url, PROVIDER_PARSER)?;
+        let page = self
+            .client
+            .get_html(embed_url.to_string(), None, PROVIDER_PARSER)
+            .await?;
+
+        return Self::parse_embed_page(&url, &page);
+    }
+
+    fn parse_embed_page(embed_url: &Url, page: &str) -> EnmaResult<ExtractedStream> {
+        let Some(captures) = ROBOTLINK_REGEX.captures(page) else {
+            return Err(EnmaError::src_parse_error(
+                PROVIDER_PARSER,
+                Some(String::from(
+                    "video link script not found in the embed page",
+                )),
+                None,
+            ));
+        };
+
+        // every `.substring(n)` drops the first n characters of the token
+        let skipped = SUBSTRING_REGEX
+            .captures_iter(&captures[3])
+            .filter_map(|c| c[1].parse::<usize>().ok())
+            .sum::<usize>();
+        let token = captures[2].chars().skip(skipped).collect::<String>();
+
+        let link = format!("{}{token}", &captures[1]);
+        let mut video_url = embed_url.join(&link).map_err(|_| {
+            EnmaError::src_parse_error(
+                PROVIDER_PARSER,
+                Some(format!("invalid video link {link:?}")),
+                None,
+            )
+        })?;
+        video_url.query_pairs_mut().append_pair("stream", "1");
+
+        let (subtitles, thumbnails) = html_tracks(page, embed_url);
+        return Ok(ExtractedStream {
+            sources: vec![Source {
+                url: video_url.to_string(),
+                kind: SourceKind::Mp4,
+                quality: None,
+            }],
+            subtitles,
+            thumbnails,
+            ..Default::default()
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::StreamTape;
+    use crate::{anime::extractors::SourceKind, test_utils::fixture};
+    use reqwest::Url;
+
+    // cargo test --features extractors --lib -- anime::extractors::streamtape::test --show-output
+    #[test]
+    fn test_parse_embed_page() {
+        let url = Url::parse("https://streamtape.com/e/Kx3mPq").unwrap();
+        let stream =
+            StreamTape::parse_embed_page(&url, &fixture("extractors/streamtape_embed.html"))
+                .unwrap();
+        println!("{stream:#?}");
+
+        assert_eq!(stream.sources.len(), 1);
+        // the decoy link of `ideoolink` is skipped
+        assert_eq!(
+            stream.sources[0].url,
+            "https://streamtape.com/get_video?id=Kx3mPq&expires=1700000000&ip=F0ZkKRSOKzSHDN&token=z9Tc8jBv1nP&stream=1"
+        );
+        assert_eq!(stream.sources[0].kind, SourceKind::Mp4);
+
+        assert_eq!(stream.subtitles.len(), 1);
+        assert_eq!(
+            stream.subtitles[0].url,
+            "https://tapecontent.example.net/subs/Kx3mPq_eng.vtt"
+        );
+        assert_eq!(stream.subtitles[0].lang, "English");
+        assert!(stream.subtitles[0].default);
+        assert!(stream.headers.is_empty());
+
+        // a link without a query still gets a well formed one
+        let page = "document.getElementById('robotlink').innerHTML = '//streamtape.com/v/' + ('xKx3mPq').substring(1);";
+        assert_eq!(
+            StreamTape::parse_embed_page(&url, page).unwrap().sources[0].url,
+            "https://streamtape.com/v/Kx3mPq?stream=1"
+        );
+
+        assert!(StreamTape::parse_embed_page(&url, "<html></html>").is_err());
+    }
+}
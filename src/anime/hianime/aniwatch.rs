@@ -0,0 +1,601 @@
+use super::parsers::{
+    ScrapedAZList, ScrapedAnimeCharacters, ScrapedAnimeEpisodes, ScrapedAnimeInfo,
+    ScrapedCategoryAnime, ScrapedCharacterInfo, ScrapedEpisodeServers, ScrapedExternalIds,
+    ScrapedFranchise, ScrapedGenreAnime, ScrapedHomePage, ScrapedIdMapping, ScrapedPersonInfo,
+    ScrapedProducerAnime, ScrapedQtipInfo, ScrapedSchedule, ScrapedSearchResult,
+    ScrapedSearchSuggestion, ScrapedTitleMatches,
+};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// fields whose values are maps keyed by scraped or user given text, their
+/// keys are kept as is instead of being camel cased
+const VERBATIM_MAP_FIELDS: [&str; 3] = ["other_info", "about", "search_filter"];
+
+/// serializes the scraped data in the same JSON shape as aniwatch's
+/// typescript package, i.e. camelCase keys and the same field names and
+/// nesting as its documented responses
+///
+/// ### Example Usage
+/// ```rust
+/// async fn get_data() {
+///     use enma::anime::hianime::{self, AniwatchJson};
+///     let hianime = hianime::Scraper::new();
+///
+///     match hianime.get_info("steinsgate-3").await {
+///         Ok(data) => println!("{}", data.to_aniwatch_json().unwrap()["anime"]["moreInfo"]),
+///         Err(e) => eprintln!("error: {e}"),
+///     }
+/// }
+/// ```
+pub trait AniwatchJson: Serialize {
+    fn to_aniwatch_json(&self) -> serde_json::Result<Value> {
+        return aniwatch_value(self);
+    }
+}
+
+impl AniwatchJson for ScrapedHomePage {}
+impl AniwatchJson for ScrapedAZList {}
+impl AniwatchJson for ScrapedProducerAnime {}
+impl AniwatchJson for ScrapedGenreAnime {}
+impl AniwatchJson for ScrapedAnimeEpisodes {}
+impl AniwatchJson for ScrapedCharacterInfo {}
+impl AniwatchJson for ScrapedPersonInfo {}
+impl AniwatchJson for ScrapedAnimeCharacters {}
+impl AniwatchJson for ScrapedFranchise {}
+impl AniwatchJson for ScrapedIdMapping {}
+impl AniwatchJson for ScrapedExternalIds {}
+impl AniwatchJson for ScrapedTitleMatches {}
+
+impl AniwatchJson for ScrapedSearchResult {
+    fn to_aniwatch_json(&self) -> serde_json::Result<Value> {
+        let mut value = aniwatch_value(self)?;
+        rename_key(&mut value, "searchFilter", "searchFilters");
+        return Ok(value);
+    }
+}
+
+impl AniwatchJson for ScrapedSearchSuggestion {
+    fn to_aniwatch_json(&self) -> serde_json::Result<Value> {
+        let mut value = aniwatch_value(self)?;
+        for suggestion in items_mut(&mut value, "suggestions") {
+            rename_key(suggestion, "otherInfo", "moreInfo");
+        }
+        return Ok(value);
+    }
+}
+
+impl AniwatchJson for ScrapedQtipInfo {
+    fn to_aniwatch_json(&self) -> serde_json::Result<Value> {
+        let mut value = aniwatch_value(self)?;
+        if let Some(anime) = value.get_mut("anime") {
+            rename_key(anime, "malScore", "malscore");
+            // aniwatch keeps the score as it's shown, e.g. `"8.54"`
+            if let Some(score) = anime.get_mut("malscore").filter(|s| s.is_number()) {
+                *score = Value::String(score.to_string());
+            }
+        }
+        return Ok(value);
+    }
+}
+
+impl AniwatchJson for ScrapedCategoryAnime {
+    fn to_aniwatch_json(&self) -> serde_json::Result<Value> {
+        let mut value = aniwatch_value(self)?;
+        rename_key(&mut value, "categoryName", "category");
+        return Ok(value);
+    }
+}
+
+impl AniwatchJson for ScrapedEpisodeServers {
+    fn to_aniwatch_json(&self) -> serde_json::Result<Value> {
+        let mut value = aniwatch_value(self)?;
+        rename_key(&mut value, "animeEpisodeId", "episodeId");
+        rename_key(&mut value, "episodeNumber", "episodeNo");
+        return Ok(value);
+    }
+}
+
+impl AniwatchJson for ScrapedSchedule {
+    fn to_aniwatch_json(&self) -> serde_json::Result<Value> {
+        let mut value = aniwatch_value(self)?;
+        rename_key(&mut value, "animes", "scheduledAnimes");
+        for anime in items_mut(&mut value, "scheduledAnimes") {
+            rename_key(anime, "episodeNumber", "episode");
+            // aniwatch only has `secondsUntilAiring` to tell it
+            remove_key(anime, "alreadyAired");
+        }
+        return Ok(value);
+    }
+}
+
+impl AniwatchJson for ScrapedAnimeInfo {
+    /// aniwatch splits the anime into `anime.info` and `anime.moreInfo`, and
+    /// lists the seasons next to the other anime lists
+    fn to_aniwatch_json(&self) -> serde_json::Result<Value> {
+        let mut value = aniwatch_value(self)?;
+        let Some(Value::Object(mut info)) = value.get_mut("anime").map(Value::take) else {
+            return Ok(value);
+        };
+
+        let seasons = info.remove("seasons").unwrap_or(Value::Array(vec![]));
+        // aniwatch only has the japanese name in `moreInfo.japanese`
+        info.remove("jname");
+        let more_info = info
+            .remove("otherInfo")
+            .unwrap_or(Value::Object(Map::new()));
+
+        if let Value::Object(map) = &mut value {
+            let mut anime = Map::new();
+            anime.insert(String::from("info"), Value::Object(info));
+            anime.insert(String::from("moreInfo"), more_info);
+
+            map.insert(String::from("anime"), Value::Object(anime));
+            map.insert(String::from("seasons"), seasons);
+        }
+        return Ok(value);
+    }
+}
+
+/// camel cased json of the data, without the `schema_version` marker that
+/// aniwatch doesn't have
+fn aniwatch_value<T: Serialize + ?Sized>(data: &T) -> serde_json::Result<Value> {
+    let mut value = camel_case_keys(serde_json::to_value(data)?);
+    if let Value::Object(map) = &mut value {
+        map.remove("schemaVersion");
+    }
+    return Ok(value);
+}
+
+/// recursively converts every snake_case object key to camelCase
+fn camel_case_keys(value: Value) -> Value {
+    return match value {
+        Value::Object(map) => Value::Object(
+            map.into_iter()
+                .map(|(key, value)| {
+                    let value = match VERBATIM_MAP_FIELDS.contains(&key.as_str()) {
+                        true => value,
+                        false => camel_case_keys(value),
+                    };
+                    (to_camel_case(&key), value)
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(items.into_iter().map(camel_case_keys).collect()),
+        other => other,
+    };
+}
+
+/// `top10_animes` -> `top10Animes`
+fn to_camel_case(key: &str) -> String {
+    let mut out = String::with_capacity(key.len());
+    let mut upper_next = false;
+
+    for c in key.chars() {
+        match c {
+            '_' => upper_next = !out.is_empty(),
+            c if upper_next => {
+                out.extend(c.to_uppercase());
+                upper_next = false;
+            }
+            c => out.push(c),
+        }
+    }
+    return out;
+}
+
+fn rename_key(value: &mut Value, from: &str, to: &str) {
+    if let Value::Object(map) = value {
+        if let Some(v) = map.remove(from) {
+            map.insert(to.to_string(), v);
+        }
+    }
+}
+
+fn remove_key(value: &mut Value, key: &str) {
+    if let Value::Object(map) = value {
+        map.remove(key);
+    }
+}
+
+fn items_mut<'a>(value: &'a mut Value, key: &str) -> impl Iterator<Item = &'a mut Value> {
+    value
+        .get_mut(key)
+        .and_then(Value::as_array_mut)
+        .into_iter()
+        .flatten()
+}
+
+#[cfg(test)]
+mod test {
+    use super::{to_camel_case, AniwatchJson};
+    use crate::anime::hianime::{
+        types::{
+            Anime, AnimeCharacter, AnimeCharactersVoiceActors, AnimeDetailedInfo,
+            AnimeDetailedStats, AnimeEpisode, AnimePromotionalVideo, AnimeSearchSuggestion,
+            EpisodeInfo, Episodes, MostPopularAnime, OtherInfoValue, QtipAnime, ScheduledAnime,
+            Season, SpotlightAnime, Top10Anime, Top10AnimesWithPeriod, TrendingAnime,
+        },
+        AnimeId, EpisodeId, ScrapedAZList, ScrapedAnimeEpisodes, ScrapedAnimeInfo,
+        ScrapedCategoryAnime, ScrapedEpisodeServers, ScrapedGenreAnime, ScrapedHomePage,
+        ScrapedProducerAnime, ScrapedQtipInfo, ScrapedSchedule, ScrapedSearchResult,
+        ScrapedSearchSuggestion, SearchFilter, SearchFilters,
+    };
+    use crate::SchemaVersion;
+    use serde_json::Value;
+    use std::collections::HashMap;
+
+    /// golden files are the `data` part of aniwatch's responses, see
+    /// `fixtures/aniwatch/update.sh` to refresh them
+    fn golden(name: &str) -> Value {
+        let path = format!(
+            "{}/fixtures/aniwatch/{name}.json",
+            env!("CARGO_MANIFEST_DIR")
+        );
+        let file = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{path}: {e}"));
+        return serde_json::from_str(&file).unwrap_or_else(|e| panic!("{path}: {e}"));
+    }
+
+    /// `value` must have exactly the keys of `golden`, recursively, with
+    /// values of the same json type; the first item of a golden array is the
+    /// shape of every item of the value's array
+    fn shape_errors(path: &str, golden: &Value, value: &Value, errors: &mut Vec<String>) {
+        match (golden, value) {
+            (Value::Object(golden), Value::Object(value)) => {
+                for key in golden.keys().filter(|key| !value.contains_key(*key)) {
+                    errors.push(format!("`{path}.{key}` is missing"));
+                }
+                for key in value.keys().filter(|key| !golden.contains_key(*key)) {
+                    errors.push(format!("`{path}.{key}` isn't in aniwatch's response"));
+                }
+                for (key, golden) in golden {
+                    if let Some(value) = value.get(key) {
+                        shape_errors(&format!("{path}.{key}"), golden, value, errors);
+                    }
+                }
+            }
+            (Value::Array(golden), Value::Array(value)) => match golden.first() {
+                Some(_) if value.is_empty() => {
+                    errors.push(format!("`{path}` is empty, can't compare"))
+                }
+                Some(golden) => {
+                    for value in value {
+                        shape_errors(&format!("{path}[]"), golden, value, errors);
+                    }
+                }
+                None if !value.is_empty() => errors.push(format!("`{path}` has no golden item")),
+                None => {}
+            },
+            (Value::Null, Value::Null)
+            | (Value::Number(_), Value::Number(_))
+            | (Value::String(_), Value::String(_))
+            | (Value::Bool(_), Value::Bool(_)) => {}
+            (golden, value) => errors.push(format!(
+                "`{path}` is {value}, expected a value like {golden}"
+            )),
+        }
+    }
+
+    fn check(name: &str, data: &impl AniwatchJson) {
+        let value = data.to_aniwatch_json().unwrap();
+        println!("{name}: {value:#}");
+
+        let mut errors = Vec::new();
+        shape_errors(name, &golden(name), &value, &mut errors);
+        assert!(errors.is_empty(), "{}", errors.join("\n"));
+    }
+
+    fn anime_id() -> Option<AnimeId> {
+        "attack-on-titan-112".parse().ok()
+    }
+
+    fn episodes() -> Episodes {
+        Episodes {
+            sub: Some(25),
+            dub: Some(25),
+        }
+    }
+
+    fn anime() -> Anime {
+        Anime {
+            id: anime_id(),
+            name: Some(String::from("Attack on Titan")),
+            poster: Some(String::from("https://cdn.noitatnemucod.net/poster.jpg")),
+            jname: Some(String::from("Shingeki no Kyojin")),
+            duration: Some(String::from("24m")),
+            rating: Some(String::from("18+")),
+            anime_type: Some(String::from("TV")),
+            episodes: episodes(),
+        }
+    }
+
+    fn most_popular_anime() -> MostPopularAnime {
+        MostPopularAnime {
+            id: anime_id(),
+            name: Some(String::from("Attack on Titan")),
+            poster: Some(String::from("https://cdn.noitatnemucod.net/poster.jpg")),
+            jname: Some(String::from("Shingeki no Kyojin")),
+            anime_type: Some(String::from("TV")),
+            episodes: episodes(),
+        }
+    }
+
+    fn top10_animes() -> Top10AnimesWithPeriod {
+        let top10_anime = || Top10Anime {
+            id: anime_id(),
+            name: Some(String::from("Attack on Titan")),
+            poster: Some(String::from("https://cdn.noitatnemucod.net/poster.jpg")),
+            jname: Some(String::from("Shingeki no Kyojin")),
+            rank: Some(1),
+            episodes: episodes(),
+        };
+        Top10AnimesWithPeriod {
+            today: vec![top10_anime()],
+            week: vec![top10_anime()],
+            month: vec![top10_anime()],
+        }
+    }
+
+    fn genres() -> Vec<String> {
+        vec![String::from("Action"), String::from("Drama")]
+    }
+
+    // cargo test --lib -- anime::hianime::aniwatch::test --show-output
+    #[test]
+    fn test_to_camel_case() {
+        assert_eq!(to_camel_case("top10_animes"), "top10Animes");
+        assert_eq!(to_camel_case("has_next_page"), "hasNextPage");
+        assert_eq!(to_camel_case("episodes"), "episodes");
+        assert_eq!(to_camel_case("_private"), "private");
+    }
+
+    #[test]
+    fn test_home_page() {
+        let data = ScrapedHomePage {
+            schema_version: SchemaVersion::CURRENT,
+            genres: genres(),
+            top10_animes: top10_animes(),
+            spotlight_animes: vec![SpotlightAnime {
+                id: anime_id(),
+                name: Some(String::from("Attack on Titan")),
+                jname: Some(String::from("Shingeki no Kyojin")),
+                poster: Some(String::from("https://cdn.noitatnemucod.net/poster.jpg")),
+                anime_type: Some(String::from("TV")),
+                rank: Some(1),
+                description: Some(String::from("Centuries ago, mankind was slaughtered...")),
+                other_info: vec![String::from("TV"), String::from("24m")],
+                episodes: episodes(),
+            }],
+            trending_animes: vec![TrendingAnime {
+                id: anime_id(),
+                name: Some(String::from("Attack on Titan")),
+                poster: Some(String::from("https://cdn.noitatnemucod.net/poster.jpg")),
+                jname: Some(String::from("Shingeki no Kyojin")),
+                rank: Some(1),
+            }],
+            latest_episode_animes: vec![anime()],
+            top_upcoming_animes: vec![anime()],
+            top_airing_animes: vec![most_popular_anime()],
+            most_popular_animes: vec![most_popular_anime()],
+            most_favorite_animes: vec![most_popular_anime()],
+            latest_completed_animes: vec![most_popular_anime()],
+        };
+        check("home", &data);
+    }
+
+    #[test]
+    fn test_anime_info() {
+        let data = ScrapedAnimeInfo {
+            schema_version: SchemaVersion::CURRENT,
+            anime: AnimeDetailedInfo {
+                id: anime_id(),
+                name: Some(String::from("Attack on Titan")),
+                jname: Some(String::from("Shingeki no Kyojin")),
+                poster: Some(String::from("https://cdn.noitatnemucod.net/poster.jpg")),
+                description: Some(String::from("Centuries ago, mankind was slaughtered...")),
+                anilist_id: Some(16498),
+                mal_id: Some(16498),
+                seasons: vec![Season {
+                    id: anime_id(),
+                    name: Some(String::from("Attack on Titan")),
+                    poster: Some(String::from("https://cdn.noitatnemucod.net/poster.jpg")),
+                    title: Some(String::from("Season 1")),
+                    is_current: true,
+                }],
+                other_info: HashMap::from([
+                    (
+                        String::from("japanese"),
+                        OtherInfoValue::Single(String::from("進撃の巨人")),
+                    ),
+                    (String::from("genres"), OtherInfoValue::Multiple(genres())),
+                ]),
+                stats: AnimeDetailedStats {
+                    quality: Some(String::from("HD")),
+                    duration: Some(String::from("24m")),
+                    rating: Some(String::from("R")),
+                    anime_type: Some(String::from("TV")),
+                    episodes: episodes(),
+                },
+                promotional_videos: vec![AnimePromotionalVideo {
+                    title: Some(String::from("PV 1")),
+                    source: Some(String::from("https://www.youtube.com/embed/LHtdKWJdif4")),
+                    thumbnail: Some(String::from("https://i.ytimg.com/vi/LHtdKWJdif4/hq.jpg")),
+                }],
+                characters_voice_actors: vec![AnimeCharactersVoiceActors {
+                    character: AnimeCharacter {
+                        id: Some(String::from("character:eren-yeager-70")),
+                        name: Some(String::from("Yeager, Eren")),
+                        poster: Some(String::from("https://cdn.noitatnemucod.net/eren.jpg")),
+                        cast: Some(String::from("Main")),
+                    },
+                    voice_actor: AnimeCharacter {
+                        id: Some(String::from("people:yuki-kaji-188")),
+                        name: Some(String::from("Kaji, Yuki")),
+                        poster: Some(String::from("https://cdn.noitatnemucod.net/kaji.jpg")),
+                        cast: Some(String::from("Japanese")),
+                    },
+                }],
+            },
+            related_animes: vec![most_popular_anime()],
+            recommended_animes: vec![anime()],
+            most_popular_animes: vec![most_popular_anime()],
+        };
+        check("info", &data);
+
+        let value = data.to_aniwatch_json().unwrap();
+        assert_eq!(value["anime"]["moreInfo"]["japanese"], "進撃の巨人");
+        assert_eq!(value["seasons"][0]["isCurrent"], true);
+        assert!(value["anime"]["info"].get("seasons").is_none());
+    }
+
+    #[test]
+    fn test_search() {
+        let data = ScrapedSearchResult {
+            schema_version: SchemaVersion::CURRENT,
+            search_query: String::from("titan"),
+            search_filter: SearchFilters::new(vec![
+                SearchFilter::Type(String::from("tv")),
+                SearchFilter::StartDate(String::from("2013-4-7")),
+            ]),
+            animes: vec![anime()],
+            total_pages: 2,
+            current_page: 1,
+            has_next_page: true,
+            most_popular_animes: vec![most_popular_anime()],
+        };
+        check("search", &data);
+
+        let value = data.to_aniwatch_json().unwrap();
+        assert_eq!(value["searchFilters"]["start_date"], "2013-4-7");
+
+        let data = ScrapedSearchSuggestion {
+            schema_version: SchemaVersion::CURRENT,
+            suggestions: vec![AnimeSearchSuggestion {
+                id: anime_id(),
+                name: Some(String::from("Attack on Titan")),
+                poster: Some(String::from("https://cdn.noitatnemucod.net/poster.jpg")),
+                jname: Some(String::from("Shingeki no Kyojin")),
+                other_info: vec![String::from("Apr 7, 2013"), String::from("TV")],
+            }],
+        };
+        check("search_suggestion", &data);
+    }
+
+    #[test]
+    fn test_anime_lists() {
+        let data = ScrapedCategoryAnime {
+            schema_version: SchemaVersion::CURRENT,
+            category_name: String::from("Most Popular Anime"),
+            animes: vec![anime()],
+            total_pages: 50,
+            current_page: 1,
+            has_next_page: true,
+            genres: genres(),
+            top10_animes: top10_animes(),
+        };
+        check("category", &data);
+
+        let data = ScrapedGenreAnime {
+            schema_version: SchemaVersion::CURRENT,
+            genre_name: String::from("Shounen Anime"),
+            animes: vec![anime()],
+            genres: genres(),
+            total_pages: 40,
+            current_page: 1,
+            has_next_page: true,
+            top_airing_animes: vec![most_popular_anime()],
+        };
+        check("genre", &data);
+
+        let data = ScrapedProducerAnime {
+            schema_version: SchemaVersion::CURRENT,
+            producer_name: String::from("Toei Animation Anime"),
+            animes: vec![anime()],
+            total_pages: 30,
+            current_page: 1,
+            has_next_page: true,
+            top_airing_animes: vec![most_popular_anime()],
+            top10_animes: top10_animes(),
+        };
+        check("producer", &data);
+
+        let data = ScrapedAZList {
+            schema_version: SchemaVersion::CURRENT,
+            animes: vec![anime()],
+            sort_option: String::from("a"),
+            total_pages: 20,
+            current_page: 1,
+            has_next_page: true,
+        };
+        check("az_list", &data);
+    }
+
+    #[test]
+    fn test_qtip() {
+        let data = ScrapedQtipInfo {
+            schema_version: SchemaVersion::CURRENT,
+            anime: QtipAnime {
+                id: anime_id(),
+                name: Some(String::from("Attack on Titan")),
+                mal_score: Some(8.54),
+                quality: Some(String::from("HD")),
+                episodes: episodes(),
+                anime_type: Some(String::from("TV")),
+                description: Some(String::from("Centuries ago, mankind was slaughtered...")),
+                jname: Some(String::from("Shingeki no Kyojin")),
+                synonyms: Some(String::from("AoT")),
+                aired: Some(String::from("Apr 7, 2013 to Sep 29, 2013")),
+                status: Some(String::from("Finished Airing")),
+                genres: genres(),
+            },
+        };
+        check("qtip", &data);
+    }
+
+    #[test]
+    fn test_episodes() {
+        let data = ScrapedAnimeEpisodes {
+            schema_version: SchemaVersion::CURRENT,
+            total_episodes: Some(25),
+            episodes: vec![AnimeEpisode {
+                title: Some(String::from("To You, in 2000 Years")),
+                number: Some(1),
+                episode_id: "attack-on-titan-112?ep=3303".parse::<EpisodeId>().ok(),
+                is_filler: false,
+            }],
+        };
+        check("episodes", &data);
+
+        let server = || EpisodeInfo {
+            server_name: Some(String::from("hd-1")),
+            server_id: Some(4),
+        };
+        let data = ScrapedEpisodeServers {
+            schema_version: SchemaVersion::CURRENT,
+            anime_episode_id: "attack-on-titan-112?ep=3303".parse().unwrap(),
+            episode_number: Some(1),
+            sub: vec![server()],
+            dub: vec![server()],
+            raw: vec![server()],
+        };
+        check("episode_servers", &data);
+    }
+
+    #[test]
+    fn test_schedule() {
+        let data = ScrapedSchedule {
+            schema_version: SchemaVersion::CURRENT,
+            animes: vec![ScheduledAnime {
+                id: anime_id(),
+                name: Some(String::from("Attack on Titan")),
+                jname: Some(String::from("Shingeki no Kyojin")),
+                time: Some(String::from("23:30")),
+                episode_number: Some(3),
+                airing_timestamp: 1_365_345_000_000,
+                already_aired: true,
+                seconds_until_airing: -1_000,
+            }],
+        };
+        check("schedule", &data);
+    }
+}
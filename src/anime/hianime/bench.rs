@@ -0,0 +1,124 @@
+//! entry points of the criterion benches under `benches/`, only built with
+//! the `bench` feature and hidden from the docs as they aren't part of the api
+
+use super::{AnimeId, EpisodeId, Scraper, SearchFilters, SelectorTable};
+use once_cell::sync::Lazy;
+
+/// every page parser with the sections of the [`SelectorTable`] it selects
+/// with, the fixture of a parser is named after it without the
+/// `hianime:get_` prefix
+const PAGE_PARSERS: [(&'static str, &[&'static str]); 15] = [
+    (
+        "hianime:get_home_page",
+        &[
+            "home_page",
+            "anime_card",
+            "sidebar_card",
+            "top10_card",
+            "spotlight_card",
+            "trending_card",
+        ],
+    ),
+    (
+        "hianime:get_az_list",
+        &["az_list", "anime_card", "pagination"],
+    ),
+    (
+        "hianime:get_category_anime",
+        &["category", "anime_card", "top10_card", "pagination"],
+    ),
+    (
+        "hianime:get_genre_anime",
+        &["genre", "anime_card", "sidebar_card", "pagination"],
+    ),
+    (
+        "hianime:get_producer_anime",
+        &[
+            "producer",
+            "anime_card",
+            "sidebar_card",
+            "top10_card",
+            "pagination",
+        ],
+    ),
+    (
+        "hianime:get_search_results",
+        &["search", "anime_card", "sidebar_card", "pagination"],
+    ),
+    ("hianime:get_search_suggestions", &["search_suggestions"]),
+    ("hianime:get_info", &["info", "anime_card", "sidebar_card"]),
+    ("hianime:get_qtip_info", &["qtip"]),
+    ("hianime:get_anime_episodes", &["episodes"]),
+    ("hianime:get_episode_servers", &["episode_servers"]),
+    ("hianime:get_schedule", &["schedule"]),
+    (
+        "hianime:get_anime_characters",
+        &["anime_characters", "pagination"],
+    ),
+    ("hianime:get_character", &["character"]),
+    ("hianime:get_person", &["person", "pagination"]),
+];
+
+static DEFAULT_TABLE: Lazy<SelectorTable> = Lazy::new(SelectorTable::default);
+
+impl Scraper {
+    /// names of the parsers [`Scraper::bench_parse_page`] runs
+    #[doc(hidden)]
+    pub fn bench_page_parsers() -> impl Iterator<Item = &'static str> {
+        return PAGE_PARSERS.iter().map(|(parser, _)| *parser);
+    }
+
+    /// runs `parser`, e.g. `hianime:get_home_page`, over `page` with the
+    /// same arguments as [`Scraper::self_test_fixtures`], returning whether
+    /// it parsed
+    #[doc(hidden)]
+    pub fn bench_parse_page(&self, parser: &str, page: &str) -> bool {
+        let anime_id = || AnimeId::new("one-piece", 100).unwrap();
+
+        return match parser {
+            "hianime:get_home_page" => self.parse_home_page(page).is_ok(),
+            "hianime:get_az_list" => self.parse_az_list(page, "all", 1).is_ok(),
+            "hianime:get_category_anime" => {
+                self.parse_category_anime(page, "most-popular", 1).is_ok()
+            }
+            "hianime:get_genre_anime" => self.parse_genre_anime(page, "action", 1).is_ok(),
+            "hianime:get_producer_anime" => {
+                self.parse_producer_anime(page, "toei-animation", 1).is_ok()
+            }
+            "hianime:get_search_results" => self
+                .parse_search_results(page, "monster", 1, SearchFilters::default())
+                .is_ok(),
+            "hianime:get_search_suggestions" => self.parse_search_suggestions(page).is_ok(),
+            "hianime:get_info" => self.parse_info(page).is_ok(),
+            "hianime:get_qtip_info" => self.parse_qtip_info(page).is_ok(),
+            "hianime:get_anime_episodes" => self.parse_anime_episodes(page).is_ok(),
+            "hianime:get_episode_servers" => self
+                .parse_episode_servers(page, EpisodeId::new(anime_id(), 2055))
+                .is_ok(),
+            "hianime:get_schedule" => self.parse_schedule(page, "2024-01-01").is_ok(),
+            "hianime:get_anime_characters" => {
+                self.parse_anime_characters(page, anime_id(), 1).is_ok()
+            }
+            "hianime:get_character" => self.parse_character(page, "monkey-d-luffy-3").is_ok(),
+            "hianime:get_person" => self.parse_person(page, "mayumi-tanaka-4").is_ok(),
+            _ => false,
+        };
+    }
+
+    /// parses the default selectors `parser` selects with, what the parser
+    /// paid on every call when it parsed its selectors inline
+    #[doc(hidden)]
+    pub fn bench_parse_inline_selectors(parser: &str) {
+        let sections = PAGE_PARSERS
+            .iter()
+            .find(|(name, _)| *name == parser)
+            .map(|(_, sections)| *sections)
+            .unwrap_or_default();
+
+        for section in sections {
+            DEFAULT_TABLE
+                .compile_section(section)
+                .expect("built-in selectors are valid");
+        }
+    }
+}
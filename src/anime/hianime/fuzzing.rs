@@ -0,0 +1,49 @@
+//! entry points of the cargo-fuzz targets under `fuzz/`, only built when
+//! compiling with `--cfg fuzzing`, which `cargo fuzz` passes, or for tests
+
+use super::{AnimeId, EpisodeId, Router, Scraper, SearchFilter, SearchFilters};
+
+impl Scraper {
+    /// runs every page parser over `page`, whatever the markup none of
+    /// them may panic
+    #[doc(hidden)]
+    pub fn fuzz_parse_page(&self, page: &str) {
+        let _ = self.parse_home_page(page);
+        let _ = self.parse_az_list(page, "all", 1);
+        let _ = self.parse_category_anime(page, "tv", 1);
+        let _ = self.parse_genre_anime(page, "action", 1);
+        let _ = self.parse_producer_anime(page, "toei-animation", 1);
+        let _ = self.parse_search_results(page, "monster", 1, SearchFilters::default());
+        let _ = self.parse_search_suggestions(page);
+        let _ = self.parse_info(page);
+        let _ = self.parse_qtip_info(page);
+        let _ = self.parse_anime_episodes(page);
+        let _ = self.parse_episode_servers(
+            page,
+            EpisodeId::new(AnimeId::new("one-piece", 100).unwrap(), 1),
+        );
+        let _ = self.parse_schedule(page, "2024-01-01");
+        let _ = self.parse_anime_characters(page, AnimeId::new("one-piece", 100).unwrap(), 1);
+        let _ = self.parse_character(page, "monkey-d-luffy-3");
+        let _ = self.parse_person(page, "mayumi-tanaka-4");
+    }
+
+    /// treats `input` as every kind of user provided id, href and search
+    /// filter the scraper accepts
+    #[doc(hidden)]
+    pub fn fuzz_parse_input(input: &str) {
+        let _ = input.parse::<AnimeId>();
+        let _ = input.parse::<EpisodeId>();
+        let _ = AnimeId::from_href(input);
+        let _ = EpisodeId::from_href(input);
+        let _ = Router::default().parse(input);
+
+        let filters = SearchFilters::new(
+            ["type", "start_date", "end_date", "sort", "genres"]
+                .into_iter()
+                .map(|key| SearchFilter::from_raw(key, input.to_string()))
+                .collect(),
+        );
+        let _ = filters.to_query_params();
+    }
+}
@@ -0,0 +1,714 @@
+use crate::{
+    anime::hianime::{
+        parsers::{
+            ScrapedAZList, ScrapedAnimeCharacters, ScrapedAnimeEpisodes, ScrapedAnimeInfo,
+            ScrapedCategoryAnime, ScrapedCharacterInfo, ScrapedEpisodeServers, ScrapedGenreAnime,
+            ScrapedHomePage, ScrapedPersonInfo, ScrapedProducerAnime, ScrapedQtipInfo,
+            ScrapedSchedule, ScrapedSearchResult, ScrapedSearchSuggestion,
+        },
+        Scraper,
+    },
+    EnmaResult, SchemaVersion,
+};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+/// anime, episode, character and person ids the live [`Scraper::self_test`]
+/// runs the detail parsers with, long running entries unlikely to be taken down
+const SELF_TEST_ANIME_ID: &'static str = "one-piece-100";
+const SELF_TEST_EPISODE_ID: &'static str = "steinsgate-0-92?ep=2055";
+const SELF_TEST_CHARACTER_ID: &'static str = "monkey-d-luffy-3";
+const SELF_TEST_PERSON_ID: &'static str = "mayumi-tanaka-4";
+const SELF_TEST_QUERY: &'static str = "monster";
+/// date the schedule fixture is parsed as
+const SELF_TEST_DATE: &'static str = "2024-01-01";
+
+/// what a parser's result has to look like on a page the parser still
+/// understands
+pub(crate) trait Health {
+    /// item counts of the fields filled by a selector that matches on every
+    /// healthy page
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![]
+    }
+
+    /// fields the parser must always fill, and whether it did
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![]
+    }
+}
+
+/// structured result of [`Scraper::self_test`], one [`ParserReport`] per
+/// checked parser
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct SelfTestReport {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
+    pub parsers: Vec<ParserReport>,
+}
+
+impl SelfTestReport {
+    /// whether every parser understood its page
+    pub fn is_healthy(&self) -> bool {
+        self.parsers.iter().all(|p| p.is_healthy())
+    }
+
+    /// reports of the parsers that errored or came back with empty fields
+    pub fn unhealthy(&self) -> impl Iterator<Item = &ParserReport> {
+        self.parsers.iter().filter(|p| !p.is_healthy())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ParserReport {
+    /// e.g. `hianime:get_home_page`
+    pub parser: String,
+    /// source page or fixture file the parser ran against
+    pub page: String,
+    /// set when the page couldn't be fetched, read or parsed at all
+    pub error: Option<String>,
+    /// fields whose selector matched no element of the page
+    pub empty_selectors: Vec<String>,
+    /// required fields that came back empty
+    pub empty_fields: Vec<String>,
+}
+
+impl ParserReport {
+    fn new<T: Health>(parser: &'static str, page: String, result: EnmaResult<T>) -> Self {
+        let mut report = ParserReport {
+            parser: parser.to_string(),
+            page,
+            ..Default::default()
+        };
+
+        match result {
+            Ok(data) => {
+                report.empty_selectors = empty_selectors(&data)
+                    .into_iter()
+                    .map(String::from)
+                    .collect();
+                report.empty_fields = empty_fields(&data).into_iter().map(String::from).collect();
+            }
+            Err(e) => report.error = Some(e.to_string()),
+        }
+
+        return report;
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.error.is_none() && self.empty_selectors.is_empty() && self.empty_fields.is_empty()
+    }
+}
+
+pub(crate) fn empty_selectors<T: Health>(data: &T) -> Vec<&'static str> {
+    return data
+        .selector_counts()
+        .into_iter()
+        .filter_map(|(field, count)| (count == 0).then_some(field))
+        .collect();
+}
+
+pub(crate) fn empty_fields<T: Health>(data: &T) -> Vec<&'static str> {
+    return data
+        .required_fields()
+        .into_iter()
+        .filter_map(|(field, filled)| (!filled).then_some(field))
+        .collect();
+}
+
+/// whether every item has the field checked by `filled`, vacuously true for
+/// no items as those are covered by [`Health::selector_counts`]
+fn all<T>(items: &[T], filled: impl Fn(&T) -> bool) -> bool {
+    items.iter().all(filled)
+}
+
+fn filled(value: &Option<String>) -> bool {
+    value.as_deref().is_some_and(|s| !s.trim().is_empty())
+}
+
+impl Scraper {
+    /// runs every page parser against the live site and reports, per parser,
+    /// which selectors matched nothing and which required fields came back
+    /// empty, meant to be run as a canary that alerts once the source's
+    /// markup changes
+    ///
+    /// the composite parsers, like [`Scraper::get_franchise`], are left out
+    /// as they're built on top of the checked ones
+    ///
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::hianime;
+    ///     let hianime = hianime::Scraper::new();
+    ///
+    ///     let report = hianime.self_test().await;
+    ///     for parser in report.unhealthy() {
+    ///         eprintln!("{parser:#?}");
+    ///     }
+    /// }
+    ///  ```
+    pub async fn self_test(&self) -> SelfTestReport {
+        let date = chrono::Utc::now().date_naive();
+        let (year, month, day) = (
+            chrono::Datelike::year(&date) as u16,
+            chrono::Datelike::month(&date) as u8,
+            chrono::Datelike::day(&date) as u8,
+        );
+
+        let parsers = vec![
+            ParserReport::new(
+                "hianime:get_home_page",
+                String::from("home"),
+                self.get_home_page().await,
+            ),
+            ParserReport::new(
+                "hianime:get_az_list",
+                String::from("az-list/all"),
+                self.get_az_list("all", None).await,
+            ),
+            ParserReport::new(
+                "hianime:get_category_anime",
+                String::from("most-popular"),
+                self.get_category_anime("most-popular", None).await,
+            ),
+            ParserReport::new(
+                "hianime:get_genre_anime",
+                String::from("genre/action"),
+                self.get_genre_anime("action", None).await,
+            ),
+            ParserReport::new(
+                "hianime:get_producer_anime",
+                String::from("producer/toei-animation"),
+                self.get_producer_anime("toei-animation", None).await,
+            ),
+            ParserReport::new(
+                "hianime:get_search_results",
+                format!("search?keyword={SELF_TEST_QUERY}"),
+                self.get_search_results(SELF_TEST_QUERY, None, None).await,
+            ),
+            ParserReport::new(
+                "hianime:get_search_suggestions",
+                format!("ajax/search/suggest?keyword={SELF_TEST_QUERY}"),
+                self.get_search_suggestions(SELF_TEST_QUERY).await,
+            ),
+            ParserReport::new(
+                "hianime:get_info",
+                String::from(SELF_TEST_ANIME_ID),
+                self.get_info(SELF_TEST_ANIME_ID).await,
+            ),
+            ParserReport::new(
+                "hianime:get_qtip_info",
+                format!("ajax/movie/qtip/{SELF_TEST_ANIME_ID}"),
+                self.get_qtip_info(SELF_TEST_ANIME_ID).await,
+            ),
+            ParserReport::new(
+                "hianime:get_anime_episodes",
+                format!("ajax/v2/episode/list/{SELF_TEST_ANIME_ID}"),
+                self.get_anime_episodes(SELF_TEST_ANIME_ID).await,
+            ),
+            ParserReport::new(
+                "hianime:get_episode_servers",
+                format!("ajax/v2/episode/servers/{SELF_TEST_EPISODE_ID}"),
+                self.get_episode_servers(SELF_TEST_EPISODE_ID).await,
+            ),
+            ParserReport::new(
+                "hianime:get_schedule",
+                format!("ajax/schedule/list?date={year}-{month:02}-{day:02}"),
+                self.get_schedule(year, month, day).await,
+            ),
+            ParserReport::new(
+                "hianime:get_anime_characters",
+                format!("ajax/character/list/{SELF_TEST_ANIME_ID}"),
+                self.get_anime_characters(SELF_TEST_ANIME_ID, None).await,
+            ),
+            ParserReport::new(
+                "hianime:get_character",
+                format!("character/{SELF_TEST_CHARACTER_ID}"),
+                self.get_character(SELF_TEST_CHARACTER_ID).await,
+            ),
+            ParserReport::new(
+                "hianime:get_person",
+                format!("people/{SELF_TEST_PERSON_ID}"),
+                self.get_person(SELF_TEST_PERSON_ID).await,
+            ),
+        ];
+
+        return SelfTestReport {
+            schema_version: SchemaVersion::CURRENT,
+            parsers,
+        };
+    }
+
+    /// same as [`Scraper::self_test`] but against pages saved in `dir`, one
+    /// `<parser>.html` file per parser named after it without the
+    /// `hianime:get_` prefix, e.g. `home_page.html` or `info.html`
+    ///
+    /// the ajax parsers expect the `html` field of the json the source
+    /// responds with, a parser whose file is missing is reported with an error
+    ///
+    /// runs with the scraper's selectors, so a patched [`super::SelectorTable`]
+    /// can be checked against the saved pages before it's rolled out
+    pub fn self_test_fixtures(&self, dir: impl AsRef<Path>) -> SelfTestReport {
+        let dir = dir.as_ref();
+
+        fn check<T: Health>(
+            dir: &Path,
+            parser: &'static str,
+            parse: impl Fn(&str) -> EnmaResult<T>,
+        ) -> ParserReport {
+            let name = parser.trim_start_matches("hianime:get_");
+            let path = dir.join(format!("{name}.html"));
+
+            let result = match std::fs::read_to_string(&path) {
+                Ok(page) => parse(&page),
+                Err(e) => {
+                    return ParserReport {
+                        parser: parser.to_string(),
+                        page: path.display().to_string(),
+                        error: Some(format!("couldn't read fixture: {e}")),
+                        ..Default::default()
+                    }
+                }
+            };
+
+            return ParserReport::new(parser, path.display().to_string(), result);
+        }
+
+        let parsers = vec![
+            check(dir, "hianime:get_home_page", |page| {
+                self.parse_home_page(page)
+            }),
+            check(dir, "hianime:get_az_list", |page| {
+                self.parse_az_list(page, "all", 1)
+            }),
+            check(dir, "hianime:get_category_anime", |page| {
+                self.parse_category_anime(page, "most-popular", 1)
+            }),
+            check(dir, "hianime:get_genre_anime", |page| {
+                self.parse_genre_anime(page, "action", 1)
+            }),
+            check(dir, "hianime:get_producer_anime", |page| {
+                self.parse_producer_anime(page, "toei-animation", 1)
+            }),
+            check(dir, "hianime:get_search_results", |page| {
+                self.parse_search_results(page, SELF_TEST_QUERY, 1, Default::default())
+            }),
+            check(dir, "hianime:get_search_suggestions", |page| {
+                self.parse_search_suggestions(page)
+            }),
+            check(dir, "hianime:get_info", |page| self.parse_info(page)),
+            check(dir, "hianime:get_qtip_info", |page| {
+                self.parse_qtip_info(page)
+            }),
+            check(dir, "hianime:get_anime_episodes", |page| {
+                self.parse_anime_episodes(page)
+            }),
+            check(dir, "hianime:get_episode_servers", |page| {
+                let episode_id = SELF_TEST_EPISODE_ID.parse().expect("valid episode id");
+                self.parse_episode_servers(page, episode_id)
+            }),
+            check(dir, "hianime:get_schedule", |html| {
+                self.parse_schedule(html, SELF_TEST_DATE)
+            }),
+            check(dir, "hianime:get_anime_characters", |page| {
+                let anime_id = SELF_TEST_ANIME_ID.parse().expect("valid anime id");
+                self.parse_anime_characters(page, anime_id, 1)
+            }),
+            check(dir, "hianime:get_character", |page| {
+                self.parse_character(page, SELF_TEST_CHARACTER_ID)
+            }),
+            check(dir, "hianime:get_person", |page| {
+                self.parse_person(page, SELF_TEST_PERSON_ID)
+            }),
+        ];
+
+        return SelfTestReport {
+            schema_version: SchemaVersion::CURRENT,
+            parsers,
+        };
+    }
+}
+
+impl Health for ScrapedHomePage {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("spotlight_animes", self.spotlight_animes.len()),
+            ("trending_animes", self.trending_animes.len()),
+            ("latest_episode_animes", self.latest_episode_animes.len()),
+            ("top_upcoming_animes", self.top_upcoming_animes.len()),
+            ("genres", self.genres.len()),
+            ("top10_animes", self.top10_animes.today.len()),
+            ("top_airing_animes", self.top_airing_animes.len()),
+            ("most_popular_animes", self.most_popular_animes.len()),
+            ("most_favorite_animes", self.most_favorite_animes.len()),
+            (
+                "latest_completed_animes",
+                self.latest_completed_animes.len(),
+            ),
+        ]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            (
+                "spotlight_animes[].id",
+                all(&self.spotlight_animes, |a| a.id.is_some()),
+            ),
+            (
+                "spotlight_animes[].name",
+                all(&self.spotlight_animes, |a| filled(&a.name)),
+            ),
+            (
+                "trending_animes[].id",
+                all(&self.trending_animes, |a| a.id.is_some()),
+            ),
+            (
+                "latest_episode_animes[].id",
+                all(&self.latest_episode_animes, |a| a.id.is_some()),
+            ),
+            (
+                "latest_episode_animes[].name",
+                all(&self.latest_episode_animes, |a| filled(&a.name)),
+            ),
+            (
+                "top10_animes.today[].id",
+                all(&self.top10_animes.today, |a| a.id.is_some()),
+            ),
+        ]
+    }
+}
+
+impl Health for ScrapedAZList {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![("animes", self.animes.len())]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("animes[].id", all(&self.animes, |a| a.id.is_some())),
+            ("animes[].name", all(&self.animes, |a| filled(&a.name))),
+        ]
+    }
+}
+
+impl Health for ScrapedCategoryAnime {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("animes", self.animes.len()),
+            ("genres", self.genres.len()),
+            ("top10_animes", self.top10_animes.today.len()),
+        ]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("category_name", !self.category_name.trim().is_empty()),
+            ("animes[].id", all(&self.animes, |a| a.id.is_some())),
+            ("animes[].name", all(&self.animes, |a| filled(&a.name))),
+        ]
+    }
+}
+
+impl Health for ScrapedGenreAnime {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("animes", self.animes.len()),
+            ("genres", self.genres.len()),
+            ("top_airing_animes", self.top_airing_animes.len()),
+        ]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("genre_name", !self.genre_name.trim().is_empty()),
+            ("animes[].id", all(&self.animes, |a| a.id.is_some())),
+            ("animes[].name", all(&self.animes, |a| filled(&a.name))),
+        ]
+    }
+}
+
+impl Health for ScrapedProducerAnime {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("animes", self.animes.len()),
+            ("top10_animes", self.top10_animes.today.len()),
+            ("top_airing_animes", self.top_airing_animes.len()),
+        ]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("producer_name", !self.producer_name.trim().is_empty()),
+            ("animes[].id", all(&self.animes, |a| a.id.is_some())),
+            ("animes[].name", all(&self.animes, |a| filled(&a.name))),
+        ]
+    }
+}
+
+impl Health for ScrapedSearchResult {
+    // a search can legitimately find nothing, the sidebar is always there
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![("most_popular_animes", self.most_popular_animes.len())]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("animes[].id", all(&self.animes, |a| a.id.is_some())),
+            ("animes[].name", all(&self.animes, |a| filled(&a.name))),
+            (
+                "most_popular_animes[].id",
+                all(&self.most_popular_animes, |a| a.id.is_some()),
+            ),
+        ]
+    }
+}
+
+impl Health for ScrapedSearchSuggestion {
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            (
+                "suggestions[].id",
+                all(&self.suggestions, |a| a.id.is_some()),
+            ),
+            (
+                "suggestions[].name",
+                all(&self.suggestions, |a| filled(&a.name)),
+            ),
+        ]
+    }
+}
+
+impl Health for ScrapedAnimeInfo {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![
+            ("other_info", self.anime.other_info.len()),
+            ("most_popular_animes", self.most_popular_animes.len()),
+        ]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("anime.id", self.anime.id.is_some()),
+            ("anime.name", filled(&self.anime.name)),
+            ("anime.poster", filled(&self.anime.poster)),
+            ("anime.description", filled(&self.anime.description)),
+        ]
+    }
+}
+
+impl Health for ScrapedQtipInfo {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![("genres", self.anime.genres.len())]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("anime.id", self.anime.id.is_some()),
+            ("anime.name", filled(&self.anime.name)),
+        ]
+    }
+}
+
+impl Health for ScrapedAnimeEpisodes {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![("episodes", self.episodes.len())]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            (
+                "episodes[].episode_id",
+                all(&self.episodes, |e| e.episode_id.is_some()),
+            ),
+            (
+                "episodes[].number",
+                all(&self.episodes, |e| e.number.is_some()),
+            ),
+        ]
+    }
+}
+
+impl Health for ScrapedEpisodeServers {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![("servers", self.sub.len() + self.dub.len() + self.raw.len())]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        let servers = self.sub.iter().chain(&self.dub).chain(&self.raw);
+        vec![
+            (
+                "servers[].server_id",
+                servers.clone().all(|s| s.server_id.is_some()),
+            ),
+            (
+                "servers[].server_name",
+                servers.clone().all(|s| filled(&s.server_name)),
+            ),
+        ]
+    }
+}
+
+impl Health for ScrapedSchedule {
+    // a date can legitimately have nothing scheduled
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            ("animes[].id", all(&self.animes, |a| a.id.is_some())),
+            ("animes[].name", all(&self.animes, |a| filled(&a.name))),
+            ("animes[].time", all(&self.animes, |a| filled(&a.time))),
+        ]
+    }
+}
+
+impl Health for ScrapedAnimeCharacters {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![("characters", self.characters.len())]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![
+            (
+                "characters[].character.id",
+                all(&self.characters, |c| filled(&c.character.id)),
+            ),
+            (
+                "characters[].character.name",
+                all(&self.characters, |c| filled(&c.character.name)),
+            ),
+        ]
+    }
+}
+
+impl Health for ScrapedCharacterInfo {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![("animeography", self.character.animeography.len())]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![("character.name", filled(&self.character.name))]
+    }
+}
+
+impl Health for ScrapedPersonInfo {
+    fn selector_counts(&self) -> Vec<(&'static str, usize)> {
+        vec![("voice_acting_roles", self.person.voice_acting_roles.len())]
+    }
+
+    fn required_fields(&self) -> Vec<(&'static str, bool)> {
+        vec![("person.name", filled(&self.person.name))]
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anime::hianime::{ParserReport, Scraper, SelectorTable};
+    use std::path::PathBuf;
+
+    fn fixtures_dir() -> PathBuf {
+        PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("fixtures/hianime")
+    }
+
+    fn parser<'a>(parsers: &'a [ParserReport], name: &str) -> &'a ParserReport {
+        parsers.iter().find(|p| p.parser == name).unwrap()
+    }
+
+    // cargo test --lib -- anime::hianime::health::test --include-ignored --show-output
+    #[test]
+    fn test_self_test_fixtures() {
+        let report = Scraper::new().self_test_fixtures(fixtures_dir());
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+
+        assert_eq!(report.parsers.len(), 15);
+        assert!(
+            report.is_healthy(),
+            "{:#?}",
+            report.unhealthy().collect::<Vec<_>>()
+        );
+    }
+
+    #[test]
+    fn test_self_test_drifted_markup() {
+        let dir = std::env::temp_dir().join(format!("enma-self-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        for entry in std::fs::read_dir(fixtures_dir()).unwrap() {
+            let path = entry.unwrap().path();
+            std::fs::copy(&path, dir.join(path.file_name().unwrap())).unwrap();
+        }
+
+        // items renamed their class, none of them match anymore
+        let az_list = std::fs::read_to_string(fixtures_dir().join("az_list.html")).unwrap();
+        std::fs::write(
+            dir.join("az_list.html"),
+            az_list.replace("flw-item", "film-item"),
+        )
+        .unwrap();
+
+        // items still match but their links moved
+        let episodes = std::fs::read_to_string(fixtures_dir().join("anime_episodes.html")).unwrap();
+        std::fs::write(
+            dir.join("anime_episodes.html"),
+            episodes.replace("href=", "data-href="),
+        )
+        .unwrap();
+
+        // the page is gone
+        std::fs::remove_file(dir.join("info.html")).unwrap();
+
+        let report = Scraper::new().self_test_fixtures(&dir);
+
+        // hot patching the selector picks the renamed items up again
+        let mut table = SelectorTable::default();
+        table.az_list.animes = String::from("#main-wrapper .film_list-wrap .film-item");
+        let patched = Scraper::new()
+            .with_selectors(table)
+            .unwrap()
+            .self_test_fixtures(&dir);
+        std::fs::remove_dir_all(&dir).unwrap();
+
+        assert!(parser(&patched.parsers, "hianime:get_az_list").is_healthy());
+
+        let az_list = parser(&report.parsers, "hianime:get_az_list");
+        assert_eq!(az_list.error, None);
+        assert_eq!(az_list.empty_selectors, vec!["animes"]);
+        assert!(az_list.empty_fields.is_empty());
+
+        let episodes = parser(&report.parsers, "hianime:get_anime_episodes");
+        assert!(episodes.empty_selectors.is_empty());
+        assert_eq!(episodes.empty_fields, vec!["episodes[].episode_id"]);
+
+        let info = parser(&report.parsers, "hianime:get_info");
+        assert!(info
+            .error
+            .as_ref()
+            .unwrap()
+            .contains("couldn't read fixture"));
+
+        // every other parser still understands its page
+        let unhealthy = report
+            .unhealthy()
+            .map(|p| p.parser.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            unhealthy,
+            vec![
+                "hianime:get_az_list",
+                "hianime:get_info",
+                "hianime:get_anime_episodes"
+            ]
+        );
+    }
+
+    #[tokio::test]
+    #[ignore = "hits the live site"]
+    async fn test_self_test() {
+        let hianime = Scraper::new();
+        let report = hianime.self_test().await;
+
+        println!("{}", serde_json::to_string_pretty(&report).unwrap());
+        assert_eq!(report.parsers.len(), 15);
+        for p in report.unhealthy() {
+            eprintln!("unhealthy {p:#?}");
+        }
+    }
+}
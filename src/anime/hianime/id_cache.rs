@@ -0,0 +1,151 @@
+use super::AnimeId;
+use serde::{Deserialize, Serialize};
+use std::sync::Mutex;
+#[cfg(feature = "cache")]
+use std::{fs, path::PathBuf};
+#[cfg(feature = "cache")]
+use tokio::sync::Mutex as AsyncMutex;
+
+/// a hianime anime id along with its MyAnimeList and AniList ids
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub struct IdMapping {
+    pub anime_id: AnimeId,
+    pub mal_id: Option<u32>,
+    pub anilist_id: Option<u32>,
+}
+
+/// remembers resolved id mappings, optionally persisting them to a json file
+/// so that they survive restarts when the `cache` feature is enabled
+#[derive(Debug, Default)]
+pub(crate) struct IdMappingCache {
+    #[cfg(feature = "cache")]
+    path: Option<PathBuf>,
+    mappings: Mutex<Vec<IdMapping>>,
+    /// serializes the writes of the file, so that an older snapshot never
+    /// overwrites a newer one
+    #[cfg(feature = "cache")]
+    file_lock: AsyncMutex<()>,
+}
+
+impl IdMappingCache {
+    /// loads previously persisted mappings from `path`, a missing or
+    /// unreadable file simply starts an empty cache
+    #[cfg(feature = "cache")]
+    pub fn from_file(path: PathBuf) -> Self {
+        let mappings = fs::read_to_string(&path)
+            .ok()
+            .and_then(|s| serde_json::from_str::<Vec<IdMapping>>(&s).ok())
+            .unwrap_or_default();
+
+        return IdMappingCache {
+            path: Some(path),
+            mappings: Mutex::new(mappings),
+            file_lock: AsyncMutex::new(()),
+        };
+    }
+
+    pub fn find_by_mal_id(&self, mal_id: u32) -> Option<IdMapping> {
+        self.find(|m| m.mal_id == Some(mal_id))
+    }
+
+    pub fn find_by_anilist_id(&self, anilist_id: u32) -> Option<IdMapping> {
+        self.find(|m| m.anilist_id == Some(anilist_id))
+    }
+
+    pub fn find_by_anime_id(&self, anime_id: &AnimeId) -> Option<IdMapping> {
+        self.find(|m| m.anime_id == *anime_id)
+    }
+
+    fn find(&self, predicate: impl Fn(&IdMapping) -> bool) -> Option<IdMapping> {
+        let mappings = self.mappings.lock().unwrap_or_else(|e| e.into_inner());
+        return mappings.iter().find(|m| predicate(m)).cloned();
+    }
+
+    /// inserts or replaces the mapping of `mapping.anime_id`
+    pub async fn insert(&self, mapping: IdMapping) {
+        {
+            let mut mappings = self.mappings.lock().unwrap_or_else(|e| e.into_inner());
+
+            match mappings.iter_mut().find(|m| m.anime_id == mapping.anime_id) {
+                Some(existing) if *existing == mapping => return,
+                Some(existing) => *existing = mapping,
+                None => mappings.push(mapping),
+            }
+        }
+
+        #[cfg(feature = "cache")]
+        self.persist().await;
+    }
+
+    /// writes the mappings to the cache file, outside of the mappings lock;
+    /// failing to persist only costs a rescrape later on, so it's logged
+    /// rather than returned
+    #[cfg(feature = "cache")]
+    async fn persist(&self) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let _file_guard = self.file_lock.lock().await;
+
+        let json = {
+            let mappings = self.mappings.lock().unwrap_or_else(|e| e.into_inner());
+            serde_json::to_string_pretty(&*mappings)
+        };
+        let written = match json {
+            Ok(json) => tokio::fs::write(path, json).await,
+            Err(e) => Err(e.into()),
+        };
+        if let Err(e) = written {
+            tracing::warn!(path = %path.display(), error = %e, "failed to persist the id cache");
+        }
+    }
+}
+
+#[cfg(all(test, feature = "cache"))]
+mod test {
+    use super::{IdMapping, IdMappingCache};
+    use tracing_test::traced_test;
+
+    // cargo test --features cache --lib -- anime::hianime::id_cache::test --show-output
+    #[tokio::test]
+    async fn test_id_mapping_cache_persists() {
+        let path = std::env::temp_dir().join(format!("enma-id-cache-{}.json", std::process::id()));
+        let _ = std::fs::remove_file(&path);
+
+        let cache = IdMappingCache::from_file(path.clone());
+        cache
+            .insert(IdMapping {
+                anime_id: "one-piece-100".parse().unwrap(),
+                mal_id: Some(21),
+                anilist_id: Some(21),
+            })
+            .await;
+
+        let cache = IdMappingCache::from_file(path.clone());
+        assert_eq!(
+            cache.find_by_mal_id(21).map(|m| m.anime_id.to_string()),
+            Some(String::from("one-piece-100"))
+        );
+        assert_eq!(cache.find_by_anilist_id(22), None);
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[tokio::test]
+    #[traced_test]
+    async fn test_id_mapping_cache_write_failure() {
+        // a directory can't be written as a file
+        let path = std::env::temp_dir();
+        let cache = IdMappingCache::from_file(path.clone());
+        cache
+            .insert(IdMapping {
+                anime_id: "one-piece-100".parse().unwrap(),
+                mal_id: Some(21),
+                anilist_id: None,
+            })
+            .await;
+
+        assert!(cache.find_by_mal_id(21).is_some());
+        assert!(logs_contain("failed to persist the id cache"));
+    }
+}
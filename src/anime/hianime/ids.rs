@@ -0,0 +1,290 @@
+use crate::EnmaError;
+use reqwest::StatusCode;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{fmt, str::FromStr};
+use thiserror::Error as ThisError;
+
+const EPISODE_QUERY_PREFIX: &str = "?ep=";
+
+/// error returned when a hianime anime or episode id is malformed
+#[derive(Debug, Clone, PartialEq, Eq, ThisError)]
+pub enum IdParseError {
+    #[error("id is empty")]
+    Empty,
+
+    #[error("invalid anime id '{0}', expected `<slug>-<numeric id>` like `one-piece-100`")]
+    MissingNumericId(String),
+
+    #[error("invalid anime id '{0}', the slug may only contain ascii letters, digits and `-`")]
+    InvalidSlug(String),
+
+    #[error("invalid episode id '{0}', expected `<anime id>?ep=<episode>` like `steinsgate-0-92?ep=2055`")]
+    MissingEpisode(String),
+
+    #[error("invalid episode id '{0}', the episode must be a number")]
+    InvalidEpisode(String),
+}
+
+impl IdParseError {
+    pub(crate) fn into_enma_error(self, provider_parser: &'static str) -> EnmaError {
+        EnmaError::invalid_data_error(
+            provider_parser,
+            Some(self.to_string()),
+            Some(StatusCode::BAD_REQUEST),
+        )
+    }
+}
+
+/// a hianime anime id like `one-piece-100`, made of a slug and the numeric id
+/// the source's ajax endpoints are keyed by
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct AnimeId {
+    slug: String,
+    numeric_id: u32,
+}
+
+impl AnimeId {
+    pub fn new(slug: impl Into<String>, numeric_id: u32) -> Result<Self, IdParseError> {
+        let slug = slug.into();
+        if !Self::is_valid_slug(&slug) {
+            return Err(IdParseError::InvalidSlug(format!("{slug}-{numeric_id}")));
+        }
+        return Ok(AnimeId { slug, numeric_id });
+    }
+
+    /// e.g. `one-piece` of `one-piece-100`
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+
+    /// e.g. `100` of `one-piece-100`
+    pub fn numeric_id(&self) -> u32 {
+        self.numeric_id
+    }
+
+    /// parses anime links of the source like `/one-piece-100?ref=search`
+    /// or `/watch/one-piece-100?ep=2142`
+    pub(crate) fn from_href(href: &str) -> Option<Self> {
+        let path = Self::href_path(href);
+        return path.split(['?', '#']).next()?.parse().ok();
+    }
+
+    fn href_path(href: &str) -> &str {
+        let href = href.trim();
+        let href = href
+            .find("://")
+            .and_then(|idx| href[idx + 3..].find('/').map(|end| &href[idx + 3 + end..]))
+            .unwrap_or(href);
+
+        let href = href.trim_start_matches('/');
+        return href.strip_prefix("watch/").unwrap_or(href);
+    }
+
+    fn is_valid_slug(slug: &str) -> bool {
+        !slug.is_empty()
+            && !slug.starts_with('-')
+            && !slug.ends_with('-')
+            && slug.chars().all(|c| c.is_ascii_alphanumeric() || c == '-')
+    }
+}
+
+impl FromStr for AnimeId {
+    type Err = IdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(IdParseError::Empty);
+        }
+
+        let Some((slug, numeric_id)) = s.rsplit_once('-') else {
+            return Err(IdParseError::MissingNumericId(s.to_string()));
+        };
+        if numeric_id.is_empty() || !numeric_id.chars().all(|c| c.is_ascii_digit()) {
+            return Err(IdParseError::MissingNumericId(s.to_string()));
+        }
+        let numeric_id = numeric_id
+            .parse::<u32>()
+            .map_err(|_| IdParseError::MissingNumericId(s.to_string()))?;
+
+        if !Self::is_valid_slug(slug) {
+            return Err(IdParseError::InvalidSlug(s.to_string()));
+        }
+
+        return Ok(AnimeId {
+            slug: slug.to_string(),
+            numeric_id,
+        });
+    }
+}
+
+impl fmt::Display for AnimeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}-{}", self.slug, self.numeric_id)
+    }
+}
+
+/// a hianime episode id like `steinsgate-0-92?ep=2055`, where `2055` is the
+/// source's id of the episode, not its number
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct EpisodeId {
+    anime: AnimeId,
+    episode: u32,
+}
+
+impl EpisodeId {
+    pub fn new(anime: AnimeId, episode: u32) -> Self {
+        EpisodeId { anime, episode }
+    }
+
+    pub fn anime(&self) -> &AnimeId {
+        &self.anime
+    }
+
+    /// the source's id of the episode, e.g. `2055` of `steinsgate-0-92?ep=2055`
+    pub fn episode(&self) -> u32 {
+        self.episode
+    }
+
+    /// parses episode links of the source like `/watch/steinsgate-0-92?ep=2055`
+    pub(crate) fn from_href(href: &str) -> Option<Self> {
+        AnimeId::href_path(href).parse().ok()
+    }
+}
+
+impl FromStr for EpisodeId {
+    type Err = IdParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(IdParseError::Empty);
+        }
+
+        let Some((anime, episode)) = s.split_once(EPISODE_QUERY_PREFIX) else {
+            return Err(IdParseError::MissingEpisode(s.to_string()));
+        };
+        if episode.is_empty() || !episode.chars().all(|c| c.is_ascii_digit()) {
+            return Err(IdParseError::InvalidEpisode(s.to_string()));
+        }
+        let episode = episode
+            .parse::<u32>()
+            .map_err(|_| IdParseError::InvalidEpisode(s.to_string()))?;
+
+        return Ok(EpisodeId {
+            anime: anime.parse()?,
+            episode,
+        });
+    }
+}
+
+impl fmt::Display for EpisodeId {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}{EPISODE_QUERY_PREFIX}{}", self.anime, self.episode)
+    }
+}
+
+// both ids (de)serialize as their string form, e.g. `"one-piece-100"`
+macro_rules! impl_id_serde {
+    ($id:ty, $pattern:literal) => {
+        impl Serialize for $id {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $id {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                let s = String::deserialize(deserializer)?;
+                s.parse().map_err(de::Error::custom)
+            }
+        }
+
+        #[cfg(feature = "schema")]
+        impl schemars::JsonSchema for $id {
+            fn schema_name() -> std::borrow::Cow<'static, str> {
+                stringify!($id).into()
+            }
+
+            fn json_schema(_: &mut schemars::SchemaGenerator) -> schemars::Schema {
+                schemars::json_schema!({ "type": "string", "pattern": $pattern })
+            }
+        }
+    };
+}
+
+impl_id_serde!(AnimeId, "^[A-Za-z0-9]([A-Za-z0-9-]*[A-Za-z0-9])?-[0-9]+$");
+impl_id_serde!(
+    EpisodeId,
+    "^[A-Za-z0-9]([A-Za-z0-9-]*[A-Za-z0-9])?-[0-9]+\\?ep=[0-9]+$"
+);
+
+#[cfg(test)]
+mod test {
+    use super::{AnimeId, EpisodeId, IdParseError};
+
+    // cargo test --lib -- anime::hianime::ids::test --show-output
+    #[test]
+    fn test_anime_id() {
+        let id = "steinsgate-0-92".parse::<AnimeId>().unwrap();
+        assert_eq!(id.slug(), "steinsgate-0");
+        assert_eq!(id.numeric_id(), 92);
+        assert_eq!(id.to_string(), "steinsgate-0-92");
+
+        assert_eq!("".parse::<AnimeId>(), Err(IdParseError::Empty));
+        for invalid in [
+            "one-piece",
+            "100",
+            "-100",
+            "one piece-100",
+            "one-piece-+100",
+        ] {
+            assert!(invalid.parse::<AnimeId>().is_err(), "{invalid}");
+        }
+
+        assert_eq!(
+            AnimeId::from_href("/watch/one-piece-100?ref=search"),
+            AnimeId::new("one-piece", 100).ok()
+        );
+        assert_eq!(
+            AnimeId::from_href("https://hianime.to/one-piece-100"),
+            AnimeId::new("one-piece", 100).ok()
+        );
+    }
+
+    #[test]
+    fn test_episode_id() {
+        let id = "steinsgate-0-92?ep=2055".parse::<EpisodeId>().unwrap();
+        assert_eq!(id.anime().to_string(), "steinsgate-0-92");
+        assert_eq!(id.episode(), 2055);
+        assert_eq!(id.to_string(), "steinsgate-0-92?ep=2055");
+
+        assert!(matches!(
+            "steinsgate-0-92".parse::<EpisodeId>(),
+            Err(IdParseError::MissingEpisode(_))
+        ));
+        assert!(matches!(
+            "steinsgate-0-92?ep=".parse::<EpisodeId>(),
+            Err(IdParseError::InvalidEpisode(_))
+        ));
+        assert!(matches!(
+            "steinsgate?ep=2055".parse::<EpisodeId>(),
+            Err(IdParseError::MissingNumericId(_))
+        ));
+
+        assert_eq!(
+            EpisodeId::from_href("/watch/steinsgate-0-92?ep=2055"),
+            Some(id)
+        );
+    }
+
+    #[test]
+    fn test_id_serde() {
+        let id = "one-piece-100?ep=2142".parse::<EpisodeId>().unwrap();
+        let json = serde_json::to_string(&id).unwrap();
+        assert_eq!(json, r#""one-piece-100?ep=2142""#);
+        assert_eq!(serde_json::from_str::<EpisodeId>(&json).unwrap(), id);
+
+        assert!(serde_json::from_str::<AnimeId>(r#""one-piece""#).is_err());
+    }
+}
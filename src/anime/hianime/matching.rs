@@ -0,0 +1,196 @@
+use std::{collections::HashSet, hash::Hash};
+
+const ORDINAL_WORDS: [&str; 10] = [
+    "first", "second", "third", "fourth", "fifth", "sixth", "seventh", "eighth", "ninth", "tenth",
+];
+const ROMAN_NUMERALS: [&str; 5] = ["ii", "iii", "iv", "vi", "vii"];
+const ROMAN_NUMERAL_VALUES: [u32; 5] = [2, 3, 4, 6, 7];
+
+/// a title split into its words, with any season marker pulled out
+#[derive(Debug, PartialEq)]
+pub(crate) struct NormalizedTitle {
+    pub words: Vec<String>,
+    pub season: Option<u32>,
+}
+
+/// lowercases `title`, drops punctuation and folds season markers like
+/// `S2`, `Season 2`, `2nd Season`, `Second Season` or a trailing `II`
+pub(crate) fn normalize_title(title: &str) -> NormalizedTitle {
+    let cleaned = title
+        .to_lowercase()
+        .replace('&', " and ")
+        .replace(['\'', '’'], "")
+        .chars()
+        .map(|c| if c.is_alphanumeric() { c } else { ' ' })
+        .collect::<String>();
+    let tokens = cleaned.split_whitespace().collect::<Vec<_>>();
+
+    let mut words = vec![];
+    let mut season = None;
+    let mut idx = 0;
+
+    while idx < tokens.len() {
+        let token = tokens[idx];
+        let next = tokens.get(idx + 1).copied();
+
+        // `s2`, `s02`
+        if let Some(n) = token
+            .strip_prefix('s')
+            .filter(|n| !n.is_empty() && n.len() <= 2)
+            .and_then(|n| n.parse::<u32>().ok())
+        {
+            season = Some(n);
+            idx += 1;
+            continue;
+        }
+
+        // `season 2`, `season two`
+        if token == "season" {
+            if let Some(n) = next.and_then(parse_number) {
+                season = Some(n);
+                idx += 2;
+                continue;
+            }
+        }
+
+        // `2nd season`, `second season`
+        if next == Some("season") {
+            if let Some(n) = parse_ordinal(token) {
+                season = Some(n);
+                idx += 2;
+                continue;
+            }
+        }
+
+        // a trailing `ii`, `iii`...
+        if idx == tokens.len() - 1 && idx > 0 {
+            if let Some(pos) = ROMAN_NUMERALS.iter().position(|r| *r == token) {
+                season = Some(ROMAN_NUMERAL_VALUES[pos]);
+                idx += 1;
+                continue;
+            }
+        }
+
+        words.push(token.to_string());
+        idx += 1;
+    }
+
+    return NormalizedTitle { words, season };
+}
+
+fn parse_number(s: &str) -> Option<u32> {
+    s.parse::<u32>()
+        .ok()
+        .or_else(|| parse_ordinal(s))
+        .or_else(|| {
+            [
+                "one", "two", "three", "four", "five", "six", "seven", "eight", "nine", "ten",
+            ]
+            .iter()
+            .position(|w| *w == s)
+            .map(|pos| pos as u32 + 1)
+        })
+}
+
+fn parse_ordinal(s: &str) -> Option<u32> {
+    if let Some(pos) = ORDINAL_WORDS.iter().position(|w| *w == s) {
+        return Some(pos as u32 + 1);
+    }
+
+    let digits = s
+        .strip_suffix("st")
+        .or(s.strip_suffix("nd"))
+        .or(s.strip_suffix("rd"))
+        .or(s.strip_suffix("th"))?;
+    return digits.parse::<u32>().ok();
+}
+
+/// the title without its season marker, handy as a broader search query
+pub(crate) fn base_title(title: &str) -> String {
+    normalize_title(title).words.join(" ")
+}
+
+/// scores how well `candidate` matches `query`, in the range `0.0..=1.0`
+pub(crate) fn title_score(query: &str, candidate: &str) -> f32 {
+    let (query, candidate) = (normalize_title(query), normalize_title(candidate));
+    if query.words.is_empty() || candidate.words.is_empty() {
+        return 0.0;
+    }
+
+    let word_score = dice::<&String>(
+        &query.words.iter().collect(),
+        &candidate.words.iter().collect(),
+    );
+    let bigram_score = dice(
+        &bigrams(&query.words.join(" ")),
+        &bigrams(&candidate.words.join(" ")),
+    );
+    let score = 0.6 * word_score + 0.4 * bigram_score;
+
+    let season_factor = match (query.season, candidate.season) {
+        (Some(q), c) if q == c.unwrap_or(1) => 1.0,
+        (Some(_), _) => 0.6,
+        // no season asked for, so the first season is the likelier pick
+        (None, Some(c)) if c > 1 => 0.85,
+        (None, _) => 1.0,
+    };
+
+    return (score * season_factor).clamp(0.0, 1.0);
+}
+
+fn bigrams(s: &str) -> HashSet<String> {
+    let chars = s.chars().collect::<Vec<_>>();
+
+    return chars
+        .windows(2)
+        .filter(|w| !w.contains(&' '))
+        .map(|w| w.iter().collect())
+        .collect();
+}
+
+fn dice<T: Eq + Hash>(a: &HashSet<T>, b: &HashSet<T>) -> f32 {
+    if a.is_empty() || b.is_empty() {
+        return 0.0;
+    }
+    return (2 * a.intersection(b).count()) as f32 / (a.len() + b.len()) as f32;
+}
+
+#[cfg(test)]
+mod test {
+    use super::{base_title, normalize_title, title_score};
+
+    // cargo test --lib -- anime::hianime::matching::test --show-output
+    #[test]
+    fn test_normalize_title() {
+        for title in [
+            "Attack on Titan S2",
+            "Attack on Titan Season 2",
+            "Attack on Titan 2nd Season",
+            "Attack on Titan: Second Season",
+            "Attack on Titan II",
+        ] {
+            let normalized = normalize_title(title);
+            assert_eq!(normalized.words, vec!["attack", "on", "titan"], "{title}");
+            assert_eq!(normalized.season, Some(2), "{title}");
+        }
+
+        assert_eq!(normalize_title("JoJo's Bizarre Adventure").season, None);
+        assert_eq!(base_title("Mob Psycho 100 II"), "mob psycho 100");
+    }
+
+    #[test]
+    fn test_title_score() {
+        let query = "Attack on Titan S2";
+
+        let right = title_score(query, "Attack on Titan Season 2");
+        let wrong_season = title_score(query, "Attack on Titan Season 3");
+        let first_season = title_score(query, "Attack on Titan");
+        let unrelated = title_score(query, "One Piece");
+
+        assert_eq!(right, 1.0);
+        assert!(right > wrong_season);
+        assert!(right > first_season);
+        assert!(first_season > unrelated);
+        assert!(title_score("Shingeki no Kyojin Season 2", "Shingeki no Kyojin Season 2") > 0.99);
+    }
+}
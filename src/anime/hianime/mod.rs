@@ -1,19 +1,34 @@
 use once_cell::sync::Lazy;
 use regex::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Deserializer, Serialize};
 use std::{
     collections::{HashMap, HashSet},
     fmt,
     hash::Hash,
 };
 
+mod aniwatch;
+#[cfg(feature = "bench")]
+mod bench;
+#[cfg(any(test, fuzzing))]
+mod fuzzing;
+mod health;
+mod id_cache;
+mod ids;
+mod matching;
 mod parsers;
+mod router;
+mod selectors;
 mod types;
 mod utils;
 
+pub use aniwatch::AniwatchJson;
+pub use health::{ParserReport, SelfTestReport};
+pub use ids::{AnimeId, EpisodeId, IdParseError};
 pub use parsers::*;
-
-use crate::EnmaError;
+pub use router::{Route, Router};
+pub use selectors::*;
+pub use types::{Server, ANIME_SERVERS};
 
 #[derive(Debug)]
 pub struct SearchPageFilters {
@@ -127,6 +142,11 @@ pub static SEARCH_PAGE_FILTERS: Lazy<SearchPageFilters> = Lazy::new(|| SearchPag
     ]),
 });
 
+/// `yyyy-m-d` dates accepted by the start/end date search filters
+static DATE_FILTER_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(r"^\d{4}-([0-9]|1[0-2])-([0-9]|[12][0-9]|3[01])$").unwrap();
+});
+
 pub static AZ_LIST_SORT_OPTIONS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     return [
         "all", "other", "0-9", "a", "b", "c", "d", "e", "f", "g", "h", "i", "j", "k", "l", "m",
@@ -136,6 +156,36 @@ pub static AZ_LIST_SORT_OPTIONS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     .collect();
 });
 
+/// limits the traversal done by [`Scraper::get_franchise`]
+#[derive(Debug, Clone, Copy)]
+pub struct FranchiseOptions {
+    /// how many season/related hops to follow from the starting anime
+    pub max_depth: u8,
+    /// maximum number of animes to scrape, including the starting one
+    pub max_nodes: usize,
+}
+
+impl Default for FranchiseOptions {
+    fn default() -> Self {
+        return FranchiseOptions {
+            max_depth: 2,
+            max_nodes: 25,
+        };
+    }
+}
+
+/// optional hints used by [`Scraper::resolve_title`] to break ties between
+/// similarly named animes
+#[derive(Debug, Default, Clone)]
+pub struct TitleHints {
+    /// e.g. `TV`, `Movie`, `OVA`
+    pub anime_type: Option<String>,
+    /// year the anime started airing
+    pub year: Option<u16>,
+    /// total number of episodes
+    pub episodes: Option<u16>,
+}
+
 /// enables search filters creation
 #[derive(Debug, Default, Clone)]
 pub struct SearchFilters {
@@ -155,8 +205,12 @@ impl SearchFilters {
     fn to_query_params(&self) -> String {
         // self.filters.remove(&SearchFilter::Invalid.to_string());
 
-        self.filters
-            .iter()
+        // sorted so that the same filters always produce the same url
+        let mut filters = self.filters.iter().collect::<Vec<_>>();
+        filters.sort_by_key(|s| s.to_string());
+
+        filters
+            .into_iter()
             .filter_map(|s| {
                 let value = s.mapped_value();
                 if value.is_empty() {
@@ -166,7 +220,7 @@ impl SearchFilters {
                 if s.to_string().contains("_date") {
                     return Some(value);
                 }
-                return Some(format!("{}={}", s.to_string(), value));
+                return Some(format!("{}={}", s, value));
             })
             .collect::<Vec<String>>()
             .join("&")
@@ -175,6 +229,10 @@ impl SearchFilters {
     pub fn is_empty(&self) -> bool {
         self.filters.is_empty()
     }
+
+    pub fn iter(&self) -> impl Iterator<Item = &SearchFilter> {
+        self.filters.iter()
+    }
 }
 
 impl Serialize for SearchFilters {
@@ -192,6 +250,33 @@ impl Serialize for SearchFilters {
     }
 }
 
+impl<'de> Deserialize<'de> for SearchFilters {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let map = HashMap::<String, String>::deserialize(deserializer)?;
+        let filters = map
+            .into_iter()
+            .map(|(key, value)| SearchFilter::from_raw(&key, value))
+            .filter(|s| !matches!(s, SearchFilter::Invalid))
+            .collect();
+
+        Ok(SearchFilters::new(filters))
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for SearchFilters {
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        "SearchFilters".into()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        HashMap::<String, String>::json_schema(generator)
+    }
+}
+
 /// different types of seach filter
 #[derive(Serialize, Debug, Eq, Clone)]
 #[serde(untagged)]
@@ -215,23 +300,23 @@ pub enum SearchFilter {
 impl SearchFilter {
     /// checks whether given filter is valid
     pub fn is_valid(filter_key: &'static str) -> bool {
-        match filter_key {
-            "type" => true,
-            "status" => true,
-            "rated" => true,
-            "score" => true,
-            "season" => true,
-            "language" => true,
-            "start_date" => true,
-            "end_date" => true,
-            "sort" => true,
-            "genres" => true,
-            _ => false,
-        }
+        matches!(
+            filter_key,
+            "type"
+                | "status"
+                | "rated"
+                | "score"
+                | "season"
+                | "language"
+                | "start_date"
+                | "end_date"
+                | "sort"
+                | "genres"
+        )
     }
 
     /// converts raw query string and its value to [SearchFilter] enum
-    pub fn from_raw(filter_key: &'static str, filter_value: String) -> Self {
+    pub fn from_raw(filter_key: &str, filter_value: String) -> Self {
         match filter_key {
             "type" => Self::Type(filter_value),
             "status" => Self::Status(filter_value),
@@ -247,11 +332,6 @@ impl SearchFilter {
         }
     }
 
-    /// converts variant to its representable query params key
-    fn to_string(&self) -> String {
-        format!("{self}")
-    }
-
     /// gets the mapped value of the variant
     fn mapped_value(&self) -> String {
         match self {
@@ -319,17 +399,8 @@ impl SearchFilter {
         }
     }
 
-    fn get_date_filter_value(raw_value: &String, category: &String) -> String {
-        let date_regex = Regex::new(r"^\d{4}-([0-9]|1[0-2])-([0-9]|[12][0-9]|3[01])$")
-            .map_err(|_| {
-                EnmaError::parsing_error(
-                    "hianime:get_search_results",
-                    Some(String::from("couldn't parse regex")),
-                    None,
-                )
-            })
-            .unwrap();
-        if !date_regex.is_match(&raw_value) {
+    fn get_date_filter_value(raw_value: &str, category: &str) -> String {
+        if !DATE_FILTER_REGEX.is_match(raw_value) {
             return String::from("");
         }
 
@@ -339,11 +410,20 @@ impl SearchFilter {
             "e"
         };
 
-        let period = raw_value.split('-').collect::<Vec<_>>();
+        let mut period = raw_value.split('-');
         let (year, month, day) = (
-            period[0].parse::<u16>().unwrap_or_default(),
-            period[1].parse::<u8>().unwrap_or_default(),
-            period[2].parse::<u8>().unwrap_or_default(),
+            period
+                .next()
+                .and_then(|s| s.parse::<u16>().ok())
+                .unwrap_or_default(),
+            period
+                .next()
+                .and_then(|s| s.parse::<u8>().ok())
+                .unwrap_or_default(),
+            period
+                .next()
+                .and_then(|s| s.parse::<u8>().ok())
+                .unwrap_or_default(),
         );
 
         format!(
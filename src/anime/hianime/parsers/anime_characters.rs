@@ -0,0 +1,215 @@
+use crate::{
+    anime::hianime::{
+        parsers::types::ScrapedAnimeCharacters,
+        types::{AnimeCharacter, AnimeCharacterWithVoiceActors},
+        utils::HiAnimeUtils,
+        AnimeId, Scraper,
+    },
+    error::EnmaResult,
+    utils::{EnmaClient, EnmaUtils},
+    EnmaError, SchemaVersion,
+};
+use reqwest::{
+    header::{HeaderMap, HeaderName, HeaderValue, REFERER},
+    StatusCode,
+};
+use scraper::{ElementRef, Html};
+use serde::Deserialize;
+
+// represents the raw json data we get from the source
+#[derive(Deserialize, Debug)]
+struct RawData {
+    html: Option<String>,
+}
+
+impl Scraper {
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::hianime;
+    ///     let hianime = hianime::Scraper::new();
+    ///
+    ///     let anime_id = "one-piece-100";
+    ///     let page_number = Some(2);
+    ///
+    ///     match hianime.get_anime_characters(anime_id, page_number).await {
+    ///         Ok(data) => println!("{data:#?}"),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "hianime:get_anime_characters", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn get_anime_characters(
+        &self,
+        anime_id: &str,
+        page_number: Option<u16>,
+    ) -> EnmaResult<ScrapedAnimeCharacters> {
+        const PROVIDER_PARSER: &'static str = "hianime:get_anime_characters";
+
+        let anime_id = anime_id
+            .parse::<AnimeId>()
+            .map_err(|e| e.into_enma_error(PROVIDER_PARSER))?;
+        let id = anime_id.numeric_id();
+
+        let current_page = page_number.unwrap_or(1).max(1);
+
+        let referer = format!("{}/{anime_id}", HiAnimeUtils::BaseUrl.value());
+        let headers: HeaderMap = [
+            (
+                REFERER,
+                HeaderValue::from_str(&referer).map_err(|_| {
+                    EnmaError::invalid_data_error(
+                        PROVIDER_PARSER,
+                        Some(String::from("invalid anime id")),
+                        Some(StatusCode::BAD_REQUEST),
+                    )
+                })?,
+            ),
+            (
+                HeaderName::from_static("x-requested-with"),
+                HeaderValue::from_static(EnmaUtils::XRequestedWithHeader.value()),
+            ),
+        ]
+        .into_iter()
+        .collect();
+        let url = format!(
+            "{}/{id}?page={current_page}",
+            HiAnimeUtils::CharacterListUrl.value(),
+        );
+
+        let data = self
+            .client
+            .get_json::<RawData>(url, Some(headers), PROVIDER_PARSER)
+            .await?;
+        let res =
+            self.parse_anime_characters(&data.html.unwrap_or_default(), anime_id, current_page)?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.characters.len());
+        Ok(res)
+    }
+
+    /// parses the `html` of the raw character list json
+    pub(crate) fn parse_anime_characters(
+        &self,
+        html: &str,
+        anime_id: AnimeId,
+        current_page: u16,
+    ) -> EnmaResult<ScrapedAnimeCharacters> {
+        let mut res = ScrapedAnimeCharacters {
+            schema_version: SchemaVersion::CURRENT,
+            anime_id,
+            characters: vec![],
+            total_pages: 0,
+            current_page,
+            has_next_page: false,
+        };
+
+        let document = Html::parse_fragment(html);
+
+        let selectors = &self.selectors.anime_characters;
+        let char_vc_actor_selector = &selectors.characters;
+        let char_selector = &selectors.character;
+        let vc_actor_selector = &selectors.voice_actor;
+        let other_vc_actors_selector = &selectors.other_voice_actors;
+
+        let avatar_selector = &selectors.avatar;
+        let poster_selector = &selectors.poster;
+        let name_selector = &selectors.name;
+        let cast_selector = &selectors.cast;
+        let img_selector = &selectors.other_voice_actor_poster;
+
+        let extract_person = |el: ElementRef| AnimeCharacter {
+            id: el
+                .select(avatar_selector)
+                .next()
+                .and_then(|el| el.attr("href"))
+                .and_then(|s| s.trim().split('/').nth(2))
+                .map(|s| s.to_string()),
+            name: el
+                .select(name_selector)
+                .next()
+                .and_then(|el| el.text().next())
+                .map(|s| s.trim().to_string()),
+            poster: el
+                .select(poster_selector)
+                .next()
+                .and_then(|el| el.attr("data-src").or(el.attr("src")))
+                .map(|s| s.trim().to_string()),
+            cast: el
+                .select(cast_selector)
+                .next()
+                .and_then(|el| el.text().next())
+                .map(|s| s.trim().to_string()),
+        };
+
+        for el in document.select(char_vc_actor_selector) {
+            let character = el
+                .select(char_selector)
+                .next()
+                .map(extract_person)
+                .unwrap_or_default();
+
+            let mut voice_actors = el
+                .select(vc_actor_selector)
+                .map(extract_person)
+                .collect::<Vec<_>>();
+
+            // voice actors beyond the first one are only rendered as avatars
+            for el in el.select(other_vc_actors_selector) {
+                voice_actors.push(AnimeCharacter {
+                    id: el
+                        .attr("href")
+                        .and_then(|s| s.trim().split('/').nth(2))
+                        .map(|s| s.to_string()),
+                    name: el
+                        .attr("title")
+                        .or(el.attr("data-original-title"))
+                        .map(|s| s.trim().to_string()),
+                    poster: el
+                        .select(img_selector)
+                        .next()
+                        .and_then(|el| el.attr("data-src").or(el.attr("src")))
+                        .map(|s| s.trim().to_string()),
+                    cast: el.attr("data-language").map(|s| s.trim().to_string()),
+                });
+            }
+
+            res.characters.push(AnimeCharacterWithVoiceActors {
+                character,
+                voice_actors,
+            });
+        }
+
+        res.total_pages = HiAnimeUtils::get_total_pages(&document, &self.selectors.pagination);
+        res.has_next_page = HiAnimeUtils::has_next_page(&document, &self.selectors.pagination);
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::anime::hianime;
+    use serde_json::to_string_pretty;
+
+    // cargo test --lib -- anime::hianime::parsers::anime_characters::test --include-ignored --show-output
+    #[tokio::test]
+    #[ignore = "hits the live site"]
+    async fn test_get_anime_characters() {
+        let hianime = hianime::Scraper::new();
+        let anime_id = "one-piece-100";
+        let page_number = Some(2);
+
+        match hianime.get_anime_characters(anime_id, page_number).await {
+            // Ok(_) => (),
+            Ok(data) => {
+                println!("{}", to_string_pretty(&data).unwrap());
+
+                assert_ne!(data.characters.len(), 0);
+                assert!(data.has_next_page);
+            }
+            Err(e) => eprintln!("error {}", e),
+        }
+    }
+}
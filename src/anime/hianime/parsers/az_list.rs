@@ -4,7 +4,7 @@ use crate::{
     EnmaError, EnmaResult,
 };
 use reqwest::StatusCode;
-use scraper::{Html, Selector};
+use scraper::Html;
 
 impl Scraper {
     /// ### Example Usage
@@ -22,9 +22,10 @@ impl Scraper {
     ///     }
     /// }
     ///  ```
+    #[tracing::instrument(name = "hianime:get_az_list", skip(self), fields(items = tracing::field::Empty))]
     pub async fn get_az_list(
         &self,
-        sort_option: &'static str,
+        sort_option: &str,
         page_number: Option<u16>,
     ) -> EnmaResult<ScrapedAZList> {
         const PROVIDER_PARSER: &'static str = "hianime:get_az_list";
@@ -38,32 +39,46 @@ impl Scraper {
             ));
         }
 
-        let mut res = ScrapedAZList {
-            sort_option,
-            current_page: page_number.unwrap_or(1).max(1),
-            ..Default::default()
-        };
-
-        let sort_option = match sort_option {
-            "all" => "".to_string(),
-            "other" => "other".to_string(),
-            _ => sort_option.to_uppercase(),
-        };
+        let current_page = page_number.unwrap_or(1).max(1);
         let url = format!(
-            "{}/{sort_option}?page={}",
+            "{}/{}?page={current_page}",
             HiAnimeUtils::AZListUrl.value(),
-            &res.current_page
+            match sort_option {
+                "all" => "".to_string(),
+                "other" => "other".to_string(),
+                _ => sort_option.to_uppercase(),
+            }
         );
 
-        let selector =
-            &Selector::parse("#main-wrapper .tab-content .film_list-wrap .flw-item").unwrap();
-
         let page = self.client.get_html(url, None, PROVIDER_PARSER).await?;
-        let document = Html::parse_document(&page);
+        let res = self.parse_az_list(&page, sort_option, current_page)?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.animes.len());
+        Ok(res)
+    }
+
+    /// parses a raw az list page
+    pub(crate) fn parse_az_list(
+        &self,
+        page: &str,
+        sort_option: &str,
+        current_page: u16,
+    ) -> EnmaResult<ScrapedAZList> {
+        let mut res = ScrapedAZList {
+            sort_option: sort_option.to_string(),
+            current_page,
+            ..Default::default()
+        };
+
+        let selectors = &self.selectors.az_list;
+        let selector = &selectors.animes;
+
+        let document = Html::parse_document(page);
 
-        res.has_next_page = HiAnimeUtils::has_next_page(&document);
-        res.total_pages = HiAnimeUtils::get_total_pages(&document);
-        res.animes = HiAnimeUtils::extract_animes(&document, selector);
+        res.has_next_page = HiAnimeUtils::has_next_page(&document, &self.selectors.pagination);
+        res.total_pages = HiAnimeUtils::get_total_pages(&document, &self.selectors.pagination);
+        res.animes = HiAnimeUtils::extract_animes(&document, selector, &self.selectors.anime_card);
 
         Ok(res)
     }
@@ -74,8 +89,9 @@ mod test {
     use crate::anime::hianime;
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::az_list::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::az_list::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_az_list() {
         let hianime = hianime::Scraper::new();
         let sort_option = "e";
@@ -88,7 +104,7 @@ mod test {
 
                 assert_ne!(data.animes.len(), 0);
                 assert_eq!(data.total_pages, 3);
-                assert_eq!(data.has_next_page, true);
+                assert!(data.has_next_page);
             }
             Err(e) => eprintln!("{}", e),
         }
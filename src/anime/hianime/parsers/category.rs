@@ -7,7 +7,7 @@ use crate::{
     EnmaError,
 };
 use reqwest::StatusCode;
-use scraper::{Html, Selector};
+use scraper::Html;
 
 impl Scraper {
     /// ### Example Usage
@@ -25,9 +25,10 @@ impl Scraper {
     ///     }
     /// }
     ///  ```
+    #[tracing::instrument(name = "hianime:get_category_anime", skip(self), fields(items = tracing::field::Empty))]
     pub async fn get_category_anime(
         &self,
-        category_name: &'static str,
+        category_name: &str,
         page_number: Option<u16>,
     ) -> EnmaResult<ScrapedCategoryAnime> {
         const PROVIDER_PARSER: &'static str = "hianime:get_category_anime";
@@ -41,31 +42,41 @@ impl Scraper {
             ));
         }
 
-        let mut res = ScrapedCategoryAnime {
-            current_page: page_number.unwrap_or(1).max(1),
-            genres: Vec::with_capacity(41),
-            ..Default::default()
-        };
-
-        let category_name_selector =
-            &Selector::parse("#main-content .block_area .block_area-header .cat-heading").unwrap();
-        let anime_selector =
-            &Selector::parse("#main-content .tab-content .film_list-wrap .flw-item").unwrap();
-        let genre_selector = &Selector::parse(
-            "#main-sidebar .block_area.block_area_sidebar.block_area-genres .sb-genre-list li",
-        )
-        .unwrap();
-        let top10_animes_selector =
-            &Selector::parse("#main-sidebar .block_area-realtime [id^=\"top-viewed-\"]").unwrap();
-
+        let current_page = page_number.unwrap_or(1).max(1);
         let url = format!(
             "{}/{category_name}?page={}",
             HiAnimeUtils::BaseUrl.value(),
-            &res.current_page
+            current_page
         );
 
         let page = self.client.get_html(url, None, PROVIDER_PARSER).await?;
-        let document = Html::parse_document(&page);
+        let res = self.parse_category_anime(&page, category_name, current_page)?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.animes.len());
+        Ok(res)
+    }
+
+    /// parses a raw category page, `category_name` is used when the page has no heading
+    pub(crate) fn parse_category_anime(
+        &self,
+        page: &str,
+        category_name: &str,
+        current_page: u16,
+    ) -> EnmaResult<ScrapedCategoryAnime> {
+        let mut res = ScrapedCategoryAnime {
+            current_page,
+            genres: Vec::with_capacity(41),
+            ..Default::default()
+        };
+
+        let selectors = &self.selectors.category;
+        let category_name_selector = &selectors.name;
+        let anime_selector = &selectors.animes;
+        let genre_selector = &selectors.genres;
+        let top10_animes_selector = &selectors.top10;
+
+        let document = Html::parse_document(page);
 
         res.category_name = document
             .select(category_name_selector)
@@ -73,10 +84,15 @@ impl Scraper {
             .and_then(|e| e.text().next())
             .map(|s| s.to_string())
             .unwrap_or(category_name.to_string());
-        res.total_pages = HiAnimeUtils::get_total_pages(&document);
-        res.has_next_page = HiAnimeUtils::has_next_page(&document);
-        res.animes = HiAnimeUtils::extract_animes(&document, anime_selector);
-        res.top10_animes = HiAnimeUtils::extract_top10_animes(&document, top10_animes_selector);
+        res.total_pages = HiAnimeUtils::get_total_pages(&document, &self.selectors.pagination);
+        res.has_next_page = HiAnimeUtils::has_next_page(&document, &self.selectors.pagination);
+        res.animes =
+            HiAnimeUtils::extract_animes(&document, anime_selector, &self.selectors.anime_card);
+        res.top10_animes = HiAnimeUtils::extract_top10_animes(
+            &document,
+            top10_animes_selector,
+            &self.selectors.top10_card,
+        );
         HiAnimeUtils::extract_genres(&document, genre_selector, &mut res.genres);
 
         Ok(res)
@@ -88,8 +104,9 @@ mod test {
     use crate::anime::hianime;
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::category::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::category::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_category_anime() {
         let hianime = hianime::Scraper::new();
 
@@ -102,7 +119,7 @@ mod test {
                 println!("{}", to_string_pretty(&data).unwrap());
 
                 assert_eq!(data.total_pages, 187);
-                assert_eq!(data.has_next_page, true);
+                assert!(data.has_next_page);
 
                 assert_ne!(data.animes.len(), 0);
                 assert_ne!(data.genres.len(), 0);
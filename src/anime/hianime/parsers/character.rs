@@ -0,0 +1,235 @@
+use crate::{
+    anime::hianime::{
+        parsers::types::ScrapedCharacterInfo,
+        types::{CharacterAnimeography, CharacterVoiceActor},
+        utils::HiAnimeUtils,
+        AnimeId, Scraper,
+    },
+    error::EnmaResult,
+    utils::EnmaClient,
+    EnmaError,
+};
+use reqwest::StatusCode;
+use scraper::Html;
+
+impl Scraper {
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::hianime;
+    ///     let hianime = hianime::Scraper::new();
+    ///
+    ///     let character_id = "monkey-d-luffy-3";
+    ///
+    ///     match hianime.get_character(character_id).await {
+    ///         Ok(data) => println!("{data:#?}"),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "hianime:get_character", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn get_character(&self, character_id: &str) -> EnmaResult<ScrapedCharacterInfo> {
+        const PROVIDER_PARSER: &'static str = "hianime:get_character";
+
+        let character_id = character_id.trim();
+        if character_id.is_empty() || character_id.contains('/') {
+            return Err(EnmaError::invalid_data_error(
+                PROVIDER_PARSER,
+                Some(String::from("invalid character id")),
+                Some(StatusCode::BAD_REQUEST),
+            ));
+        }
+
+        let url = format!("{}/{character_id}", HiAnimeUtils::CharacterUrl.value());
+        let page = self.client.get_html(url, None, PROVIDER_PARSER).await?;
+        let res = self.parse_character(&page, character_id)?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.character.animeography.len());
+        Ok(res)
+    }
+
+    /// parses a raw character page
+    pub(crate) fn parse_character(
+        &self,
+        page: &str,
+        character_id: &str,
+    ) -> EnmaResult<ScrapedCharacterInfo> {
+        let mut res = ScrapedCharacterInfo::default();
+        res.character.id = Some(character_id.to_string());
+
+        let document = Html::parse_document(page);
+
+        let selectors = &self.selectors.character;
+        let name_selector = &selectors.name;
+        let jname_selector = &selectors.jname;
+        let poster_selector = &selectors.poster;
+        let bio_selector = &selectors.bio;
+
+        let voice_actor_selector = &selectors.voice_actors;
+        let voice_actor_id_selector = &selectors.voice_actor_id;
+        let voice_actor_poster_selector = &selectors.voice_actor_poster;
+        let voice_actor_name_selector = &selectors.voice_actor_name;
+        let voice_actor_language_selector = &selectors.voice_actor_language;
+
+        let animeography_selector = &selectors.animeography;
+        let animeography_id_selector = &selectors.animeography_id;
+        let animeography_name_selector = animeography_id_selector;
+        let animeography_jname_selector = animeography_id_selector;
+        let animeography_poster_selector = &selectors.animeography_poster;
+        let animeography_info_selector = &selectors.animeography_info;
+
+        // character info
+        {
+            res.character.name = document
+                .select(name_selector)
+                .next()
+                .and_then(|el| el.text().next())
+                .map(|s| s.trim().to_string());
+            res.character.jname = document
+                .select(jname_selector)
+                .next()
+                .and_then(|el| el.text().next())
+                .map(|s| s.trim().to_string());
+            res.character.poster = document
+                .select(poster_selector)
+                .next()
+                .and_then(|el| el.attr("src"))
+                .map(|s| s.trim().to_string());
+        }
+
+        // bio
+        (res.character.description, res.character.about) =
+            HiAnimeUtils::extract_bio(&document, bio_selector);
+
+        // voice actors
+        for el in document.select(voice_actor_selector) {
+            res.character.voice_actors.push(CharacterVoiceActor {
+                id: el
+                    .select(voice_actor_id_selector)
+                    .next()
+                    .and_then(|el| el.attr("href"))
+                    .and_then(|s| s.trim().split('/').nth(2))
+                    .map(|s| s.to_string()),
+                name: el
+                    .select(voice_actor_name_selector)
+                    .next()
+                    .and_then(|el| el.text().next())
+                    .map(|s| s.trim().to_string()),
+                poster: el
+                    .select(voice_actor_poster_selector)
+                    .next()
+                    .and_then(|el| el.attr("src").or(el.attr("data-src")))
+                    .map(|s| s.trim().to_string()),
+                language: el
+                    .select(voice_actor_language_selector)
+                    .next()
+                    .and_then(|el| el.text().next())
+                    .map(|s| s.trim().to_string()),
+            });
+        }
+
+        // animeography
+        for el in document.select(animeography_selector) {
+            let infos = el
+                .select(animeography_info_selector)
+                .filter_map(|el| el.text().next())
+                .map(|s| s.trim().to_string())
+                .collect::<Vec<_>>();
+
+            res.character.animeography.push(CharacterAnimeography {
+                id: el
+                    .select(animeography_id_selector)
+                    .next()
+                    .and_then(|el| el.attr("href"))
+                    .and_then(AnimeId::from_href),
+                name: el
+                    .select(animeography_name_selector)
+                    .next()
+                    .and_then(|el| el.text().next())
+                    .map(|s| s.trim().to_string()),
+                jname: el
+                    .select(animeography_jname_selector)
+                    .next()
+                    .and_then(|el| el.attr("data-jname"))
+                    .map(|s| s.trim().to_string()),
+                poster: el
+                    .select(animeography_poster_selector)
+                    .next()
+                    .and_then(|el| el.attr("data-src"))
+                    .map(|s| s.trim().to_string()),
+                role: infos.first().cloned(),
+                anime_type: if infos.len() > 1 {
+                    infos.last().cloned()
+                } else {
+                    None
+                },
+            });
+        }
+
+        Ok(res)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{anime::hianime, test_utils::fixture};
+    use serde_json::to_string_pretty;
+
+    #[test]
+    fn test_parse_character() {
+        let hianime = hianime::Scraper::new();
+        let page = fixture("hianime/character.html");
+        let character = hianime
+            .parse_character(&page, "monkey-d-luffy-3")
+            .unwrap()
+            .character;
+
+        assert_eq!(character.id.as_deref(), Some("monkey-d-luffy-3"));
+        assert_eq!(character.name.as_deref(), Some("Monkey D., Luffy"));
+        assert_eq!(character.jname.as_deref(), Some("モンキー・D・ルフィ"));
+        assert_eq!(
+            character.description.as_deref(),
+            Some("Monkey D. Luffy is the captain of the Straw Hat Pirates.")
+        );
+        assert_eq!(character.about.len(), 2);
+        assert_eq!(character.about["age"], "17");
+        assert_eq!(character.about["height"], "172 cm");
+
+        assert_eq!(character.animeography.len(), 1);
+        let anime = &character.animeography[0];
+        assert_eq!(
+            anime.id.as_ref().map(|id| id.to_string()).as_deref(),
+            Some("one-piece-100")
+        );
+        assert_eq!(anime.name.as_deref(), Some("One Piece"));
+        assert_eq!(anime.role.as_deref(), Some("Main"));
+        assert_eq!(anime.anime_type.as_deref(), Some("TV"));
+
+        assert_eq!(character.voice_actors.len(), 1);
+        let voice_actor = &character.voice_actors[0];
+        assert_eq!(voice_actor.id.as_deref(), Some("mayumi-tanaka-4"));
+        assert_eq!(voice_actor.name.as_deref(), Some("Mayumi Tanaka"));
+        assert_eq!(voice_actor.language.as_deref(), Some("Japanese"));
+    }
+
+    // cargo test --lib -- anime::hianime::parsers::character::test --include-ignored --show-output
+    #[tokio::test]
+    #[ignore = "hits the live site"]
+    async fn test_get_character() {
+        let hianime = hianime::Scraper::new();
+        let character_id = "monkey-d-luffy-3";
+
+        match hianime.get_character(character_id).await {
+            // Ok(_) => (),
+            Ok(data) => {
+                println!("{}", to_string_pretty(&data).unwrap());
+
+                assert_ne!(data.character.name, None);
+                assert_ne!(data.character.animeography.len(), 0);
+                assert_ne!(data.character.voice_actors.len(), 0);
+            }
+            Err(e) => eprintln!("error {}", e),
+        }
+    }
+}
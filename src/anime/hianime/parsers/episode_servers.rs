@@ -1,13 +1,13 @@
 use crate::{
-    anime::hianime::{types::EpisodeInfo, utils::HiAnimeUtils, Scraper},
+    anime::hianime::{types::EpisodeInfo, utils::HiAnimeUtils, EpisodeId, Scraper},
     utils::{EnmaClient, EnmaUtils},
-    EnmaError, EnmaResult,
+    EnmaError, EnmaResult, SchemaVersion,
 };
 use reqwest::{
     header::{HeaderMap, HeaderName, HeaderValue, REFERER},
     StatusCode,
 };
-use scraper::{Html, Selector};
+use scraper::Html;
 use serde::Deserialize;
 
 use super::ScrapedEpisodeServers;
@@ -33,36 +33,33 @@ impl Scraper {
     ///     }
     /// }
     ///  ```
+    #[tracing::instrument(name = "hianime:get_episode_servers", skip(self), fields(items = tracing::field::Empty))]
     pub async fn get_episode_servers(
         &self,
-        anime_episode_id: &'static str,
+        anime_episode_id: &str,
     ) -> EnmaResult<ScrapedEpisodeServers> {
         const PROVIDER_PARSER: &'static str = "hianime:get_episode_servers";
 
-        let episode_id: &'static str = anime_episode_id
-            .trim()
-            .split("?ep=")
-            .last()
-            .unwrap_or_default();
-        if episode_id.is_empty() {
-            return Err(EnmaError::invalid_data_error(
-                PROVIDER_PARSER,
-                Some(String::from("invalid anime episode id")),
-                Some(StatusCode::BAD_REQUEST),
-            ));
-        }
-
-        let mut res = ScrapedEpisodeServers {
-            anime_episode_id,
-            ..Default::default()
-        };
+        let anime_episode_id = anime_episode_id
+            .parse::<EpisodeId>()
+            .map_err(|e| e.into_enma_error(PROVIDER_PARSER))?;
+        let episode_id = anime_episode_id.episode();
 
         let referer = format!("{}/watch/{anime_episode_id}", HiAnimeUtils::BaseUrl.value(),);
         let headers: HeaderMap = [
-            (REFERER, HeaderValue::from_str(&referer).unwrap()),
+            (
+                REFERER,
+                HeaderValue::from_str(&referer).map_err(|_| {
+                    EnmaError::invalid_data_error(
+                        PROVIDER_PARSER,
+                        Some(String::from("invalid anime episode id")),
+                        Some(StatusCode::BAD_REQUEST),
+                    )
+                })?,
+            ),
             (
                 HeaderName::from_static("x-requested-with"),
-                HeaderValue::from_str(EnmaUtils::XRequestedWithHeader.value()).unwrap(),
+                HeaderValue::from_static(EnmaUtils::XRequestedWithHeader.value()),
             ),
         ]
         .into_iter()
@@ -73,28 +70,48 @@ impl Scraper {
             HiAnimeUtils::EpisodeServersUrl.value()
         );
 
-        let episodes_no_selector = &Selector::parse(".server-notice strong b").unwrap();
-        let server_name_selector = &Selector::parse("a").unwrap();
-        let server_selector = |name: &str| {
-            return Selector::parse(&format!(
-                ".ps_-block.ps_-block-sub.servers-{name} .ps__-list .server-item"
-            ))
-            .unwrap();
-        };
-
         let data = self
             .client
             .get_json::<RawData>(url, Some(headers), PROVIDER_PARSER)
             .await?;
 
-        let document = &Html::parse_document(&data.html.unwrap_or_default().as_str());
+        let res = self.parse_episode_servers(&data.html.unwrap_or_default(), anime_episode_id)?;
 
-        ["sub", "dub", "raw"].map(|server| {
-            for el in document.select(&server_selector(server)) {
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.sub.len() + res.dub.len() + res.raw.len());
+        Ok(res)
+    }
+
+    /// parses the `html` of the raw episode servers json
+    pub(crate) fn parse_episode_servers(
+        &self,
+        html: &str,
+        anime_episode_id: EpisodeId,
+    ) -> EnmaResult<ScrapedEpisodeServers> {
+        let selectors = &self.selectors.episode_servers;
+        let episodes_no_selector = &selectors.episode_number;
+        let server_name_selector = &selectors.server_name;
+
+        let document = &Html::parse_document(html);
+
+        let mut res = ScrapedEpisodeServers {
+            schema_version: SchemaVersion::CURRENT,
+            anime_episode_id,
+            episode_number: None,
+            sub: vec![],
+            dub: vec![],
+            raw: vec![],
+        };
+
+        for (server, server_selector) in [
+            ("sub", &selectors.sub),
+            ("dub", &selectors.dub),
+            ("raw", &selectors.raw),
+        ] {
+            for el in document.select(server_selector) {
                 let server_id = el
                     .attr("data-server-id")
-                    .map(|s| s.trim().parse::<u16>().ok())
-                    .flatten();
+                    .and_then(|s| s.trim().parse::<u16>().ok());
                 let server_name = el
                     .select(server_name_selector)
                     .next()
@@ -113,7 +130,7 @@ impl Scraper {
                     _ => (),
                 }
             }
-        });
+        }
 
         res.episode_number = document
             .select(episodes_no_selector)
@@ -131,8 +148,9 @@ mod test {
     use crate::anime::hianime;
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::episode_servers::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::episode_servers::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_episode_servers() {
         let hianime = hianime::Scraper::new();
         let anime_episode_id = "steinsgate-0-92?ep=2055";
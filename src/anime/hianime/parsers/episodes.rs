@@ -1,5 +1,7 @@
 use crate::{
-    anime::hianime::{types::AnimeEpisode, utils::HiAnimeUtils, ScrapedAnimeEpisodes, Scraper},
+    anime::hianime::{
+        types::AnimeEpisode, utils::HiAnimeUtils, AnimeId, EpisodeId, ScrapedAnimeEpisodes, Scraper,
+    },
     utils::{EnmaClient, EnmaUtils},
     EnmaError, EnmaResult,
 };
@@ -7,7 +9,7 @@ use reqwest::{
     header::{HeaderMap, HeaderName, HeaderValue, REFERER},
     StatusCode,
 };
-use scraper::{Html, Selector};
+use scraper::Html;
 use serde::Deserialize;
 
 // represents the raw json data we get from the source
@@ -31,46 +33,59 @@ impl Scraper {
     ///     }
     /// }
     ///  ```
-    pub async fn get_anime_episodes(
-        &self,
-        anime_id: &'static str,
-    ) -> EnmaResult<ScrapedAnimeEpisodes> {
+    #[tracing::instrument(name = "hianime:get_anime_episodes", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn get_anime_episodes(&self, anime_id: &str) -> EnmaResult<ScrapedAnimeEpisodes> {
         const PROVIDER_PARSER: &'static str = "hianime:get_anime_episodes";
 
-        let anime_id: &'static str = anime_id.trim().split('-').last().unwrap_or_default();
-        if anime_id.is_empty() {
-            return Err(EnmaError::invalid_data_error(
-                PROVIDER_PARSER,
-                Some(String::from("invalid anime id")),
-                Some(StatusCode::BAD_REQUEST),
-            ));
-        }
+        let anime_id = anime_id
+            .parse::<AnimeId>()
+            .map_err(|e| e.into_enma_error(PROVIDER_PARSER))?;
 
         let referer = format!("{}/watch/{anime_id}", HiAnimeUtils::BaseUrl.value());
         let headers: HeaderMap = [
-            (REFERER, HeaderValue::from_str(&referer).unwrap()),
+            (
+                REFERER,
+                HeaderValue::from_str(&referer).map_err(|_| {
+                    EnmaError::invalid_data_error(
+                        PROVIDER_PARSER,
+                        Some(String::from("invalid anime id")),
+                        Some(StatusCode::BAD_REQUEST),
+                    )
+                })?,
+            ),
             (
                 HeaderName::from_static("x-requested-with"),
-                HeaderValue::from_str(EnmaUtils::XRequestedWithHeader.value()).unwrap(),
+                HeaderValue::from_static(EnmaUtils::XRequestedWithHeader.value()),
             ),
         ]
         .into_iter()
         .collect();
-        let url = format!("{}/{anime_id}", HiAnimeUtils::EpisodeListUrl.value());
+        let url = format!(
+            "{}/{}",
+            HiAnimeUtils::EpisodeListUrl.value(),
+            anime_id.numeric_id()
+        );
 
         let data = self
             .client
             .get_json::<RawData>(url, Some(headers), PROVIDER_PARSER)
             .await?;
+        let res = self.parse_anime_episodes(&data.html.unwrap_or_default())?;
 
-        let document = &Html::parse_document(&data.html.unwrap_or_default().as_str());
-        let episodes_selector = &Selector::parse(".detail-infor-content .ss-list a").unwrap();
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.episodes.len());
+        Ok(res)
+    }
 
-        let mut res = ScrapedAnimeEpisodes::default();
+    /// parses the `html` of the raw episode list json
+    pub(crate) fn parse_anime_episodes(&self, html: &str) -> EnmaResult<ScrapedAnimeEpisodes> {
+        let document = &Html::parse_document(html);
+        let selectors = &self.selectors.episodes;
+        let episodes_selector = &selectors.episodes;
 
-        res.total_episodes = match u16::try_from(document.select(episodes_selector).count()) {
-            Ok(val) => Some(val),
-            Err(_) => None,
+        let mut res = ScrapedAnimeEpisodes {
+            total_episodes: u16::try_from(document.select(episodes_selector).count()).ok(),
+            ..Default::default()
         };
 
         for el in document.select(episodes_selector) {
@@ -78,15 +93,11 @@ impl Scraper {
 
             let number = el
                 .attr("data-number")
-                .map(|s| s.trim().parse::<u16>().ok())
-                .flatten();
+                .and_then(|s| s.trim().parse::<u16>().ok());
 
             let is_filler = el.value().classes().any(|class| class == "ssl-item-filler");
 
-            let episode_id = el
-                .attr("href")
-                .map(|s| s.split("/").last().map(|s| s.to_string()))
-                .flatten();
+            let episode_id = el.attr("href").and_then(EpisodeId::from_href);
 
             res.episodes.push(AnimeEpisode {
                 title,
@@ -105,8 +116,9 @@ mod test {
     use crate::anime::hianime;
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::episodes::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::episodes::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_anime_episodes() {
         let hianime = hianime::Scraper::new();
         let anime_id = "steinsgate-3";
@@ -0,0 +1,437 @@
+use crate::{
+    anime::hianime::{
+        parsers::types::{ScrapedAnimeInfo, ScrapedFranchise},
+        types::{FranchiseEdge, FranchiseNode, FranchiseRelation, OtherInfoValue},
+        utils::HiAnimeUtils,
+        AnimeId, FranchiseOptions, Scraper,
+    },
+    error::EnmaResult,
+    SchemaVersion,
+};
+use chrono::NaiveDate;
+use std::collections::{HashSet, VecDeque};
+
+impl Scraper {
+    /// walks the seasons and related animes of an anime breadth-first,
+    /// building a deduplicated graph of the franchise and a watch order
+    /// sorted by air date
+    ///
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::hianime;
+    ///     let hianime = hianime::Scraper::new();
+    ///
+    ///     let anime_id = "steinsgate-3";
+    ///     let options = hianime::FranchiseOptions {
+    ///         max_depth: 1,
+    ///         ..Default::default()
+    ///     };
+    ///
+    ///     match hianime.get_franchise(anime_id, Some(options)).await {
+    ///         Ok(data) => println!("{data:#?}"),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "hianime:get_franchise", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn get_franchise(
+        &self,
+        anime_id: &str,
+        options: Option<FranchiseOptions>,
+    ) -> EnmaResult<ScrapedFranchise> {
+        const PROVIDER_PARSER: &'static str = "hianime:get_franchise";
+
+        let anime_id = anime_id
+            .parse::<AnimeId>()
+            .map_err(|e| e.into_enma_error(PROVIDER_PARSER))?;
+
+        let options = options.unwrap_or_default();
+        let max_nodes = options.max_nodes.max(1);
+
+        let mut res = ScrapedFranchise {
+            schema_version: SchemaVersion::CURRENT,
+            anime_id: anime_id.clone(),
+            nodes: vec![],
+            edges: vec![],
+            watch_order: vec![],
+        };
+
+        let mut queued: HashSet<AnimeId> = HashSet::from([anime_id.clone()]);
+        let mut queue: VecDeque<(AnimeId, u8)> = VecDeque::from([(anime_id.clone(), 0)]);
+        let mut edges: HashSet<FranchiseEdge> = HashSet::new();
+        let mut edge_order: Vec<FranchiseEdge> = vec![];
+
+        while let Some((id, depth)) = queue.pop_front() {
+            let info = match self.get_info(&id.to_string()).await {
+                Ok(info) => info,
+                Err(e) if id == anime_id => return Err(e),
+                // a broken link further down shouldn't take the whole graph down
+                Err(_) => continue,
+            };
+
+            let mut neighbours: Vec<(AnimeId, FranchiseRelation)> = vec![];
+
+            let current_season = info.anime.seasons.iter().position(|s| s.is_current);
+            for (idx, season) in info.anime.seasons.iter().enumerate() {
+                if season.is_current {
+                    continue;
+                }
+                if let Some(season_id) = &season.id {
+                    let relation = match current_season {
+                        Some(current) if idx + 1 == current => FranchiseRelation::Prequel,
+                        Some(current) if idx == current + 1 => FranchiseRelation::Sequel,
+                        _ => FranchiseRelation::Season,
+                    };
+                    neighbours.push((season_id.clone(), relation));
+                }
+            }
+            for related in info.related_animes.iter() {
+                if let Some(related_id) = &related.id {
+                    neighbours.push((related_id.clone(), FranchiseRelation::Related));
+                }
+            }
+
+            for (to, relation) in neighbours {
+                if to == id {
+                    continue;
+                }
+
+                if !queued.contains(&to) {
+                    if depth >= options.max_depth || queued.len() >= max_nodes {
+                        continue;
+                    }
+                    queued.insert(to.clone());
+                    queue.push_back((to.clone(), depth + 1));
+                }
+
+                let edge = FranchiseEdge {
+                    from: id.clone(),
+                    to,
+                    relation,
+                };
+                if edges.insert(edge.clone()) {
+                    edge_order.push(edge);
+                }
+            }
+
+            res.nodes.push(Self::to_franchise_node(id, depth, info));
+        }
+
+        // drop edges pointing at animes that failed to scrape
+        let scraped: HashSet<&AnimeId> = res.nodes.iter().map(|n| &n.id).collect();
+        res.edges = edge_order
+            .into_iter()
+            .filter(|e| scraped.contains(&e.from) && scraped.contains(&e.to))
+            .collect();
+
+        let mut watch_order = res
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(idx, node)| {
+                let air_date = node
+                    .air_date
+                    .as_deref()
+                    .and_then(|s| NaiveDate::parse_from_str(s, "%Y-%m-%d").ok());
+                (air_date, idx, node.id.clone())
+            })
+            .collect::<Vec<_>>();
+        watch_order.sort_by(|a, b| match (a.0, b.0) {
+            (Some(x), Some(y)) => x.cmp(&y).then(a.1.cmp(&b.1)),
+            (Some(_), None) => std::cmp::Ordering::Less,
+            (None, Some(_)) => std::cmp::Ordering::Greater,
+            (None, None) => a.1.cmp(&b.1),
+        });
+        res.watch_order = watch_order.into_iter().map(|(_, _, id)| id).collect();
+
+        HiAnimeUtils::record_items(res.nodes.len());
+        Ok(res)
+    }
+
+    fn to_franchise_node(id: AnimeId, depth: u8, info: ScrapedAnimeInfo) -> FranchiseNode {
+        let other_info = |key: &str| match info.anime.other_info.get(key) {
+            Some(OtherInfoValue::Single(value)) => Some(value.trim().to_string()),
+            Some(OtherInfoValue::Multiple(values)) => Some(values.join(", ")),
+            None => None,
+        };
+
+        let aired = other_info("aired");
+        let premiered = other_info("premiered");
+        let air_date = aired
+            .as_deref()
+            .and_then(Self::parse_air_date)
+            .or(premiered.as_deref().and_then(Self::parse_air_date))
+            .map(|date| date.format("%Y-%m-%d").to_string());
+
+        return FranchiseNode {
+            id,
+            depth,
+            aired,
+            premiered,
+            air_date,
+            name: info.anime.name,
+            jname: info.anime.jname,
+            poster: info.anime.poster,
+            anime_type: info.anime.stats.anime_type,
+        };
+    }
+
+    /// parses the start of values like `Oct 20, 1999 to ?` or `Spring 2013`
+    fn parse_air_date(aired: &str) -> Option<NaiveDate> {
+        let start = aired.split(" to ").next().unwrap_or_default().trim();
+
+        for format in ["%b %d, %Y", "%B %d, %Y", "%Y-%m-%d"] {
+            if let Ok(date) = NaiveDate::parse_from_str(start, format) {
+                return Some(date);
+            }
+        }
+
+        // only the year is known, e.g. `Spring 2013` or `2013`
+        let year = start
+            .split_whitespace()
+            .last()
+            .and_then(|s| s.parse::<i32>().ok())?;
+        let month = match start.split_whitespace().next().map(|s| s.to_lowercase()) {
+            Some(season) if season == "spring" => 4,
+            Some(season) if season == "summer" => 7,
+            Some(season) if season == "fall" => 10,
+            _ => 1,
+        };
+
+        return NaiveDate::from_ymd_opt(year, month, 1);
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        anime::hianime::{self, types::FranchiseRelation, FranchiseOptions},
+        test_utils::{Routes, TestServer},
+    };
+    use chrono::NaiveDate;
+    use serde_json::to_string_pretty;
+
+    /// an info page carrying only the markup the franchise walk reads
+    fn info_page(id: &str, seasons: &[&str], related: &[&str], aired: Option<&str>) -> String {
+        let seasons = seasons
+            .iter()
+            .map(|season| {
+                let active = if *season == id { " active" } else { "" };
+                format!(r#"<a class="os-item{active}" href="/{season}" title="{season}"></a>"#)
+            })
+            .collect::<String>();
+        let related = related
+            .iter()
+            .map(|related| {
+                format!(
+                    r#"<li><div class="film-detail"><h3 class="film-name">
+                        <a class="dynamic-name" href="/{related}">{related}</a>
+                    </h3></div></li>"#
+                )
+            })
+            .collect::<String>();
+        let aired = aired
+            .map(|aired| {
+                format!(
+                    r#"<div class="item"><span class="item-head">Aired:</span><span class="name">{aired}</span></div>"#
+                )
+            })
+            .unwrap_or_default();
+
+        return format!(
+            r#"<html><body>
+                <div id="main-content"><div class="os-list">{seasons}</div></div>
+                <div class="anisc-info-wrap"><div class="anisc-info">{aired}</div></div>
+                <div id="main-sidebar"><section class="block_area block_area_sidebar block_area-realtime">
+                    <div class="anif-block-ul"><ul>{related}</ul></div>
+                </section></div>
+            </body></html>"#
+        );
+    }
+
+    /// three seasons, a movie related to the second one listing it back
+    /// twice, and a spinoff only reachable through the movie
+    async fn franchise_server() -> TestServer {
+        let seasons = ["show-1", "show-2", "show-3"];
+
+        return Routes::new()
+            .route(
+                "/show-1",
+                info_page("show-1", &seasons, &[], Some("Apr 6, 2011 to Sep 14, 2011")),
+            )
+            .route(
+                "/show-2",
+                info_page("show-2", &seasons, &["movie-4"], Some("Oct 1, 2012")),
+            )
+            .route(
+                "/show-3",
+                info_page("show-3", &seasons, &[], Some("Spring 2015")),
+            )
+            .route(
+                "/movie-4",
+                info_page("movie-4", &[], &["show-2", "show-2", "spinoff-5"], None),
+            )
+            .route(
+                "/spinoff-5",
+                info_page("spinoff-5", &[], &[], Some("Jan 1, 2010")),
+            )
+            .serve()
+            .await;
+    }
+
+    fn edges(franchise: &hianime::ScrapedFranchise) -> Vec<(String, String, FranchiseRelation)> {
+        return franchise
+            .edges
+            .iter()
+            .map(|e| (e.from.to_string(), e.to.to_string(), e.relation))
+            .collect();
+    }
+
+    fn ids(ids: &[hianime::AnimeId]) -> Vec<String> {
+        return ids.iter().map(|id| id.to_string()).collect();
+    }
+
+    #[tokio::test]
+    async fn test_get_franchise_offline() {
+        use FranchiseRelation::*;
+
+        let server = franchise_server().await;
+        let hianime = hianime::Scraper::new().with_origin(server.base());
+        let options = FranchiseOptions {
+            max_depth: 1,
+            max_nodes: 10,
+        };
+
+        let res = hianime
+            .get_franchise("show-2", Some(options))
+            .await
+            .unwrap();
+        let nodes = res
+            .nodes
+            .iter()
+            .map(|node| (node.id.to_string(), node.depth, node.air_date.as_deref()))
+            .collect::<Vec<_>>();
+
+        assert_eq!(
+            nodes,
+            [
+                ("show-2".to_string(), 0, Some("2012-10-01")),
+                ("show-1".to_string(), 1, Some("2011-04-06")),
+                ("show-3".to_string(), 1, Some("2015-04-01")),
+                ("movie-4".to_string(), 1, None),
+            ]
+        );
+        let edge = |from: &str, to: &str, relation| (from.to_string(), to.to_string(), relation);
+        assert_eq!(
+            edges(&res),
+            [
+                edge("show-2", "show-1", Prequel),
+                edge("show-2", "show-3", Sequel),
+                edge("show-2", "movie-4", Related),
+                edge("show-1", "show-2", Sequel),
+                edge("show-1", "show-3", Season),
+                edge("show-3", "show-1", Season),
+                edge("show-3", "show-2", Prequel),
+                edge("movie-4", "show-2", Related),
+            ]
+        );
+        assert_eq!(
+            ids(&res.watch_order),
+            ["show-1", "show-2", "show-3", "movie-4"]
+        );
+
+        // every anime is scraped once, the spinoff lies past the max depth
+        for path in ["/show-1", "/show-2", "/show-3", "/movie-4"] {
+            assert_eq!(server.hits(path), 1, "{path}");
+        }
+        assert_eq!(server.hits("/spinoff-5"), 0);
+    }
+
+    #[tokio::test]
+    async fn test_get_franchise_limits() {
+        let server = franchise_server().await;
+        let hianime = hianime::Scraper::new().with_origin(server.base());
+
+        let options = FranchiseOptions {
+            max_depth: 2,
+            max_nodes: 10,
+        };
+        let res = hianime
+            .get_franchise("show-2", Some(options))
+            .await
+            .unwrap();
+        let spinoff = res
+            .nodes
+            .iter()
+            .find(|node| node.id.to_string() == "spinoff-5");
+        assert_eq!(res.nodes.len(), 5);
+        assert_eq!(spinoff.map(|node| node.depth), Some(2));
+        assert_eq!(
+            ids(&res.watch_order),
+            ["spinoff-5", "show-1", "show-2", "show-3", "movie-4"]
+        );
+
+        let options = FranchiseOptions {
+            max_depth: 2,
+            max_nodes: 2,
+        };
+        let res = hianime
+            .get_franchise("show-2", Some(options))
+            .await
+            .unwrap();
+        assert_eq!(ids(&res.watch_order), ["show-1", "show-2"]);
+        assert_eq!(
+            edges(&res),
+            [
+                (
+                    "show-2".to_string(),
+                    "show-1".to_string(),
+                    FranchiseRelation::Prequel
+                ),
+                (
+                    "show-1".to_string(),
+                    "show-2".to_string(),
+                    FranchiseRelation::Sequel
+                ),
+            ]
+        );
+    }
+
+    // cargo test --lib -- anime::hianime::parsers::franchise::test --include-ignored --show-output
+    #[tokio::test]
+    #[ignore = "hits the live site"]
+    async fn test_get_franchise() {
+        let hianime = hianime::Scraper::new();
+        let anime_id = "steinsgate-3";
+        let options = FranchiseOptions {
+            max_depth: 1,
+            max_nodes: 10,
+        };
+
+        match hianime.get_franchise(anime_id, Some(options)).await {
+            // Ok(_) => (),
+            Ok(data) => {
+                println!("{}", to_string_pretty(&data).unwrap());
+
+                assert_ne!(data.nodes.len(), 0);
+                assert!(data.nodes.len() <= 10);
+                assert_eq!(data.watch_order.len(), data.nodes.len());
+            }
+            Err(e) => eprintln!("error {}", e),
+        }
+    }
+
+    #[test]
+    fn test_parse_air_date() {
+        let parse = hianime::Scraper::parse_air_date;
+
+        assert_eq!(
+            parse("Oct 20, 1999 to ?"),
+            NaiveDate::from_ymd_opt(1999, 10, 20)
+        );
+        assert_eq!(parse("Apr 6, 2011"), NaiveDate::from_ymd_opt(2011, 4, 6));
+        assert_eq!(parse("Spring 2011"), NaiveDate::from_ymd_opt(2011, 4, 1));
+        assert_eq!(parse("?"), None);
+    }
+}
@@ -5,7 +5,7 @@ use crate::{
     EnmaError,
 };
 use reqwest::StatusCode;
-use scraper::{Html, Selector};
+use scraper::Html;
 
 impl Scraper {
     /// ### Example Usage
@@ -23,9 +23,10 @@ impl Scraper {
     ///     }
     /// }
     ///  ```
+    #[tracing::instrument(name = "hianime:get_genre_anime", skip(self), fields(items = tracing::field::Empty))]
     pub async fn get_genre_anime(
         &self,
-        genre_name: &'static str,
+        genre_name: &str,
         page_number: Option<u16>,
     ) -> EnmaResult<ScrapedGenreAnime> {
         const PROVIDER_PARSER: &'static str = "hianime:get_genre_anime";
@@ -39,6 +40,28 @@ impl Scraper {
             ));
         }
 
+        let current_page = page_number.unwrap_or(1).max(1);
+        let url = format!(
+            "{}/{genre_name}?page={}",
+            HiAnimeUtils::GenreUrl.value(),
+            current_page
+        );
+
+        let page = self.client.get_html(url, None, PROVIDER_PARSER).await?;
+        let res = self.parse_genre_anime(&page, genre_name, current_page)?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.animes.len());
+        Ok(res)
+    }
+
+    /// parses a raw genre page
+    pub(crate) fn parse_genre_anime(
+        &self,
+        page: &str,
+        genre_name: &str,
+        current_page: u16,
+    ) -> EnmaResult<ScrapedGenreAnime> {
         let mut res = ScrapedGenreAnime {
             genre_name: if genre_name == "martial-arts" {
                 String::from("marial-arts")
@@ -46,30 +69,17 @@ impl Scraper {
                 genre_name.to_string()
             },
             genres: Vec::with_capacity(41),
-            current_page: page_number.unwrap_or(1).max(1),
+            current_page,
             ..Default::default()
         };
 
-        let genre_name_selector =
-            &Selector::parse("#main-content .block_area .block_area-header .cat-heading").unwrap();
-        let genre_selector = &Selector::parse(
-            "#main-sidebar .block_area.block_area_sidebar.block_area-genres .sb-genre-list li",
-        )
-        .unwrap();
-        let anime_selector =
-            &Selector::parse("#main-content .tab-content .film_list-wrap .flw-item").unwrap();
-        let top_airing_selector =
-            &Selector::parse("#main-sidebar .block_area_sidebar:nth-child(2) .block_area-content .anif-block-ul ul li")
-                .unwrap();
+        let selectors = &self.selectors.genre;
+        let genre_name_selector = &selectors.name;
+        let genre_selector = &selectors.genres;
+        let anime_selector = &selectors.animes;
+        let top_airing_selector = &selectors.top_airing;
 
-        let url = format!(
-            "{}/{genre_name}?page={}",
-            HiAnimeUtils::GenreUrl.value(),
-            &res.current_page
-        );
-
-        let page = self.client.get_html(url, None, PROVIDER_PARSER).await?;
-        let document = Html::parse_document(&page);
+        let document = Html::parse_document(page);
 
         res.genre_name = document
             .select(genre_name_selector)
@@ -78,13 +88,17 @@ impl Scraper {
             .map(|s| s.to_string())
             .unwrap_or(genre_name.to_string());
 
-        res.total_pages = HiAnimeUtils::get_total_pages(&document);
-        res.has_next_page = HiAnimeUtils::has_next_page(&document);
+        res.total_pages = HiAnimeUtils::get_total_pages(&document, &self.selectors.pagination);
+        res.has_next_page = HiAnimeUtils::has_next_page(&document, &self.selectors.pagination);
 
         HiAnimeUtils::extract_genres(&document, genre_selector, &mut res.genres);
-        res.animes = HiAnimeUtils::extract_animes(&document, anime_selector);
-        res.top_airing_animes =
-            HiAnimeUtils::extract_most_popular_anime(&document, top_airing_selector);
+        res.animes =
+            HiAnimeUtils::extract_animes(&document, anime_selector, &self.selectors.anime_card);
+        res.top_airing_animes = HiAnimeUtils::extract_most_popular_anime(
+            &document,
+            top_airing_selector,
+            &self.selectors.sidebar_card,
+        );
 
         Ok(res)
     }
@@ -95,8 +109,9 @@ mod test {
     use crate::anime::hianime;
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::genre::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::genre::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_genre_anime() {
         let hianime = hianime::Scraper::new();
 
@@ -109,7 +124,7 @@ mod test {
                 println!("{}", to_string_pretty(&data).unwrap());
 
                 assert_eq!(data.total_pages, 43);
-                assert_eq!(data.has_next_page, true);
+                assert!(data.has_next_page);
 
                 assert_ne!(data.animes.len(), 0);
                 assert_ne!(data.top_airing_animes.len(), 0);
@@ -3,7 +3,7 @@ use crate::{
     error::EnmaResult,
     utils::EnmaClient,
 };
-use scraper::{Html, Selector};
+use scraper::Html;
 
 impl Scraper {
     /// ### Example Usage
@@ -18,43 +18,9 @@ impl Scraper {
     ///     }
     /// }
     ///  ```
+    #[tracing::instrument(name = "hianime:get_home_page", skip(self), fields(items = tracing::field::Empty))]
     pub async fn get_home_page(&self) -> EnmaResult<ScrapedHomePage> {
         const PROVIDER_PARSER: &'static str = "hianime:get_home_page";
-        let mut res = ScrapedHomePage {
-            genres: Vec::with_capacity(41),
-            ..Default::default()
-        };
-
-        let trending_selector =
-            &Selector::parse("#trending-home .swiper-wrapper .swiper-slide").unwrap();
-        let spotlight_selector = &Selector::parse("#slider .swiper-wrapper .swiper-slide").unwrap();
-        let latest_episode_selector = &Selector::parse(
-            "#main-content .block_area_home:nth-of-type(1) .tab-content .film_list-wrap .flw-item",
-        )
-        .unwrap();
-        let top_upcoming_selector = &Selector::parse(
-            "#main-content .block_area_home:nth-of-type(3) .tab-content .film_list-wrap .flw-item",
-        )
-        .unwrap();
-        let genre_selector = &Selector::parse(
-            "#main-sidebar .block_area.block_area_sidebar.block_area-genres .sb-genre-list li",
-        )
-        .unwrap();
-        let most_viewed_selector =
-            &Selector::parse("#main-sidebar .block_area-realtime [id^=\"top-viewed-\"]").unwrap();
-
-        let top_airing_selector =
-            &Selector::parse("#anime-featured .row div:nth-of-type(1) .anif-block-ul ul li")
-                .unwrap();
-        let most_popular_selector =
-            &Selector::parse("#anime-featured .row div:nth-of-type(2) .anif-block-ul ul li")
-                .unwrap();
-        let most_favorite_selector =
-            &Selector::parse("#anime-featured .row div:nth-of-type(3) .anif-block-ul ul li")
-                .unwrap();
-        let latest_completed_selector =
-            &Selector::parse("#anime-featured .row div:nth-of-type(4) .anif-block-ul ul li")
-                .unwrap();
 
         // raw html page
         let page = self
@@ -66,28 +32,95 @@ impl Scraper {
             )
             .await?;
 
-        let document = Html::parse_document(&page);
+        let res = self.parse_home_page(&page)?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(
+            res.spotlight_animes.len()
+                + res.trending_animes.len()
+                + res.latest_episode_animes.len()
+                + res.top_upcoming_animes.len()
+                + res.top_airing_animes.len()
+                + res.most_popular_animes.len()
+                + res.most_favorite_animes.len()
+                + res.latest_completed_animes.len(),
+        );
+        return Ok(res);
+    }
+
+    /// parses the raw home page, kept apart from the fetch so that
+    /// [`Scraper::self_test_fixtures`] can run it against saved pages
+    pub(crate) fn parse_home_page(&self, page: &str) -> EnmaResult<ScrapedHomePage> {
+        let mut res = ScrapedHomePage {
+            genres: Vec::with_capacity(41),
+            ..Default::default()
+        };
+
+        let selectors = &self.selectors.home_page;
+        let trending_selector = &selectors.trending;
+        let spotlight_selector = &selectors.spotlight;
+        let latest_episode_selector = &selectors.latest_episode;
+        let top_upcoming_selector = &selectors.top_upcoming;
+        let genre_selector = &selectors.genres;
+        let most_viewed_selector = &selectors.top10;
+
+        let top_airing_selector = &selectors.top_airing;
+        let most_popular_selector = &selectors.most_popular;
+        let most_favorite_selector = &selectors.most_favorite;
+        let latest_completed_selector = &selectors.latest_completed;
+
+        let document = Html::parse_document(page);
 
-        res.spotlight_animes =
-            HiAnimeUtils::extract_spotlight_animes(&document, spotlight_selector);
-        res.trending_animes = HiAnimeUtils::extract_trending_anime(&document, trending_selector);
-        res.latest_episode_animes =
-            HiAnimeUtils::extract_animes(&document, latest_episode_selector);
-        res.top_upcoming_animes = HiAnimeUtils::extract_animes(&document, top_upcoming_selector);
+        res.spotlight_animes = HiAnimeUtils::extract_spotlight_animes(
+            &document,
+            spotlight_selector,
+            &self.selectors.spotlight_card,
+        );
+        res.trending_animes = HiAnimeUtils::extract_trending_anime(
+            &document,
+            trending_selector,
+            &self.selectors.trending_card,
+        );
+        res.latest_episode_animes = HiAnimeUtils::extract_animes(
+            &document,
+            latest_episode_selector,
+            &self.selectors.anime_card,
+        );
+        res.top_upcoming_animes = HiAnimeUtils::extract_animes(
+            &document,
+            top_upcoming_selector,
+            &self.selectors.anime_card,
+        );
 
         // genres
         HiAnimeUtils::extract_genres(&document, genre_selector, &mut res.genres);
 
-        res.top10_animes = HiAnimeUtils::extract_top10_animes(&document, most_viewed_selector);
+        res.top10_animes = HiAnimeUtils::extract_top10_animes(
+            &document,
+            most_viewed_selector,
+            &self.selectors.top10_card,
+        );
 
-        res.top_airing_animes =
-            HiAnimeUtils::extract_most_popular_anime(&document, top_airing_selector);
-        res.most_popular_animes =
-            HiAnimeUtils::extract_most_popular_anime(&document, most_popular_selector);
-        res.most_favorite_animes =
-            HiAnimeUtils::extract_most_popular_anime(&document, most_favorite_selector);
-        res.latest_completed_animes =
-            HiAnimeUtils::extract_most_popular_anime(&document, latest_completed_selector);
+        res.top_airing_animes = HiAnimeUtils::extract_most_popular_anime(
+            &document,
+            top_airing_selector,
+            &self.selectors.sidebar_card,
+        );
+        res.most_popular_animes = HiAnimeUtils::extract_most_popular_anime(
+            &document,
+            most_popular_selector,
+            &self.selectors.sidebar_card,
+        );
+        res.most_favorite_animes = HiAnimeUtils::extract_most_popular_anime(
+            &document,
+            most_favorite_selector,
+            &self.selectors.sidebar_card,
+        );
+        res.latest_completed_animes = HiAnimeUtils::extract_most_popular_anime(
+            &document,
+            latest_completed_selector,
+            &self.selectors.sidebar_card,
+        );
 
         return Ok(res);
     }
@@ -98,8 +131,9 @@ mod test {
     use crate::anime::hianime;
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::home_page::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::home_page::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_home_page() {
         let hianime = hianime::Scraper::new();
         match hianime.get_home_page().await {
@@ -0,0 +1,431 @@
+use crate::{
+    anime::hianime::{
+        id_cache::IdMapping,
+        matching,
+        parsers::types::{ScrapedExternalIds, ScrapedIdMapping},
+        types::IdMatch,
+        utils::HiAnimeUtils,
+        AnimeId, Scraper,
+    },
+    error::EnmaResult,
+    utils::EnmaClient,
+    EnmaError, SchemaVersion,
+};
+use reqwest::StatusCode;
+use serde::Deserialize;
+use serde_json::json;
+use std::collections::HashMap;
+
+/// how many search candidates get their sync ids checked
+const MAX_VERIFIED_CANDIDATES: usize = 5;
+/// unverified candidates never score above this
+const UNVERIFIED_CONFIDENCE_CAP: f32 = 0.6;
+
+const ANILIST_MEDIA_QUERY: &'static str = r#"
+query ($id: Int, $idMal: Int) {
+  Media(id: $id, idMal: $idMal, type: ANIME) {
+    id
+    idMal
+    title { romaji english native }
+    synonyms
+  }
+}"#;
+
+// represents the raw json data we get from anilist
+#[derive(Deserialize, Debug)]
+struct RawData {
+    data: Option<RawMediaData>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawMediaData {
+    #[serde(rename = "Media")]
+    media: Option<RawMedia>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawMedia {
+    id: u32,
+    #[serde(rename = "idMal")]
+    id_mal: Option<u32>,
+    title: RawMediaTitle,
+    #[serde(default)]
+    synonyms: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct RawMediaTitle {
+    romaji: Option<String>,
+    english: Option<String>,
+}
+
+#[derive(Clone, Copy)]
+enum ExternalId {
+    Mal(u32),
+    Anilist(u32),
+}
+
+impl Scraper {
+    /// resolves a MyAnimeList id to hianime animes
+    ///
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::hianime;
+    ///     let hianime = hianime::Scraper::new();
+    ///
+    ///     let mal_id = 21;
+    ///
+    ///     match hianime.find_by_mal_id(mal_id).await {
+    ///         Ok(data) => println!("{data:#?}"),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "hianime:find_by_mal_id", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn find_by_mal_id(&self, mal_id: u32) -> EnmaResult<ScrapedIdMapping> {
+        const PROVIDER_PARSER: &'static str = "hianime:find_by_mal_id";
+        return self
+            .find_by_external_id(ExternalId::Mal(mal_id), PROVIDER_PARSER)
+            .await;
+    }
+
+    /// resolves an AniList id to hianime animes
+    ///
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::hianime;
+    ///     let hianime = hianime::Scraper::new();
+    ///
+    ///     let anilist_id = 21;
+    ///
+    ///     match hianime.find_by_anilist_id(anilist_id).await {
+    ///         Ok(data) => println!("{data:#?}"),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "hianime:find_by_anilist_id", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn find_by_anilist_id(&self, anilist_id: u32) -> EnmaResult<ScrapedIdMapping> {
+        const PROVIDER_PARSER: &'static str = "hianime:find_by_anilist_id";
+        return self
+            .find_by_external_id(ExternalId::Anilist(anilist_id), PROVIDER_PARSER)
+            .await;
+    }
+
+    /// gets the MyAnimeList and AniList ids of a hianime anime
+    ///
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::hianime;
+    ///     let hianime = hianime::Scraper::new();
+    ///
+    ///     let anime_id = "one-piece-100";
+    ///
+    ///     match hianime.get_external_ids(anime_id).await {
+    ///         Ok(data) => println!("{data:#?}"),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "hianime:get_external_ids", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn get_external_ids(&self, anime_id: &str) -> EnmaResult<ScrapedExternalIds> {
+        const PROVIDER_PARSER: &'static str = "hianime:get_external_ids";
+
+        let anime_id = anime_id
+            .parse::<AnimeId>()
+            .map_err(|e| e.into_enma_error(PROVIDER_PARSER))?;
+
+        if let Some(mapping) = self.id_cache.find_by_anime_id(&anime_id) {
+            return Ok(ScrapedExternalIds {
+                schema_version: SchemaVersion::CURRENT,
+                anime_id: mapping.anime_id,
+                mal_id: mapping.mal_id,
+                anilist_id: mapping.anilist_id,
+            });
+        }
+
+        let info = self.get_info(&anime_id.to_string()).await?;
+        let mapping = IdMapping {
+            anime_id,
+            mal_id: info.anime.mal_id,
+            anilist_id: info.anime.anilist_id,
+        };
+        if mapping.mal_id.is_some() || mapping.anilist_id.is_some() {
+            self.id_cache.insert(mapping.clone()).await;
+        }
+
+        Ok(ScrapedExternalIds {
+            schema_version: SchemaVersion::CURRENT,
+            anime_id: mapping.anime_id,
+            mal_id: mapping.mal_id,
+            anilist_id: mapping.anilist_id,
+        })
+    }
+
+    async fn find_by_external_id(
+        &self,
+        external_id: ExternalId,
+        provider_parser: &'static str,
+    ) -> EnmaResult<ScrapedIdMapping> {
+        let cached = match external_id {
+            ExternalId::Mal(id) => self.id_cache.find_by_mal_id(id),
+            ExternalId::Anilist(id) => self.id_cache.find_by_anilist_id(id),
+        };
+        if let Some(mapping) = cached {
+            HiAnimeUtils::record_items(1);
+            return Ok(ScrapedIdMapping {
+                schema_version: SchemaVersion::CURRENT,
+                mal_id: mapping.mal_id,
+                anilist_id: mapping.anilist_id,
+                matches: vec![IdMatch {
+                    anime_id: mapping.anime_id,
+                    name: None,
+                    jname: None,
+                    confidence: 1.0,
+                    verified: true,
+                }],
+                from_cache: true,
+            });
+        }
+
+        let media = self.get_anilist_media(external_id, provider_parser).await?;
+        let mut res = ScrapedIdMapping {
+            mal_id: media.id_mal,
+            anilist_id: Some(media.id),
+            ..Default::default()
+        };
+
+        let mut titles: Vec<String> = vec![];
+        for title in [media.title.english, media.title.romaji]
+            .into_iter()
+            .flatten()
+            .chain(media.synonyms.into_iter().take(3))
+        {
+            let title = title.trim().to_string();
+            if !title.is_empty() && !titles.contains(&title) {
+                titles.push(title);
+            }
+        }
+
+        // anime id -> candidate, best title similarity seen so far
+        let mut candidates: HashMap<AnimeId, IdMatch> = HashMap::new();
+        for title in titles.iter() {
+            let suggestions = match self.get_search_suggestions(title).await {
+                Ok(data) => data.suggestions,
+                Err(_) => continue,
+            };
+
+            for suggestion in suggestions {
+                let Some(anime_id) = suggestion.id else {
+                    continue;
+                };
+                let similarity = [&suggestion.name, &suggestion.jname]
+                    .into_iter()
+                    .flatten()
+                    .map(|name| matching::title_score(title, name))
+                    .fold(0.0, f32::max);
+
+                let candidate = candidates.entry(anime_id.clone()).or_insert(IdMatch {
+                    anime_id,
+                    name: suggestion.name,
+                    jname: suggestion.jname,
+                    confidence: 0.0,
+                    verified: false,
+                });
+                candidate.confidence = candidate.confidence.max(similarity);
+            }
+        }
+
+        let mut candidates = candidates.into_values().collect::<Vec<_>>();
+        candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        // only a verified candidate scores above the cap, including the ones
+        // ranked after the first verified match
+        for candidate in candidates.iter_mut() {
+            candidate.confidence *= UNVERIFIED_CONFIDENCE_CAP;
+        }
+
+        for candidate in candidates.iter_mut().take(MAX_VERIFIED_CANDIDATES) {
+            let Ok(info) = self.get_info(&candidate.anime_id.to_string()).await else {
+                continue;
+            };
+            let (mal_id, anilist_id) = (info.anime.mal_id, info.anime.anilist_id);
+
+            let is_match = match external_id {
+                ExternalId::Mal(id) => mal_id == Some(id),
+                ExternalId::Anilist(id) => anilist_id == Some(id),
+            } || (anilist_id.is_some() && anilist_id == res.anilist_id);
+
+            let is_mismatch = match external_id {
+                ExternalId::Mal(_) => mal_id.is_some(),
+                ExternalId::Anilist(_) => anilist_id.is_some(),
+            };
+
+            if is_match {
+                candidate.confidence = 1.0;
+                candidate.verified = true;
+
+                self.id_cache
+                    .insert(IdMapping {
+                        anime_id: candidate.anime_id.clone(),
+                        mal_id: mal_id.or(res.mal_id),
+                        anilist_id: anilist_id.or(res.anilist_id),
+                    })
+                    .await;
+                break;
+            } else if is_mismatch {
+                // sync ids point at a different anime
+                candidate.confidence = 0.0;
+            }
+        }
+
+        candidates.retain(|c| c.confidence > 0.0);
+        candidates.sort_by(|a, b| b.confidence.total_cmp(&a.confidence));
+        res.matches = candidates;
+
+        HiAnimeUtils::record_items(res.matches.len());
+        Ok(res)
+    }
+
+    async fn get_anilist_media(
+        &self,
+        external_id: ExternalId,
+        provider_parser: &'static str,
+    ) -> EnmaResult<RawMedia> {
+        let variables = match external_id {
+            ExternalId::Mal(id) => json!({ "idMal": id }),
+            ExternalId::Anilist(id) => json!({ "id": id }),
+        };
+        let body = json!({ "query": ANILIST_MEDIA_QUERY, "variables": variables });
+
+        let data = self
+            .client
+            .post_json::<RawData, _>(
+                HiAnimeUtils::AnilistGraphqlUrl.value().to_string(),
+                &body,
+                None,
+                provider_parser,
+            )
+            .await?;
+
+        return data.data.and_then(|d| d.media).ok_or_else(|| {
+            EnmaError::invalid_data_error(
+                provider_parser,
+                Some(String::from("no anime found for the given id")),
+                Some(StatusCode::NOT_FOUND),
+            )
+        });
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{anime::hianime, test_utils::Routes};
+    use serde_json::{json, to_string_pretty};
+
+    /// info page holding only the sync ids
+    fn info_page(mal_id: u32) -> String {
+        return format!(
+            r#"<html><body><script id="syncData" type="application/json">{{"mal_id":"{mal_id}","anilist_id":"{mal_id}"}}</script></body></html>"#
+        );
+    }
+
+    /// search suggestion json listing `(href, name)` pairs
+    fn suggestions(items: &[(&str, &str)]) -> String {
+        let html = items
+            .iter()
+            .map(|(href, name)| {
+                format!(
+                    r#"<a href="{href}?ref=search" class="nav-item"><div class="film-poster"></div><div class="srp-detail"><h3 class="film-name" data-jname="{name}">{name}</h3></div></a>"#
+                )
+            })
+            .collect::<String>();
+        return json!({ "status": true, "html": html }).to_string();
+    }
+
+    // cargo test --lib -- anime::hianime::parsers::id_mapping::test --include-ignored --show-output
+    #[tokio::test]
+    async fn test_find_by_mal_id_offline() {
+        let media = json!({ "data": { "Media": {
+            "id": 19,
+            "idMal": 19,
+            "title": { "romaji": "Monster", "english": "Monster" },
+            "synonyms": []
+        }}});
+        let server = Routes::new()
+            .route("/anilist", media.to_string())
+            .route(
+                "/ajax/search/suggest?keyword=Monster",
+                suggestions(&[
+                    ("/monster-strike-the-movie-1796", "Monster Strike"),
+                    ("/monster-37", "Monster"),
+                ]),
+            )
+            .route("/monster-37", info_page(19))
+            .route("/monster-strike-the-movie-1796", info_page(1796))
+            .serve()
+            .await;
+        let hianime = hianime::Scraper::new().with_origin(server.base());
+
+        let data = hianime.find_by_mal_id(19).await.unwrap();
+        println!("{}", to_string_pretty(&data).unwrap());
+
+        assert_eq!(data.anilist_id, Some(19));
+        assert_eq!(data.matches.len(), 2);
+        let (verified, other) = (&data.matches[0], &data.matches[1]);
+        assert_eq!(verified.anime_id.to_string(), "monster-37");
+        assert!(verified.verified && verified.confidence == 1.0);
+        // ranked after the verified match, so never checked, yet still capped
+        assert!(!other.verified && other.confidence <= 0.6);
+        assert_eq!(server.hits("/monster-strike-the-movie-1796"), 0);
+
+        let data = hianime.find_by_mal_id(19).await.unwrap();
+        assert!(data.from_cache);
+        assert_eq!(server.hits("/anilist"), 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "hits the live site"]
+    async fn test_find_by_mal_id() {
+        let hianime = hianime::Scraper::new();
+        let mal_id = 21;
+
+        match hianime.find_by_mal_id(mal_id).await {
+            // Ok(_) => (),
+            Ok(data) => {
+                println!("{}", to_string_pretty(&data).unwrap());
+
+                assert_eq!(
+                    data.matches.first().map(|m| m.anime_id.to_string()),
+                    Some(String::from("one-piece-100"))
+                );
+                assert_eq!(data.matches.first().map(|m| m.verified), Some(true));
+
+                // served from the id cache the second time around
+                let data = hianime.find_by_mal_id(mal_id).await.unwrap();
+                assert!(data.from_cache);
+            }
+            Err(e) => eprintln!("error {}", e),
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "hits the live site"]
+    async fn test_get_external_ids() {
+        let hianime = hianime::Scraper::new();
+        let anime_id = "one-piece-100";
+
+        match hianime.get_external_ids(anime_id).await {
+            // Ok(_) => (),
+            Ok(data) => {
+                println!("{}", to_string_pretty(&data).unwrap());
+
+                assert_eq!(data.mal_id, Some(21));
+                assert_eq!(data.anilist_id, Some(21));
+            }
+            Err(e) => eprintln!("error {}", e),
+        }
+    }
+}
@@ -6,14 +6,18 @@ use crate::{
             Season,
         },
         utils::HiAnimeUtils,
-        Scraper,
+        AnimeId, Scraper,
     },
-    error::{EnmaError, EnmaResult},
+    error::EnmaResult,
     utils::EnmaClient,
 };
+use once_cell::sync::Lazy;
 use regex::Regex;
-use reqwest::StatusCode;
-use scraper::{Html, Selector};
+use scraper::Html;
+
+static WHITESPACE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    return Regex::new(r"\s+").unwrap();
+});
 
 impl Scraper {
     /// ### Example Usage
@@ -30,76 +34,71 @@ impl Scraper {
     ///     }
     /// }
     ///  ```
-    pub async fn get_info(&self, anime_id: &'static str) -> EnmaResult<ScrapedAnimeInfo> {
+    #[tracing::instrument(name = "hianime:get_info", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn get_info(&self, anime_id: &str) -> EnmaResult<ScrapedAnimeInfo> {
         const PROVIDER_PARSER: &'static str = "hianime:get_info";
 
-        let anime_id = anime_id.trim();
-        if anime_id.is_empty() || !anime_id.contains('-') {
-            return Err(EnmaError::invalid_data_error(
-                PROVIDER_PARSER,
-                Some(String::from("invalid anime id")),
-                Some(StatusCode::BAD_REQUEST),
-            ));
-        }
-
-        let mut res = ScrapedAnimeInfo::default();
+        let anime_id = anime_id
+            .parse::<AnimeId>()
+            .map_err(|e| e.into_enma_error(PROVIDER_PARSER))?;
 
         let url = format!("{}/{anime_id}", HiAnimeUtils::BaseUrl.value());
         let page = self.client.get_html(url, None, PROVIDER_PARSER).await?;
+        let res = self.parse_info(&page)?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(
+            res.anime.seasons.len()
+                + res.related_animes.len()
+                + res.recommended_animes.len()
+                + res.most_popular_animes.len(),
+        );
+        Ok(res)
+    }
 
-        let document = Html::parse_document(&page);
+    /// parses a raw anime info page
+    pub(crate) fn parse_info(&self, page: &str) -> EnmaResult<ScrapedAnimeInfo> {
+        let mut res = ScrapedAnimeInfo::default();
+        let document = Html::parse_document(page);
 
-        let id_selector = &Selector::parse(".anisc-detail .film-buttons a.btn-play").unwrap();
-        let name_selector = &Selector::parse(".anisc-detail .film-name.dynamic-name").unwrap();
+        let selectors = &self.selectors.info;
+        let id_selector = &selectors.id;
+        let name_selector = &selectors.name;
         let jname_selector = name_selector;
-        let description_selector =
-            &Selector::parse(".anisc-detail .film-description .text").unwrap();
-        let poster_selector = &Selector::parse(".film-poster .film-poster-img").unwrap();
-
-        let seasons_selector = &Selector::parse("#main-content .os-list a.os-item").unwrap();
-        let season_title_selector = &Selector::parse(".title").unwrap();
-        let season_poster_selector = &Selector::parse(".season-poster").unwrap();
-
-        let rating_selector = &Selector::parse(".anis-content .film-stats .tick .tick-pg").unwrap();
-        let quality_selector =
-            &Selector::parse(".anis-content .film-stats .tick .tick-quality").unwrap();
-        let sub_episodes_selector =
-            &Selector::parse(".anis-content .film-stats .tick .tick-sub").unwrap();
-        let dub_episodes_selector =
-            &Selector::parse(".anis-content .film-stats .tick .tick-dub").unwrap();
-        let other_stats_selector = &Selector::parse(".anis-content .film-stats .tick").unwrap();
-
-        let other_info_selector =
-            &Selector::parse(".anisc-info-wrap .anisc-info .item:not(.w-hide)").unwrap();
-        let other_info_key_selector = &Selector::parse(".item-head").unwrap();
-        let other_info_value_selector = &Selector::parse("*:not(.item-head)").unwrap();
-
-        let promotional_videos_selector = &Selector::parse(
-            ".block_area.block_area-promotions .block_area-promotions-list .screen-items .item",
-        )
-        .unwrap();
-        let promotional_video_thumbnail_selector = &Selector::parse("img").unwrap();
-
-        let char_vc_actor_selector = &Selector::parse(
-            ".block_area.block_area-actors .block-actors-content .bac-list-wrap .bac-item",
-        )
-        .unwrap();
-        let char_id_selector = &Selector::parse(".per-info.ltr .pi-avatar").unwrap();
-        let char_poster_selector = &Selector::parse(".per-info.ltr .pi-avatar img").unwrap();
-        let char_name_selector = &Selector::parse(".per-info.ltr .pi-detail a").unwrap();
-        let char_cast_selector = &Selector::parse(".per-info.ltr .pi-detail .pi-cast").unwrap();
-
-        let vc_actor_id_selector = &Selector::parse(".per-info.rtl .pi-avatar").unwrap();
-        let vc_actor_name_selector = &Selector::parse(".per-info.rtl .pi-detail a").unwrap();
-        let vc_actor_poster_selector = &Selector::parse(".per-info.rtl .pi-avatar img").unwrap();
-        let vc_actor_cast_selector = &Selector::parse(".per-info.rtl .pi-detail .pi-cast").unwrap();
-
-        let related_anime_selector = &Selector::parse("#main-sidebar .block_area.block_area_sidebar.block_area-realtime:nth-of-type(1) .anif-block-ul ul li").unwrap();
-        let most_popular_selector = &Selector::parse("#main-sidebar .block_area.block_area_sidebar.block_area-realtime:nth-of-type(2) .anif-block-ul ul li").unwrap();
-        let recommended_anime_selector = &Selector::parse(
-            "#main-content .block_area.block_area_category .tab-content .flw-item",
-        )
-        .unwrap();
+        let description_selector = &selectors.description;
+        let poster_selector = &selectors.poster;
+
+        let seasons_selector = &selectors.seasons;
+        let season_title_selector = &selectors.season_title;
+        let season_poster_selector = &selectors.season_poster;
+
+        let rating_selector = &selectors.rating;
+        let quality_selector = &selectors.quality;
+        let sub_episodes_selector = &selectors.sub_episodes;
+        let dub_episodes_selector = &selectors.dub_episodes;
+        let other_stats_selector = &selectors.other_stats;
+
+        let other_info_selector = &selectors.other_info;
+        let other_info_key_selector = &selectors.other_info_key;
+        let other_info_value_selector = &selectors.other_info_value;
+
+        let promotional_videos_selector = &selectors.promotional_videos;
+        let promotional_video_thumbnail_selector = &selectors.promotional_video_thumbnail;
+
+        let char_vc_actor_selector = &selectors.characters;
+        let char_id_selector = &selectors.character_id;
+        let char_poster_selector = &selectors.character_poster;
+        let char_name_selector = &selectors.character_name;
+        let char_cast_selector = &selectors.character_cast;
+
+        let vc_actor_id_selector = &selectors.voice_actor_id;
+        let vc_actor_name_selector = &selectors.voice_actor_name;
+        let vc_actor_poster_selector = &selectors.voice_actor_poster;
+        let vc_actor_cast_selector = &selectors.voice_actor_cast;
+
+        let related_anime_selector = &selectors.related_animes;
+        let most_popular_selector = &selectors.most_popular;
+        let recommended_anime_selector = &selectors.recommended;
 
         // anime info
         {
@@ -107,8 +106,7 @@ impl Scraper {
                 .select(id_selector)
                 .next()
                 .and_then(|el| el.attr("href"))
-                .and_then(|s| s.split('/').last())
-                .map(|s| s.to_string());
+                .and_then(AnimeId::from_href);
             res.anime.name = document
                 .select(name_selector)
                 .next()
@@ -134,7 +132,7 @@ impl Scraper {
         // seasons
         for el in document.select(seasons_selector) {
             res.anime.seasons.push(Season {
-                id: el.attr("href").map(|s| s[1..].trim().to_string()),
+                id: el.attr("href").and_then(AnimeId::from_href),
                 name: el.attr("title").map(|s| s.trim().to_string()),
                 title: el
                     .select(season_title_selector)
@@ -169,31 +167,21 @@ impl Scraper {
                 .select(sub_episodes_selector)
                 .next()
                 .and_then(|el| el.text().next())
-                .map(|s| s.trim().parse::<u16>().ok())
-                .flatten();
+                .and_then(|s| s.trim().parse::<u16>().ok());
             res.anime.stats.episodes.dub = document
                 .select(dub_episodes_selector)
                 .next()
                 .and_then(|el| el.text().next())
-                .map(|s| s.trim().parse::<u16>().ok())
-                .flatten();
-
-            let re = Regex::new(r"\s+").map_err(|_| {
-                EnmaError::parsing_error(
-                    PROVIDER_PARSER,
-                    Some(String::from("error pasring regex")),
-                    None,
-                )
-            })?;
+                .and_then(|s| s.trim().parse::<u16>().ok());
 
             if let Some(el) = document.select(other_stats_selector).next() {
-                let other_info = re
+                let other_info = WHITESPACE_REGEX
                     .replace_all(el.text().collect::<Vec<_>>().concat().trim(), " ")
                     .into_owned();
                 let other_infos = other_info.split(" ").collect::<Vec<_>>();
 
                 res.anime.stats.duration = other_infos.last().map(|s| s.to_string());
-                res.anime.stats.anime_type = Some(other_infos[other_infos.len() - 2].to_string());
+                res.anime.stats.anime_type = other_infos.iter().rev().nth(1).map(|s| s.to_string());
             }
         }
 
@@ -284,7 +272,6 @@ impl Scraper {
                             .next()
                             .and_then(|el| el.attr("data-src"))
                             .map(|s| s.trim().to_string()),
-                        ..Default::default()
                     },
                     voice_actor: AnimeCharacter {
                         id: el
@@ -318,14 +305,24 @@ impl Scraper {
         }
 
         // other content
-        res.related_animes =
-            HiAnimeUtils::extract_most_popular_anime(&document, related_anime_selector);
-        res.most_popular_animes =
-            HiAnimeUtils::extract_most_popular_anime(&document, most_popular_selector);
-        res.recommended_animes =
-            HiAnimeUtils::extract_animes(&document, recommended_anime_selector);
-
-        let (mal_id, anilist_id) = HiAnimeUtils::get_mal_anilist_id(&document);
+        res.related_animes = HiAnimeUtils::extract_most_popular_anime(
+            &document,
+            related_anime_selector,
+            &self.selectors.sidebar_card,
+        );
+        res.most_popular_animes = HiAnimeUtils::extract_most_popular_anime(
+            &document,
+            most_popular_selector,
+            &self.selectors.sidebar_card,
+        );
+        res.recommended_animes = HiAnimeUtils::extract_animes(
+            &document,
+            recommended_anime_selector,
+            &self.selectors.anime_card,
+        );
+
+        let (mal_id, anilist_id) =
+            HiAnimeUtils::get_mal_anilist_id(&document, &selectors.sync_data);
         res.anime.mal_id = mal_id;
         res.anime.anilist_id = anilist_id;
 
@@ -338,8 +335,9 @@ mod test {
     use crate::anime::hianime;
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::info::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::info::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_info() {
         let hianime = hianime::Scraper::new();
 
@@ -350,7 +348,10 @@ mod test {
             Ok(data) => {
                 println!("{}", to_string_pretty(&data).unwrap());
 
-                assert_eq!(data.anime.id, Some(anime_id.to_string()));
+                assert_eq!(
+                    data.anime.id.map(|id| id.to_string()),
+                    Some(anime_id.to_string())
+                );
                 assert_eq!(data.anime.name, Some(String::from("One Piece")));
                 assert_eq!(data.anime.mal_id, Some(21));
 
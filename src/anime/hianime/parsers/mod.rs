@@ -1,28 +1,53 @@
-use crate::{anime::hianime::utils::HiAnimeUtils, utils::EnmaUtils};
-use reqwest::{
-    header::{HeaderMap, HeaderValue, REFERER},
-    Client,
+use crate::{
+    anime::hianime::{
+        id_cache::IdMappingCache,
+        selectors::{compiled, SelectorTable, DEFAULT_SELECTORS},
+        utils::{HiAnimeClient, HiAnimeUtils},
+    },
+    utils::EnmaUtils,
+    EnmaResult,
 };
+use reqwest::header::{HeaderMap, HeaderValue, REFERER};
+#[cfg(feature = "cache")]
+use std::path::PathBuf;
+use std::sync::Arc;
 
 mod types;
 pub use types::*;
 
+mod anime_characters;
 mod az_list;
 mod category;
+mod character;
 mod episode_servers;
 mod episodes;
+mod franchise;
 mod genre;
 mod home_page;
+mod id_mapping;
 mod info;
+mod person;
 mod producer;
 mod qtip;
+mod random;
+mod resolve_title;
 mod schedule;
 mod search;
 mod search_suggestions;
 
-#[derive(Debug)]
+/// hianime scraper, cloning it is cheap as clones share the same http
+/// client, id cache and selector table
+#[derive(Debug, Clone)]
 pub struct Scraper {
-    client: Client,
+    client: HiAnimeClient,
+    id_cache: Arc<IdMappingCache>,
+    selectors: Arc<compiled::Selectors>,
+}
+
+impl Default for Scraper {
+    fn default() -> Self {
+        return Self::new();
+    }
 }
 
 impl Scraper {
@@ -35,7 +60,153 @@ impl Scraper {
         .collect();
 
         return Self {
-            client: EnmaUtils::new_http_client(Some(headers)),
+            client: HiAnimeClient::new(EnmaUtils::new_http_client(Some(headers))),
+            id_cache: Arc::default(),
+            selectors: Arc::clone(&DEFAULT_SELECTORS),
         };
     }
+
+    /// swaps the built-in selectors for `table`, e.g. one patched through
+    /// [`SelectorTable::from_file`] after the site's markup changed
+    ///
+    /// ### Example Usage
+    /// ```rust
+    /// fn get_scraper() -> enma::EnmaResult<enma::anime::hianime::Scraper> {
+    ///     use enma::anime::hianime;
+    ///     let table = hianime::SelectorTable::from_file("selectors.toml")?;
+    ///
+    ///     return hianime::Scraper::new().with_selectors(table);
+    /// }
+    ///  ```
+    pub fn with_selectors(mut self, table: SelectorTable) -> EnmaResult<Self> {
+        self.selectors = Arc::new(table.compile()?);
+        return Ok(self);
+    }
+
+    /// sends the scraper's requests to a local test server instead
+    #[cfg(test)]
+    pub(crate) fn with_origin(mut self, origin: String) -> Self {
+        self.client = self.client.with_origin(origin);
+        return self;
+    }
+
+    /// persists the id mappings resolved by [`Scraper::find_by_mal_id`],
+    /// [`Scraper::find_by_anilist_id`] and [`Scraper::get_external_ids`]
+    /// to a json file at `path`, loading the mappings already stored in it
+    #[cfg(feature = "cache")]
+    pub fn with_id_cache_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.id_cache = Arc::new(IdMappingCache::from_file(path.into()));
+        return self;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::Scraper;
+    use crate::anime::hianime::{FranchiseOptions, SearchFilters, SelectorTable, TitleHints};
+    use std::{future::Future, sync::Arc};
+
+    fn assert_spawnable<F: Future + Send + 'static>(_: F) {}
+
+    fn assert_shareable<T: Clone + Send + Sync + 'static>() {}
+
+    // compile time only, every scraper future must be spawnable onto a
+    // multi-threaded runtime
+    // cargo test --lib -- anime::hianime::parsers::test --show-output
+    #[test]
+    fn test_futures_are_send() {
+        assert_shareable::<Scraper>();
+        assert_shareable::<Arc<Scraper>>();
+
+        // a scraper with patched selectors shares them the same way
+        let hianime = Scraper::new()
+            .with_selectors(SelectorTable::default())
+            .unwrap();
+        macro_rules! assert_all_spawnable {
+            ($($method:ident($($arg:expr),*);)*) => {
+                $({
+                    let hianime = hianime.clone();
+                    assert_spawnable(async move { hianime.$method($($arg),*).await });
+                })*
+            };
+        }
+
+        assert_all_spawnable! {
+            get_home_page();
+            get_search_results("monster", None, Some(SearchFilters::default()));
+            get_search_suggestions("monster");
+            get_info("steinsgate-3");
+            get_random_anime(None);
+            get_qtip_info("steinsgate-3");
+            get_anime_episodes("steinsgate-3");
+            get_episode_servers("steinsgate-0-92?ep=2055");
+            get_anime_characters("steinsgate-3", None);
+            get_character("okabe-rintarou-1");
+            get_person("mamoru-miyano-1");
+            get_franchise("steinsgate-3", Some(FranchiseOptions::default()));
+            get_schedule(2024, 1, 1);
+            get_az_list("a", None);
+            get_genre_anime("shounen", None);
+            get_producer_anime("toei-animation", None);
+            get_category_anime("tv", None);
+            find_by_mal_id(9253);
+            find_by_anilist_id(9253);
+            get_external_ids("steinsgate-3");
+            resolve_title("steins gate", Some(TitleHints::default()));
+            self_test();
+        }
+    }
+
+    #[test]
+    fn test_parsers_dont_panic() {
+        let hianime = Scraper::new();
+
+        // markup that used to panic: a stats line with a single token, a
+        // multi byte spotlight rank and a genres line without its colon
+        for page in [
+            "",
+            "<",
+            "<html><body><div id=\"main-content\"></div>",
+            r#"<div class="anis-content"><div class="film-stats"><div class="tick">TV</div></div></div>"#,
+            r#"<div id="slider"><div class="swiper-wrapper"><div class="swiper-slide">
+                <div class="deslide-item-content"><div class="desi-sub-text">№1</div></div>
+            </div></div></div>"#,
+            r#"<div class="pre-qtip-content"><div class="pre-qtip-line">
+                <span class="stick">GENRES</span>
+            </div></div>"#,
+        ] {
+            hianime.fuzz_parse_page(page);
+        }
+
+        for input in [
+            "",
+            "?ep=",
+            "-",
+            "a-0?ep=-1",
+            "2024-1-1",
+            "2024-13-1",
+            "https://",
+            "ü",
+        ] {
+            Scraper::fuzz_parse_input(input);
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn test_spawn_shared_scraper() {
+        let hianime = Arc::new(Scraper::new());
+
+        // invalid ids fail before any request is made
+        let handles = ["steinsgate", "one-piece", "?ep=1"]
+            .into_iter()
+            .map(|anime_id| {
+                let hianime = Arc::clone(&hianime);
+                tokio::spawn(async move { hianime.get_info(anime_id).await })
+            })
+            .collect::<Vec<_>>();
+
+        for handle in handles {
+            assert!(handle.await.unwrap().is_err());
+        }
+    }
 }
@@ -0,0 +1,313 @@
+use crate::{
+    anime::hianime::{
+        parsers::types::ScrapedPersonInfo,
+        types::{VoiceActingRole, VoiceActingRoleAnime, VoiceActingRoleCharacter},
+        utils::HiAnimeUtils,
+        AnimeId, Scraper,
+    },
+    error::EnmaResult,
+    utils::EnmaClient,
+    EnmaError,
+};
+use reqwest::StatusCode;
+use scraper::Html;
+
+impl Scraper {
+    /// scrapes a voice actor's page, following the pagination
+    /// of their voice acting roles till the last page
+    ///
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::hianime;
+    ///     let hianime = hianime::Scraper::new();
+    ///
+    ///     let person_id = "mayumi-tanaka-4";
+    ///
+    ///     match hianime.get_person(person_id).await {
+    ///         Ok(data) => println!("{data:#?}"),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "hianime:get_person", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn get_person(&self, person_id: &str) -> EnmaResult<ScrapedPersonInfo> {
+        const PROVIDER_PARSER: &'static str = "hianime:get_person";
+
+        let person_id = person_id.trim();
+        if person_id.is_empty() || person_id.contains('/') {
+            return Err(EnmaError::invalid_data_error(
+                PROVIDER_PARSER,
+                Some(String::from("invalid person id")),
+                Some(StatusCode::BAD_REQUEST),
+            ));
+        }
+
+        let url = format!("{}/{person_id}", HiAnimeUtils::PeopleUrl.value());
+        let page = self.client.get_html(url, None, PROVIDER_PARSER).await?;
+        let mut res = self.parse_person(&page, person_id)?;
+
+        for page_number in 2..=res.total_role_pages {
+            let url = format!(
+                "{}/{person_id}?page={page_number}",
+                HiAnimeUtils::PeopleUrl.value()
+            );
+            let page = self.client.get_html(url, None, PROVIDER_PARSER).await?;
+            let document = Html::parse_document(&page);
+
+            let roles = self.extract_voice_acting_roles(&document);
+            if roles.is_empty() {
+                break;
+            }
+            res.person.voice_acting_roles.extend(roles);
+        }
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.person.voice_acting_roles.len());
+        Ok(res)
+    }
+
+    /// parses the first page of a raw person page, the voice acting roles
+    /// of the remaining pages are left to [`Scraper::get_person`]
+    pub(crate) fn parse_person(
+        &self,
+        page: &str,
+        person_id: &str,
+    ) -> EnmaResult<ScrapedPersonInfo> {
+        let mut res = ScrapedPersonInfo {
+            total_role_pages: 1,
+            ..Default::default()
+        };
+        res.person.id = Some(person_id.to_string());
+
+        let document = Html::parse_document(page);
+
+        let selectors = &self.selectors.person;
+        let name_selector = &selectors.name;
+        let jname_selector = &selectors.jname;
+        let poster_selector = &selectors.poster;
+        let bio_selector = &selectors.bio;
+
+        res.person.name = document
+            .select(name_selector)
+            .next()
+            .and_then(|el| el.text().next())
+            .map(|s| s.trim().to_string());
+        res.person.jname = document
+            .select(jname_selector)
+            .next()
+            .and_then(|el| el.text().next())
+            .map(|s| s.trim().to_string());
+        res.person.poster = document
+            .select(poster_selector)
+            .next()
+            .and_then(|el| el.attr("src"))
+            .map(|s| s.trim().to_string());
+
+        (res.person.description, res.person.about) =
+            HiAnimeUtils::extract_bio(&document, bio_selector);
+
+        let about = &res.person.about;
+        let about_value = |keys: &[&str]| {
+            keys.iter()
+                .find_map(|key| about.get(*key))
+                .map(|s| s.to_string())
+        };
+        let birth_date = about_value(&["birthday", "birth date", "date of birth"]);
+        let birth_place = about_value(&["birthplace", "birth place", "hometown"]);
+        let language = about_value(&["language", "languages"]);
+
+        res.person.birth_date = birth_date;
+        res.person.birth_place = birth_place;
+        res.person.language = language;
+
+        res.total_role_pages = HiAnimeUtils::get_total_pages(&document, &self.selectors.pagination);
+        res.person.voice_acting_roles = self.extract_voice_acting_roles(&document);
+
+        Ok(res)
+    }
+
+    fn extract_voice_acting_roles(&self, document: &Html) -> Vec<VoiceActingRole> {
+        let mut roles = vec![];
+
+        let selectors = &self.selectors.person;
+        let role_selector = &selectors.roles;
+        let anime_id_selector = &selectors.anime_id;
+        let anime_poster_selector = &selectors.anime_poster;
+        let anime_name_selector = &selectors.anime_name;
+        let anime_info_selector = &selectors.anime_info;
+
+        let char_id_selector = &selectors.character_id;
+        let char_poster_selector = &selectors.character_poster;
+        let char_name_selector = &selectors.character_name;
+        let char_role_selector = &selectors.character_role;
+
+        for el in document.select(role_selector) {
+            roles.push(VoiceActingRole {
+                anime: VoiceActingRoleAnime {
+                    id: el
+                        .select(anime_id_selector)
+                        .next()
+                        .and_then(|el| el.attr("href"))
+                        .and_then(AnimeId::from_href),
+                    name: el
+                        .select(anime_name_selector)
+                        .next()
+                        .and_then(|el| el.text().next())
+                        .map(|s| s.trim().to_string()),
+                    poster: el
+                        .select(anime_poster_selector)
+                        .next()
+                        .and_then(|el| el.attr("data-src").or(el.attr("src")))
+                        .map(|s| s.trim().to_string()),
+                    info: el
+                        .select(anime_info_selector)
+                        .next()
+                        .and_then(|el| el.text().next())
+                        .map(|s| s.trim().to_string()),
+                },
+                character: VoiceActingRoleCharacter {
+                    id: el
+                        .select(char_id_selector)
+                        .next()
+                        .and_then(|el| el.attr("href"))
+                        .and_then(|s| s.trim().split('/').nth(2))
+                        .map(|s| s.to_string()),
+                    name: el
+                        .select(char_name_selector)
+                        .next()
+                        .and_then(|el| el.text().next())
+                        .map(|s| s.trim().to_string()),
+                    poster: el
+                        .select(char_poster_selector)
+                        .next()
+                        .and_then(|el| el.attr("data-src").or(el.attr("src")))
+                        .map(|s| s.trim().to_string()),
+                },
+                role: el
+                    .select(char_role_selector)
+                    .next()
+                    .and_then(|el| el.text().next())
+                    .map(|s| s.trim().to_string()),
+            });
+        }
+
+        return roles;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        anime::hianime,
+        test_utils::{fixture, Routes},
+    };
+    use serde_json::to_string_pretty;
+
+    /// a page of voice acting roles holding a single role
+    fn role_page(anime_href: &str, anime_name: &str) -> String {
+        return format!(
+            r#"<div id="main-content"><section class="block_area block_area-actors">
+            <div class="block-actors-content"><div class="bac-list-wrap"><div class="bac-item">
+                <div class="per-info ltr">
+                    <div class="pi-detail"><h4 class="pi-name"><a href="{anime_href}">{anime_name}</a></h4></div>
+                </div>
+                <div class="per-info rtl">
+                    <div class="pi-detail"><h4 class="pi-name"><a href="/character/monkey-d-luffy-3">Monkey D., Luffy</a></h4></div>
+                </div>
+            </div></div></div>
+            </section></div>"#
+        );
+    }
+
+    #[test]
+    fn test_parse_person() {
+        let hianime = hianime::Scraper::new();
+        let page = fixture("hianime/person.html");
+        let res = hianime.parse_person(&page, "mayumi-tanaka-4").unwrap();
+        let person = res.person;
+
+        assert_eq!(res.total_role_pages, 1);
+        assert_eq!(person.id.as_deref(), Some("mayumi-tanaka-4"));
+        assert_eq!(person.name.as_deref(), Some("Mayumi Tanaka"));
+        assert_eq!(person.jname.as_deref(), Some("田中 真弓"));
+        assert_eq!(person.birth_date.as_deref(), Some("Jan 15, 1955"));
+        assert_eq!(person.birth_place.as_deref(), Some("Tokyo, Japan"));
+        assert_eq!(person.language, None);
+
+        assert_eq!(person.voice_acting_roles.len(), 1);
+        let role = &person.voice_acting_roles[0];
+        assert_eq!(
+            role.anime.id.as_ref().map(|id| id.to_string()).as_deref(),
+            Some("one-piece-100")
+        );
+        assert_eq!(role.anime.name.as_deref(), Some("One Piece"));
+        assert_eq!(role.anime.info.as_deref(), Some("TV, 1999"));
+        assert_eq!(
+            role.anime.poster.as_deref(),
+            Some("https://cdn.noitatnemucod.net/thumbnail/300x400/100/op.jpg")
+        );
+        assert_eq!(role.character.id.as_deref(), Some("monkey-d-luffy-3"));
+        assert_eq!(role.character.name.as_deref(), Some("Monkey D., Luffy"));
+        assert_eq!(role.role.as_deref(), Some("Main"));
+    }
+
+    #[tokio::test]
+    async fn test_get_person_pagination() {
+        // four pages announced, the third one comes back empty
+        let first_page = fixture("hianime/person.html").replace(
+            "</section>",
+            r#"<ul class="pagination">
+                <li class="page-item"><a title="Last" href="/people/mayumi-tanaka-4?page=4">»</a></li>
+            </ul></section>"#,
+        );
+        let server = Routes::new()
+            .route("/people/mayumi-tanaka-4", first_page)
+            .route(
+                "/people/mayumi-tanaka-4?page=2",
+                role_page("/one-piece-film-red-18236", "One Piece Film: Red"),
+            )
+            .route(
+                "/people/mayumi-tanaka-4?page=3",
+                "<html><body></body></html>",
+            )
+            .route(
+                "/people/mayumi-tanaka-4?page=4",
+                role_page("/dragon-ball-2", "Dragon Ball"),
+            )
+            .serve()
+            .await;
+        let hianime = hianime::Scraper::new().with_origin(server.base());
+
+        let res = hianime.get_person("mayumi-tanaka-4").await.unwrap();
+        let names = res
+            .person
+            .voice_acting_roles
+            .iter()
+            .map(|role| role.anime.name.as_deref().unwrap_or_default())
+            .collect::<Vec<_>>();
+
+        assert_eq!(res.total_role_pages, 4);
+        assert_eq!(names, ["One Piece", "One Piece Film: Red"]);
+        assert_eq!(server.hits("/people/mayumi-tanaka-4"), 3);
+    }
+
+    // cargo test --lib -- anime::hianime::parsers::person::test --include-ignored --show-output
+    #[tokio::test]
+    #[ignore = "hits the live site"]
+    async fn test_get_person() {
+        let hianime = hianime::Scraper::new();
+        let person_id = "mayumi-tanaka-4";
+
+        match hianime.get_person(person_id).await {
+            // Ok(_) => (),
+            Ok(data) => {
+                println!("{}", to_string_pretty(&data).unwrap());
+
+                assert_ne!(data.person.name, None);
+                assert_ne!(data.person.voice_acting_roles.len(), 0);
+            }
+            Err(e) => eprintln!("error {}", e),
+        }
+    }
+}
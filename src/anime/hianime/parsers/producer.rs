@@ -5,7 +5,7 @@ use crate::{
     EnmaError,
 };
 use reqwest::StatusCode;
-use scraper::{Html, Selector};
+use scraper::Html;
 
 impl Scraper {
     /// ### Example Usage
@@ -23,9 +23,10 @@ impl Scraper {
     ///     }
     /// }
     ///  ```
+    #[tracing::instrument(name = "hianime:get_producer_anime", skip(self), fields(items = tracing::field::Empty))]
     pub async fn get_producer_anime(
         &self,
-        producer_name: &'static str,
+        producer_name: &str,
         page_number: Option<u16>,
     ) -> EnmaResult<ScrapedProducerAnime> {
         const PROVIDER_PARSER: &'static str = "hianime:get_producer_anime";
@@ -39,29 +40,40 @@ impl Scraper {
             ));
         }
 
-        let mut res = ScrapedProducerAnime {
-            current_page: page_number.unwrap_or(1).max(1),
-            ..Default::default()
-        };
-
-        let producer_name_selector =
-            &Selector::parse("#main-content .block_area .block_area-header .cat-heading").unwrap();
-        let anime_selector =
-            &Selector::parse("#main-content .tab-content .film_list-wrap .flw-item").unwrap();
-        let top10_animes_selector =
-            &Selector::parse(r#"#main-sidebar .block_area-realtime [id^="top-viewed-"]"#).unwrap();
-        let top_airing_selector =
-            &Selector::parse("#main-sidebar .block_area_sidebar:nth-child(2) .block_area-content .anif-block-ul ul li")
-                .unwrap();
-
+        let current_page = page_number.unwrap_or(1).max(1);
         let url = format!(
             "{}/{producer_name}?page={}",
             HiAnimeUtils::ProducerUrl.value(),
-            &res.current_page
+            current_page
         );
 
         let page = self.client.get_html(url, None, PROVIDER_PARSER).await?;
-        let document = Html::parse_document(&page);
+        let res = self.parse_producer_anime(&page, producer_name, current_page)?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.animes.len());
+        Ok(res)
+    }
+
+    /// parses a raw producer page
+    pub(crate) fn parse_producer_anime(
+        &self,
+        page: &str,
+        producer_name: &str,
+        current_page: u16,
+    ) -> EnmaResult<ScrapedProducerAnime> {
+        let mut res = ScrapedProducerAnime {
+            current_page,
+            ..Default::default()
+        };
+
+        let selectors = &self.selectors.producer;
+        let producer_name_selector = &selectors.name;
+        let anime_selector = &selectors.animes;
+        let top10_animes_selector = &selectors.top10;
+        let top_airing_selector = &selectors.top_airing;
+
+        let document = Html::parse_document(page);
 
         res.producer_name = document
             .select(producer_name_selector)
@@ -69,12 +81,20 @@ impl Scraper {
             .and_then(|e| e.text().next())
             .map(|s| s.to_string())
             .unwrap_or(producer_name.to_string());
-        res.total_pages = HiAnimeUtils::get_total_pages(&document);
-        res.has_next_page = HiAnimeUtils::has_next_page(&document);
-        res.animes = HiAnimeUtils::extract_animes(&document, anime_selector);
-        res.top10_animes = HiAnimeUtils::extract_top10_animes(&document, top10_animes_selector);
-        res.top_airing_animes =
-            HiAnimeUtils::extract_most_popular_anime(&document, top_airing_selector);
+        res.total_pages = HiAnimeUtils::get_total_pages(&document, &self.selectors.pagination);
+        res.has_next_page = HiAnimeUtils::has_next_page(&document, &self.selectors.pagination);
+        res.animes =
+            HiAnimeUtils::extract_animes(&document, anime_selector, &self.selectors.anime_card);
+        res.top10_animes = HiAnimeUtils::extract_top10_animes(
+            &document,
+            top10_animes_selector,
+            &self.selectors.top10_card,
+        );
+        res.top_airing_animes = HiAnimeUtils::extract_most_popular_anime(
+            &document,
+            top_airing_selector,
+            &self.selectors.sidebar_card,
+        );
 
         Ok(res)
     }
@@ -85,8 +105,9 @@ mod test {
     use crate::anime::hianime;
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::producer::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::producer::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_producer_animee() {
         let hianime = hianime::Scraper::new();
 
@@ -99,7 +120,7 @@ mod test {
                 println!("{}", to_string_pretty(&data).unwrap());
 
                 assert_eq!(data.total_pages, 12);
-                assert_eq!(data.has_next_page, true);
+                assert!(data.has_next_page);
 
                 assert_ne!(data.animes.len(), 0);
                 assert_ne!(data.top_airing_animes.len(), 0);
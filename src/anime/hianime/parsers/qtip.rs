@@ -1,13 +1,10 @@
 use crate::{
-    anime::hianime::{types::Episodes, utils::HiAnimeUtils, ScrapedQtipInfo, Scraper},
+    anime::hianime::{types::Episodes, utils::HiAnimeUtils, AnimeId, ScrapedQtipInfo, Scraper},
     utils::{EnmaClient, EnmaUtils},
-    EnmaError, EnmaResult,
+    EnmaResult,
 };
-use reqwest::{
-    header::{HeaderMap, HeaderName, HeaderValue, REFERER},
-    StatusCode,
-};
-use scraper::{Html, Selector};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, REFERER};
+use scraper::Html;
 
 impl Scraper {
     /// ### Example Usage
@@ -24,21 +21,19 @@ impl Scraper {
     ///     }
     /// }
     ///  ```
-    pub async fn get_qtip_info(&self, anime_id: &'static str) -> EnmaResult<ScrapedQtipInfo> {
+    #[tracing::instrument(name = "hianime:get_qtip_info", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn get_qtip_info(&self, anime_id: &str) -> EnmaResult<ScrapedQtipInfo> {
         const PROVIDER_PARSER: &'static str = "hianime:get_qtip_info";
 
-        let anime_id = anime_id.trim();
-        let id = anime_id.split('-').last().unwrap_or_default();
-
-        if id.is_empty() {
-            return Err(EnmaError::invalid_data_error(
-                PROVIDER_PARSER,
-                Some(String::from("invalid anime id")),
-                Some(StatusCode::BAD_REQUEST),
-            ));
-        }
+        let anime_id = anime_id
+            .parse::<AnimeId>()
+            .map_err(|e| e.into_enma_error(PROVIDER_PARSER))?;
 
-        let url = format!("{}/{id}", HiAnimeUtils::QtipUrl.value());
+        let url = format!(
+            "{}/{}",
+            HiAnimeUtils::QtipUrl.value(),
+            anime_id.numeric_id()
+        );
         let headers: HeaderMap = [
             (
                 REFERER,
@@ -56,20 +51,29 @@ impl Scraper {
             .client
             .get_html(url, Some(headers), PROVIDER_PARSER)
             .await?;
-        let document = &Html::parse_document(&page);
+        let res = self.parse_qtip_info(&page)?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        Ok(res)
+    }
 
-        let id_selector = &Selector::parse(".pre-qtip-button a.btn-play").unwrap();
-        let name_selector = &Selector::parse(".pre-qtip-title").unwrap();
-        let mal_score_selector = &Selector::parse(".pre-qtip-detail .pqd-li").unwrap();
-        let quality_selector = &Selector::parse(".tick .tick-quality").unwrap();
-        let anime_type_selector = &Selector::parse(".badge.badge-quality").unwrap();
-        let description_selector = &Selector::parse(".pre-qtip-description").unwrap();
-        let sub_episodes_selector = &Selector::parse(".tick .tick-sub").unwrap();
-        let dub_episodes_selector = &Selector::parse(".tick .tick-dub").unwrap();
+    /// parses a raw qtip popup
+    pub(crate) fn parse_qtip_info(&self, page: &str) -> EnmaResult<ScrapedQtipInfo> {
+        let document = &Html::parse_document(page);
 
-        let other_info_selector = &Selector::parse(".pre-qtip-content .pre-qtip-line").unwrap();
-        let sub_other_info_selector = &Selector::parse(".stick").unwrap();
-        let genres_selector = &Selector::parse(".stick-text").unwrap();
+        let selectors = &self.selectors.qtip;
+        let id_selector = &selectors.id;
+        let name_selector = &selectors.name;
+        let mal_score_selector = &selectors.mal_score;
+        let quality_selector = &selectors.quality;
+        let anime_type_selector = &selectors.anime_type;
+        let description_selector = &selectors.description;
+        let sub_episodes_selector = &selectors.sub_episodes;
+        let dub_episodes_selector = &selectors.dub_episodes;
+
+        let other_info_selector = &selectors.other_info;
+        let sub_other_info_selector = &selectors.other_info_key;
+        let genres_selector = &selectors.other_info_value;
 
         let mut res = ScrapedQtipInfo::default();
 
@@ -77,8 +81,7 @@ impl Scraper {
             .select(id_selector)
             .next()
             .and_then(|el| el.value().attr("href"))
-            .map(|s| s.split('/').last().map(|s| s.to_string()))
-            .flatten();
+            .and_then(AnimeId::from_href);
         let name = document
             .select(name_selector)
             .next()
@@ -95,8 +98,7 @@ impl Scraper {
             .select(mal_score_selector)
             .next()
             .and_then(|el| el.text().next())
-            .map(|s| s.trim().parse::<f64>().ok())
-            .flatten();
+            .and_then(|s| s.trim().parse::<f64>().ok());
 
         let description = document
             .select(description_selector)
@@ -137,9 +139,11 @@ impl Scraper {
                     .map(|s| s.to_string())
                     .unwrap_or_default()
             } else {
-                el.text().collect::<String>().trim()[key.len() + 1..]
-                    .trim()
-                    .to_string()
+                el.text()
+                    .collect::<String>()
+                    .split_once(':')
+                    .map(|(_, genres)| genres.trim().to_string())
+                    .unwrap_or_default()
             };
 
             match key.as_str() {
@@ -175,8 +179,9 @@ mod test {
     use crate::anime::hianime;
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::qtip::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::qtip::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_qtip_info() {
         let hianime = hianime::Scraper::new();
         let anime_id = "attack-on-titan-112";
@@ -0,0 +1,281 @@
+use crate::{
+    anime::hianime::{
+        parsers::types::ScrapedAnimeInfo, utils::HiAnimeUtils, AnimeId, Scraper, SearchFilters,
+    },
+    error::EnmaResult,
+    utils::{EnmaClient, EnmaUtils},
+    EnmaError,
+};
+use reqwest::{
+    header::{HeaderMap, HeaderValue, REFERER},
+    StatusCode,
+};
+use scraper::Html;
+
+impl Scraper {
+    /// gets a random anime; without `filters` the source's `/random` redirect is
+    /// followed, otherwise a random entry of a random filtered page is picked
+    ///
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::hianime::{self, SearchFilter, SearchFilters};
+    ///     let hianime = hianime::Scraper::new();
+    ///
+    ///     let filters = SearchFilters::new(vec![
+    ///         SearchFilter::Type(String::from("tv")),
+    ///         SearchFilter::Genres(String::from("comedy")),
+    ///     ]);
+    ///
+    ///     match hianime.get_random_anime(Some(filters)).await {
+    ///         Ok(data) => println!("{data:#?}"),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "hianime:get_random_anime", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn get_random_anime(
+        &self,
+        filters: Option<SearchFilters>,
+    ) -> EnmaResult<ScrapedAnimeInfo> {
+        const PROVIDER_PARSER: &'static str = "hianime:get_random_anime";
+
+        let filters = filters.unwrap_or_default();
+
+        let anime_id = if filters.is_empty() {
+            let url = self
+                .client
+                .get_final_url(
+                    HiAnimeUtils::RandomUrl.value().to_string(),
+                    None,
+                    PROVIDER_PARSER,
+                )
+                .await?;
+
+            AnimeId::from_href(&url).ok_or_else(|| {
+                EnmaError::parsing_error(
+                    PROVIDER_PARSER,
+                    Some(format!("couldn't resolve a random anime id, got '{url}'")),
+                    None,
+                )
+            })?
+        } else {
+            self.get_random_filtered_anime_id(&filters, PROVIDER_PARSER)
+                .await?
+        };
+
+        return self.get_info(&anime_id.to_string()).await;
+    }
+
+    async fn get_random_filtered_anime_id(
+        &self,
+        filters: &SearchFilters,
+        provider_parser: &'static str,
+    ) -> EnmaResult<AnimeId> {
+        let headers: HeaderMap = [(
+            REFERER,
+            HeaderValue::from_static(HiAnimeUtils::HomeUrl.value()),
+        )]
+        .into_iter()
+        .collect();
+        let filter_url = |page: u16| {
+            format!(
+                "{}?{}&page={page}",
+                HiAnimeUtils::FilterUrl.value(),
+                filters.to_query_params()
+            )
+        };
+
+        let page = self
+            .client
+            .get_html(filter_url(1), Some(headers.clone()), provider_parser)
+            .await?;
+        let (total_pages, mut anime_ids) = self.extract_filter_page(&page);
+
+        let page_number = EnmaUtils::random_index(total_pages as usize) as u16 + 1;
+        if page_number != 1 {
+            let page = self
+                .client
+                .get_html(filter_url(page_number), Some(headers), provider_parser)
+                .await?;
+            let (_, ids) = self.extract_filter_page(&page);
+            if !ids.is_empty() {
+                anime_ids = ids;
+            }
+        }
+
+        if anime_ids.is_empty() {
+            return Err(EnmaError::invalid_data_error(
+                provider_parser,
+                Some(String::from("no anime matches the given filters")),
+                Some(StatusCode::NOT_FOUND),
+            ));
+        }
+
+        let idx = EnmaUtils::random_index(anime_ids.len());
+        return Ok(anime_ids.swap_remove(idx));
+    }
+
+    /// 0th -> total pages, 1st -> anime ids of the page
+    fn extract_filter_page(&self, page: &str) -> (u16, Vec<AnimeId>) {
+        let document = Html::parse_document(page);
+        let selectors = &self.selectors.random;
+        let animes_selector = &selectors.animes;
+
+        let anime_ids =
+            HiAnimeUtils::extract_animes(&document, animes_selector, &self.selectors.anime_card)
+                .into_iter()
+                .filter_map(|anime| anime.id)
+                .collect();
+
+        return (
+            HiAnimeUtils::get_total_pages(&document, &self.selectors.pagination),
+            anime_ids,
+        );
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        anime::hianime::{self, SearchFilter, SearchFilters},
+        test_utils::Routes,
+    };
+    use reqwest::StatusCode;
+    use serde_json::to_string_pretty;
+
+    const PROVIDER_PARSER: &'static str = "hianime:get_random_anime";
+
+    /// a filter page listing `anime_ids`, announcing `total_pages` if any
+    fn filter_page(anime_ids: &[&str], total_pages: Option<u16>) -> String {
+        let animes = anime_ids
+            .iter()
+            .map(|id| {
+                format!(
+                    r#"<div class="flw-item"><div class="film-detail"><h3 class="film-name">
+                        <a class="dynamic-name" href="/{id}">{id}</a>
+                    </h3></div></div>"#
+                )
+            })
+            .collect::<String>();
+        let pagination = total_pages
+            .map(|last| {
+                format!(
+                    r#"<ul class="pagination">
+                        <li class="page-item"><a title="Last" href="/filter?type=2&page={last}">»</a></li>
+                    </ul>"#
+                )
+            })
+            .unwrap_or_default();
+
+        return format!(
+            r#"<html><body><div id="main-content">
+                <div class="tab-content"><div class="film_list-wrap">{animes}</div></div>
+                {pagination}
+            </div></body></html>"#
+        );
+    }
+
+    fn filters() -> SearchFilters {
+        return SearchFilters::new(vec![SearchFilter::Type(String::from("movie"))]);
+    }
+
+    fn first_page_target() -> String {
+        return format!("/filter?{}&page=1", filters().to_query_params());
+    }
+
+    #[tokio::test]
+    async fn test_random_filtered_without_total_pages() {
+        let server = Routes::new()
+            .route(
+                &first_page_target(),
+                filter_page(&["your-name-10"], Some(0)),
+            )
+            .serve()
+            .await;
+        let hianime = hianime::Scraper::new().with_origin(server.base());
+
+        let anime_id = hianime
+            .get_random_filtered_anime_id(&filters(), PROVIDER_PARSER)
+            .await
+            .unwrap();
+
+        assert_eq!(anime_id.to_string(), "your-name-10");
+        assert_eq!(server.hits("/filter"), 1);
+    }
+
+    #[tokio::test]
+    async fn test_random_filtered_empty_page_falls_back() {
+        // every page past the first comes back empty, with this many pages
+        // the random pick practically never lands on the first one
+        let server = Routes::new()
+            .route(
+                &first_page_target(),
+                filter_page(&["your-name-10"], Some(u16::MAX)),
+            )
+            .route("/filter", filter_page(&[], Some(u16::MAX)))
+            .serve()
+            .await;
+        let hianime = hianime::Scraper::new().with_origin(server.base());
+
+        let anime_id = hianime
+            .get_random_filtered_anime_id(&filters(), PROVIDER_PARSER)
+            .await
+            .unwrap();
+
+        assert_eq!(anime_id.to_string(), "your-name-10");
+        assert_eq!(server.hits("/filter"), 2);
+    }
+
+    #[tokio::test]
+    async fn test_random_filtered_no_match() {
+        let server = Routes::new()
+            .route("/filter", filter_page(&[], None))
+            .serve()
+            .await;
+        let hianime = hianime::Scraper::new().with_origin(server.base());
+
+        let err = hianime
+            .get_random_filtered_anime_id(&filters(), PROVIDER_PARSER)
+            .await
+            .unwrap_err();
+
+        assert_eq!(err.details().status, StatusCode::NOT_FOUND.as_u16());
+        assert_eq!(server.hits("/filter"), 1);
+    }
+
+    // cargo test --lib -- anime::hianime::parsers::random::test --include-ignored --show-output
+    #[tokio::test]
+    #[ignore = "hits the live site"]
+    async fn test_get_random_anime() {
+        let hianime = hianime::Scraper::new();
+
+        match hianime.get_random_anime(None).await {
+            // Ok(_) => (),
+            Ok(data) => {
+                println!("{}", to_string_pretty(&data).unwrap());
+
+                assert_ne!(data.anime.id, None);
+                assert_ne!(data.anime.name, None);
+            }
+            Err(e) => eprintln!("error {}", e),
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "hits the live site"]
+    async fn test_get_random_anime_with_filters() {
+        let hianime = hianime::Scraper::new();
+        let filters = SearchFilters::new(vec![SearchFilter::Type(String::from("movie"))]);
+
+        match hianime.get_random_anime(Some(filters)).await {
+            // Ok(_) => (),
+            Ok(data) => {
+                println!("{}", to_string_pretty(&data).unwrap());
+
+                assert_eq!(data.anime.stats.anime_type, Some(String::from("Movie")));
+            }
+            Err(e) => eprintln!("error {}", e),
+        }
+    }
+}
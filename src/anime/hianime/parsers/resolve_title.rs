@@ -0,0 +1,349 @@
+use crate::{
+    anime::hianime::{
+        matching, parsers::types::ScrapedTitleMatches, types::TitleMatch, AnimeId, Scraper,
+        TitleHints,
+    },
+    error::EnmaResult,
+    EnmaError, SchemaVersion,
+};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+
+/// how many of the best candidates get their air year checked
+const MAX_YEAR_CHECKS: usize = 5;
+
+const HINT_MATCH_BONUS: f32 = 0.1;
+const HINT_CLOSE_BONUS: f32 = 0.05;
+const HINT_MISMATCH_PENALTY: f32 = 0.15;
+
+impl Scraper {
+    /// ranks search results against a free-text title, comparing both the
+    /// name and japanese name, and adjusting scores with the given hints
+    ///
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::hianime::{self, TitleHints};
+    ///     let hianime = hianime::Scraper::new();
+    ///
+    ///     let query = "Attack on Titan S2";
+    ///     let hints = TitleHints {
+    ///         anime_type: Some(String::from("TV")),
+    ///         episodes: Some(12),
+    ///         ..Default::default()
+    ///     };
+    ///
+    ///     match hianime.resolve_title(query, Some(hints)).await {
+    ///         Ok(data) => println!("{data:#?}"),
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "hianime:resolve_title", skip(self), fields(items = tracing::field::Empty))]
+    pub async fn resolve_title(
+        &self,
+        query: &str,
+        hints: Option<TitleHints>,
+    ) -> EnmaResult<ScrapedTitleMatches> {
+        const PROVIDER_PARSER: &'static str = "hianime:resolve_title";
+
+        let query = query.trim();
+        if query.is_empty() {
+            return Err(EnmaError::invalid_data_error(
+                PROVIDER_PARSER,
+                Some(String::from("invalid title")),
+                Some(StatusCode::BAD_REQUEST),
+            ));
+        }
+        let hints = hints.unwrap_or_default();
+
+        // the source's search is rather literal, so `Attack on Titan S2` is
+        // also searched as `attack on titan` to get all seasons as candidates
+        let mut search_queries = vec![query.to_string()];
+        if matching::normalize_title(query).season.is_some() {
+            let base_title = matching::base_title(query);
+            if !base_title.is_empty() {
+                search_queries.push(base_title);
+            }
+        }
+
+        let mut candidates: HashMap<AnimeId, TitleMatch> = HashMap::new();
+        let mut last_err = None;
+
+        for search_query in search_queries.iter() {
+            let animes = match self.get_search_results(search_query, None, None).await {
+                Ok(data) => data.animes,
+                Err(e) => {
+                    last_err = Some(e);
+                    continue;
+                }
+            };
+
+            for anime in animes {
+                let Some(anime_id) = anime.id else {
+                    continue;
+                };
+                if candidates.contains_key(&anime_id) {
+                    continue;
+                }
+
+                let title_score = [&anime.name, &anime.jname]
+                    .into_iter()
+                    .flatten()
+                    .map(|name| matching::title_score(query, name))
+                    .fold(0.0, f32::max);
+
+                candidates.insert(
+                    anime_id.clone(),
+                    TitleMatch {
+                        anime_id,
+                        title_score,
+                        score: title_score,
+                        name: anime.name,
+                        jname: anime.jname,
+                        anime_type: anime.anime_type,
+                        episodes: anime.episodes,
+                    },
+                );
+            }
+        }
+
+        if candidates.is_empty() {
+            if let Some(e) = last_err {
+                return Err(e);
+            }
+        }
+
+        let mut matches = candidates.into_values().collect::<Vec<_>>();
+        for candidate in matches.iter_mut() {
+            candidate.score += Self::type_and_episodes_adjustment(candidate, &hints);
+        }
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        if let Some(year) = hints.year {
+            for candidate in matches.iter_mut().take(MAX_YEAR_CHECKS) {
+                let Ok(qtip) = self.get_qtip_info(&candidate.anime_id.to_string()).await else {
+                    continue;
+                };
+                if let Some(aired) = qtip.anime.aired {
+                    if aired.contains(&year.to_string()) {
+                        candidate.score += HINT_MATCH_BONUS;
+                    } else {
+                        candidate.score -= HINT_MISMATCH_PENALTY;
+                    }
+                }
+            }
+        }
+
+        for candidate in matches.iter_mut() {
+            candidate.score = candidate.score.clamp(0.0, 1.0);
+        }
+        matches.sort_by(|a, b| b.score.total_cmp(&a.score));
+
+        Ok(ScrapedTitleMatches {
+            schema_version: SchemaVersion::CURRENT,
+            query: query.to_string(),
+            matches,
+        })
+    }
+
+    fn type_and_episodes_adjustment(candidate: &TitleMatch, hints: &TitleHints) -> f32 {
+        let mut adjustment = 0.0;
+
+        if let (Some(hint), Some(anime_type)) = (&hints.anime_type, &candidate.anime_type) {
+            if hint.trim().eq_ignore_ascii_case(anime_type.trim()) {
+                adjustment += HINT_MATCH_BONUS;
+            } else {
+                adjustment -= HINT_MISMATCH_PENALTY;
+            }
+        }
+
+        let episodes = candidate.episodes.sub.max(candidate.episodes.dub);
+        if let (Some(hint), Some(episodes)) = (hints.episodes, episodes) {
+            let diff = hint.abs_diff(episodes);
+            if diff == 0 {
+                adjustment += HINT_MATCH_BONUS;
+            } else if diff <= (hint / 10).max(1) {
+                adjustment += HINT_CLOSE_BONUS;
+            } else {
+                adjustment -= HINT_MISMATCH_PENALTY;
+            }
+        }
+
+        return adjustment;
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::{
+        anime::hianime::{self, TitleHints},
+        test_utils::{Routes, TestServer},
+    };
+    use serde_json::to_string_pretty;
+
+    /// (anime id, name, type, episodes, aired)
+    const CANDIDATES: [(&str, &str, &str, u16, &str); 7] = [
+        ("monster-movie-5", "Monster", "Movie", 1, "Aug 1, 2010"),
+        (
+            "monster-37",
+            "Monster TV",
+            "TV",
+            74,
+            "Apr 7, 2004 to Sep 26, 2005",
+        ),
+        ("monster-2024-8", "Monster TV", "TV", 74, "Apr 4, 2024 to ?"),
+        (
+            "monster-special-9",
+            "Monster Special",
+            "Special",
+            2,
+            "Dec 1, 2005",
+        ),
+        (
+            "monster-strike-11",
+            "Monster Strike",
+            "ONA",
+            50,
+            "Oct 10, 2015",
+        ),
+        (
+            "monster-musume-12",
+            "Monster Musume",
+            "TV",
+            12,
+            "Jul 8, 2015",
+        ),
+        (
+            "monster-hunter-13",
+            "Monster Hunter Stories",
+            "TV",
+            75,
+            "Oct 2, 2016",
+        ),
+    ];
+
+    /// serves a search page listing every candidate and their qtip popups
+    async fn candidates_server() -> TestServer {
+        let cards = CANDIDATES
+            .iter()
+            .map(|(id, name, anime_type, episodes, _)| {
+                format!(
+                    r#"<div class="flw-item">
+                        <div class="film-poster"><div class="tick-sub">{episodes}</div></div>
+                        <div class="film-detail">
+                            <h3 class="film-name"><a class="dynamic-name" href="/{id}">{name}</a></h3>
+                            <div class="fd-infor"><span class="fdi-item">{anime_type}</span></div>
+                        </div>
+                    </div>"#
+                )
+            })
+            .collect::<String>();
+        let search_page = format!(
+            r#"<html><body><div id="main-content">
+                <div class="tab-content"><div class="film_list-wrap">{cards}</div></div>
+            </div></body></html>"#
+        );
+
+        let mut routes = Routes::new().route("/search", search_page);
+        for (id, _, _, _, aired) in CANDIDATES {
+            let numeric_id = id.rsplit('-').next().unwrap();
+            routes = routes.route(
+                &format!("/ajax/movie/qtip/{numeric_id}"),
+                format!(
+                    r#"<div class="pre-qtip-content"><div class="pre-qtip-line">
+                        <span class="stick">Aired:</span><span class="stick-text">{aired}</span>
+                    </div></div>"#
+                ),
+            );
+        }
+
+        return routes.serve().await;
+    }
+
+    fn qtip_hits(server: &TestServer, anime_id: &str) -> usize {
+        let numeric_id = anime_id.rsplit('-').next().unwrap();
+        return server.hits(&format!("/ajax/movie/qtip/{numeric_id}"));
+    }
+
+    fn ranking(data: &hianime::ScrapedTitleMatches) -> Vec<String> {
+        return data
+            .matches
+            .iter()
+            .map(|m| m.anime_id.to_string())
+            .collect();
+    }
+
+    #[tokio::test]
+    async fn test_resolve_title_hints() {
+        let server = candidates_server().await;
+        let hianime = hianime::Scraper::new().with_origin(server.base());
+
+        // the exact name wins on the title alone
+        let data = hianime.resolve_title("Monster", None).await.unwrap();
+        assert_eq!(ranking(&data)[0], "monster-movie-5");
+
+        // the type and episodes lift both tv series above the movie
+        let hints = TitleHints {
+            anime_type: Some(String::from("TV")),
+            episodes: Some(74),
+            year: None,
+        };
+        let data = hianime.resolve_title("Monster", Some(hints)).await.unwrap();
+        let mut top = ranking(&data)[..2].to_vec();
+        top.sort();
+        assert_eq!(top, ["monster-2024-8", "monster-37"]);
+        assert_eq!(data.matches[0].score, data.matches[1].score);
+        assert_eq!(ranking(&data)[2], "monster-movie-5");
+        assert_eq!(qtip_hits(&server, "monster-37"), 0);
+
+        // the year, read from the qtips, tells them apart
+        let hints = TitleHints {
+            anime_type: Some(String::from("TV")),
+            episodes: Some(74),
+            year: Some(2024),
+        };
+        let data = hianime.resolve_title("Monster", Some(hints)).await.unwrap();
+        assert_eq!(
+            ranking(&data)[..3],
+            ["monster-2024-8", "monster-37", "monster-movie-5"]
+        );
+        assert!(data.matches[0].score > data.matches[1].score);
+
+        // only the five best candidates get their qtip fetched
+        for (anime_id, ..) in CANDIDATES {
+            let expected = match anime_id {
+                "monster-strike-11" | "monster-special-9" => 0,
+                _ => 1,
+            };
+            assert_eq!(qtip_hits(&server, anime_id), expected, "{anime_id}");
+        }
+    }
+
+    // cargo test --lib -- anime::hianime::parsers::resolve_title::test --include-ignored --show-output
+    #[tokio::test]
+    #[ignore = "hits the live site"]
+    async fn test_resolve_title() {
+        let hianime = hianime::Scraper::new();
+        let query = "Attack on Titan S2";
+        let hints = TitleHints {
+            anime_type: Some(String::from("TV")),
+            episodes: Some(12),
+            year: Some(2017),
+        };
+
+        match hianime.resolve_title(query, Some(hints)).await {
+            // Ok(_) => (),
+            Ok(data) => {
+                println!("{}", to_string_pretty(&data).unwrap());
+
+                assert_ne!(data.matches.len(), 0);
+                assert_eq!(
+                    data.matches.first().map(|m| m.anime_id.to_string()),
+                    Some(String::from("attack-on-titan-season-2-189"))
+                );
+            }
+            Err(e) => eprintln!("error {}", e),
+        }
+    }
+}
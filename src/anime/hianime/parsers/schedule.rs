@@ -1,13 +1,14 @@
 use crate::{
     anime::hianime::{
-        parsers::types::ScrapedSchedule, types::ScheduledAnime, utils::HiAnimeUtils, Scraper,
+        parsers::types::ScrapedSchedule, types::ScheduledAnime, utils::HiAnimeUtils, AnimeId,
+        Scraper,
     },
     error::EnmaResult,
     utils::{EnmaClient, EnmaUtils},
 };
 use chrono::{DateTime, Utc};
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, REFERER};
-use scraper::{Html, Selector};
+use scraper::Html;
 use serde::Deserialize;
 
 /// represents the raw data received from the source
@@ -31,11 +32,10 @@ impl Scraper {
     ///     }
     /// }
     ///  ```
+    #[tracing::instrument(name = "hianime:get_schedule", skip(self), fields(items = tracing::field::Empty))]
     pub async fn get_schedule(&self, year: u16, month: u8, day: u8) -> EnmaResult<ScrapedSchedule> {
         const PROVIDER_PARSER: &'static str = "hianime:get_schedule";
-        const NO_DATA_INDICATOR: &'static str = "No data to display";
 
-        let mut res = ScrapedSchedule::default();
         let formatted_date = format!("{}-{:02}-{:02}", year, month, day);
 
         let url = format!(
@@ -61,9 +61,26 @@ impl Scraper {
             .client
             .get_json::<RawData>(url, Some(headers), PROVIDER_PARSER)
             .await?;
+        let res = self.parse_schedule(&data.html.unwrap_or_default(), &formatted_date)?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.animes.len());
+        Ok(res)
+    }
+
+    /// parses the `html` of the raw schedule json of `formatted_date`,
+    /// a `YYYY-MM-DD` date
+    pub(crate) fn parse_schedule(
+        &self,
+        html: &str,
+        formatted_date: &str,
+    ) -> EnmaResult<ScrapedSchedule> {
+        const NO_DATA_INDICATOR: &'static str = "No data to display";
 
-        let document = Html::parse_fragment(data.html.unwrap_or_default().as_str());
-        let selector = &Selector::parse("li").unwrap();
+        let mut res = ScrapedSchedule::default();
+        let document = Html::parse_fragment(html);
+        let selectors = &self.selectors.schedule;
+        let selector = &selectors.animes;
 
         if let Some(data) = document
             .select(selector)
@@ -76,18 +93,18 @@ impl Scraper {
             }
         }
 
-        let id_selector = &Selector::parse("li a.tsl-link").unwrap();
-        let name_selector = &Selector::parse("li a .film-name.dynamic-name").unwrap();
+        let id_selector = &selectors.id;
+        let name_selector = &selectors.name;
         let jname_selector = name_selector;
-        let episode_no_selector = &Selector::parse("li a .fd-play button.btn-play").unwrap();
-        let time_selector = &Selector::parse("li a.tsl-link .time").unwrap();
+        let episode_no_selector = &selectors.episode_number;
+        let time_selector = &selectors.time;
 
         for el in document.select(selector) {
             let id = el
                 .select(id_selector)
                 .next()
                 .and_then(|el| el.attr("href"))
-                .map(|href| href.trim()[1..].to_string());
+                .and_then(AnimeId::from_href);
 
             let name = el
                 .select(name_selector)
@@ -146,8 +163,9 @@ mod test {
     use chrono::{Datelike, Utc};
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::schedule::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::schedule::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_schedule() {
         let hianime = hianime::Scraper::new();
 
@@ -10,12 +10,13 @@ use reqwest::{
     header::{HeaderMap, HeaderValue, REFERER},
     StatusCode,
 };
-use scraper::{Html, Selector};
+use scraper::Html;
 
 impl Scraper {
     /// ### Example Usage
     /// ```rust
     /// async fn get_data() {
+    ///     use enma::anime::hianime::{self, SearchFilter, SearchFilters};
     ///     let hianime = hianime::Scraper::new();
     ///     let query = "monster";
     ///     let filters = SearchFilters::new(vec![
@@ -34,9 +35,10 @@ impl Scraper {
     ///     }
     /// }
     ///  ```
+    #[tracing::instrument(name = "hianime:get_search_results", skip(self), fields(items = tracing::field::Empty))]
     pub async fn get_search_results(
         &self,
-        query: &'static str,
+        query: &str,
         page_number: Option<u16>,
         filters: Option<SearchFilters>,
     ) -> EnmaResult<ScrapedSearchResult> {
@@ -51,21 +53,16 @@ impl Scraper {
             ));
         }
 
-        let mut res = ScrapedSearchResult {
-            search_query: query,
-            current_page: page_number.unwrap_or(1).max(1),
-            search_filter: filters.unwrap_or(SearchFilters::default()),
-            ..Default::default()
-        };
+        let current_page = page_number.unwrap_or(1).max(1);
+        let search_filter = filters.unwrap_or_default();
 
         let mut url = format!(
-            "{}?keyword={}&page={}",
+            "{}?keyword={}&page={current_page}",
             HiAnimeUtils::SearchUrl.value(),
             EnmaUtils::decode_uri_component(PROVIDER_PARSER, query.to_string())?,
-            res.current_page
         );
-        if !res.search_filter.is_empty() {
-            url = format!("{url}&{}", res.search_filter.to_query_params())
+        if !search_filter.is_empty() {
+            url = format!("{url}&{}", search_filter.to_query_params())
         }
 
         let headers: HeaderMap = [(
@@ -79,20 +76,43 @@ impl Scraper {
             .client
             .get_html(url, Some(headers), PROVIDER_PARSER)
             .await?;
-        let document = Html::parse_fragment(&data);
+        let res = self.parse_search_results(&data, query, current_page, search_filter)?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.animes.len());
+        Ok(res)
+    }
+
+    /// parses a raw search results page
+    pub(crate) fn parse_search_results(
+        &self,
+        page: &str,
+        query: &str,
+        current_page: u16,
+        search_filter: SearchFilters,
+    ) -> EnmaResult<ScrapedSearchResult> {
+        let mut res = ScrapedSearchResult {
+            search_query: query.to_string(),
+            current_page,
+            search_filter,
+            ..Default::default()
+        };
+
+        let document = Html::parse_fragment(page);
 
-        let animes_selector =
-            &Selector::parse("#main-content .tab-content .film_list-wrap .flw-item").unwrap();
-        let most_popular_selector = &Selector::parse(
-            "#main-sidebar .block_area.block_area_sidebar.block_area-realtime .anif-block-ul ul li",
-        )
-        .unwrap();
+        let selectors = &self.selectors.search;
+        let animes_selector = &selectors.animes;
+        let most_popular_selector = &selectors.most_popular;
 
-        res.total_pages = HiAnimeUtils::get_total_pages(&document);
-        res.has_next_page = HiAnimeUtils::has_next_page(&document);
-        res.animes = HiAnimeUtils::extract_animes(&document, animes_selector);
-        res.most_popular_animes =
-            HiAnimeUtils::extract_most_popular_anime(&document, most_popular_selector);
+        res.total_pages = HiAnimeUtils::get_total_pages(&document, &self.selectors.pagination);
+        res.has_next_page = HiAnimeUtils::has_next_page(&document, &self.selectors.pagination);
+        res.animes =
+            HiAnimeUtils::extract_animes(&document, animes_selector, &self.selectors.anime_card);
+        res.most_popular_animes = HiAnimeUtils::extract_most_popular_anime(
+            &document,
+            most_popular_selector,
+            &self.selectors.sidebar_card,
+        );
 
         Ok(res)
     }
@@ -103,8 +123,9 @@ mod test {
     use crate::anime::hianime::{self, SearchFilter, SearchFilters};
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::search::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::search::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_search_results() {
         let hianime = hianime::Scraper::new();
         let query = "monster";
@@ -1,13 +1,13 @@
 use crate::{
     anime::hianime::{
         parsers::types::ScrapedSearchSuggestion, types::AnimeSearchSuggestion, utils::HiAnimeUtils,
-        Scraper,
+        AnimeId, Scraper,
     },
     error::EnmaResult,
     utils::{EnmaClient, EnmaUtils},
 };
 use reqwest::header::{HeaderMap, HeaderName, HeaderValue, ACCEPT, PRAGMA, REFERER};
-use scraper::{Html, Selector};
+use scraper::Html;
 use serde::Deserialize;
 
 /// represents the raw data received from the source
@@ -46,9 +46,9 @@ impl Scraper {
     ///   ]
     /// }
     /// ```
+    #[tracing::instrument(name = "hianime:get_search_suggestions", skip(self), fields(items = tracing::field::Empty))]
     pub async fn get_search_suggestions(&self, query: &str) -> EnmaResult<ScrapedSearchSuggestion> {
         const PROVIDER_PARSER: &'static str = "hianime:get_search_suggestions";
-        let mut res = ScrapedSearchSuggestion::default();
 
         let url = format!(
             "{}?keyword={}",
@@ -70,25 +70,36 @@ impl Scraper {
         .into_iter()
         .collect();
 
-        let selector = &Selector::parse(".nav-item:has(.film-poster)").unwrap();
-        let name_selector = &Selector::parse(".srp-detail .film-name").unwrap();
-        let jname_selector = &Selector::parse(".srp-detail .film-name").unwrap();
-        let poster_selector = &Selector::parse(".film-poster .film-poster-img").unwrap();
-        let other_info_selector = &Selector::parse(".film-infor").unwrap();
-
         let data = self
             .client
             .get_json::<RawData>(url, Some(headers), PROVIDER_PARSER)
             .await?;
 
-        let document = Html::parse_fragment(data.html.unwrap_or_default().as_str());
+        let res = self.parse_search_suggestions(&data.html.unwrap_or_default())?;
+
+        HiAnimeUtils::warn_unhealthy(&res);
+        HiAnimeUtils::record_items(res.suggestions.len());
+        Ok(res)
+    }
+
+    /// parses the `html` of the raw suggestions json
+    pub(crate) fn parse_search_suggestions(
+        &self,
+        html: &str,
+    ) -> EnmaResult<ScrapedSearchSuggestion> {
+        let mut res = ScrapedSearchSuggestion::default();
+
+        let selectors = &self.selectors.search_suggestions;
+        let selector = &selectors.suggestions;
+        let name_selector = &selectors.name;
+        let jname_selector = &selectors.name;
+        let poster_selector = &selectors.poster;
+        let other_info_selector = &selectors.other_info;
+
+        let document = Html::parse_fragment(html);
 
         for el in document.select(selector) {
-            let id = el
-                .value()
-                .attr("href")
-                .map(|href| href[1..].split("?ref=search").next().map(|s| s.to_string()))
-                .flatten();
+            let id = el.value().attr("href").and_then(AnimeId::from_href);
 
             let name = el
                 .select(name_selector)
@@ -137,8 +148,9 @@ mod test {
     use crate::anime::hianime;
     use serde_json::to_string_pretty;
 
-    // cargo test --lib -- anime::hianime::parsers::search_suggestions::test --show-output
+    // cargo test --lib -- anime::hianime::parsers::search_suggestions::test --include-ignored --show-output
     #[tokio::test]
+    #[ignore = "hits the live site"]
     async fn test_get_search_suggestions() {
         let hianime = hianime::Scraper::new();
         let query = "monster";
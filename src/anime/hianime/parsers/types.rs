@@ -1,16 +1,22 @@
 use crate::anime::hianime::{
     types::{
-        Anime, AnimeDetailedInfo, AnimeEpisode, AnimeSearchSuggestion, DubEpisode,
-        LatestCompletedAnime, LatestEpisodeAnime, MostFavoriteAnime, MostPopularAnime, QtipAnime,
-        RawEpisode, RecommendedAnime, RelatedAnime, ScheduledAnime, SpotlightAnime, SubEpisode,
-        Top10AnimesWithPeriod, TopAiringAnime, TopUpcomingAnime, TrendingAnime,
+        Anime, AnimeCharacterWithVoiceActors, AnimeDetailedInfo, AnimeEpisode,
+        AnimeSearchSuggestion, CharacterDetailedInfo, DubEpisode, FranchiseEdge, FranchiseNode,
+        IdMatch, LatestCompletedAnime, LatestEpisodeAnime, MostFavoriteAnime, MostPopularAnime,
+        PersonDetailedInfo, QtipAnime, RawEpisode, RecommendedAnime, RelatedAnime, ScheduledAnime,
+        SpotlightAnime, SubEpisode, TitleMatch, Top10AnimesWithPeriod, TopAiringAnime,
+        TopUpcomingAnime, TrendingAnime,
     },
-    SearchFilters,
+    AnimeId, EpisodeId, SearchFilters,
 };
-use serde::Serialize;
+use crate::SchemaVersion;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedHomePage {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     pub genres: Vec<String>,
     pub top10_animes: Top10AnimesWithPeriod,
     pub spotlight_animes: Vec<SpotlightAnime>,
@@ -23,27 +29,39 @@ pub struct ScrapedHomePage {
     pub latest_completed_animes: Vec<LatestCompletedAnime>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedSearchSuggestion {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     pub suggestions: Vec<AnimeSearchSuggestion>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedAZList {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     pub animes: Vec<Anime>,
-    pub sort_option: &'static str,
+    pub sort_option: String,
     pub total_pages: u16,
     pub current_page: u16,
     pub has_next_page: bool,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedQtipInfo {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     pub anime: QtipAnime,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedCategoryAnime {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     pub category_name: String,
     pub animes: Vec<Anime>,
     pub total_pages: u16,
@@ -53,8 +71,11 @@ pub struct ScrapedCategoryAnime {
     pub top10_animes: Top10AnimesWithPeriod,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedProducerAnime {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     pub producer_name: String,
     pub animes: Vec<Anime>,
     pub total_pages: u16,
@@ -64,8 +85,11 @@ pub struct ScrapedProducerAnime {
     pub top10_animes: Top10AnimesWithPeriod,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedGenreAnime {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     pub genre_name: String,
     pub animes: Vec<Anime>,
     pub genres: Vec<String>,
@@ -75,37 +99,52 @@ pub struct ScrapedGenreAnime {
     pub top_airing_animes: Vec<TopAiringAnime>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedAnimeEpisodes {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     pub total_episodes: Option<u16>,
     pub episodes: Vec<AnimeEpisode>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedEpisodeServers {
-    pub anime_episode_id: &'static str,
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
+    pub anime_episode_id: EpisodeId,
     pub episode_number: Option<u16>,
     pub sub: Vec<SubEpisode>,
     pub dub: Vec<DubEpisode>,
     pub raw: Vec<RawEpisode>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedSchedule {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     pub animes: Vec<ScheduledAnime>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedAnimeInfo {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
     pub anime: AnimeDetailedInfo,
     pub related_animes: Vec<RelatedAnime>,
     pub recommended_animes: Vec<RecommendedAnime>,
     pub most_popular_animes: Vec<MostPopularAnime>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScrapedSearchResult {
-    pub search_query: &'static str,
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
+    pub search_query: String,
     pub search_filter: SearchFilters,
     pub animes: Vec<Anime>,
     pub total_pages: u16,
@@ -114,3 +153,208 @@ pub struct ScrapedSearchResult {
 
     pub most_popular_animes: Vec<MostPopularAnime>,
 }
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ScrapedCharacterInfo {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
+    pub character: CharacterDetailedInfo,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ScrapedPersonInfo {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
+    pub person: PersonDetailedInfo,
+    /// number of voice acting role pages that were scraped
+    pub total_role_pages: u16,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ScrapedAnimeCharacters {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
+    pub anime_id: AnimeId,
+    pub characters: Vec<AnimeCharacterWithVoiceActors>,
+    pub total_pages: u16,
+    pub current_page: u16,
+    pub has_next_page: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ScrapedFranchise {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
+    pub anime_id: AnimeId,
+    pub nodes: Vec<FranchiseNode>,
+    pub edges: Vec<FranchiseEdge>,
+    /// node ids ordered by their air date, unknown dates last
+    pub watch_order: Vec<AnimeId>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ScrapedIdMapping {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
+    pub mal_id: Option<u32>,
+    pub anilist_id: Option<u32>,
+    /// candidates sorted by confidence, highest first
+    pub matches: Vec<IdMatch>,
+    /// whether the mapping was served from the id cache
+    pub from_cache: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ScrapedExternalIds {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
+    pub anime_id: AnimeId,
+    pub mal_id: Option<u32>,
+    pub anilist_id: Option<u32>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct ScrapedTitleMatches {
+    #[serde(default = "SchemaVersion::unversioned")]
+    pub schema_version: SchemaVersion,
+    pub query: String,
+    /// candidates sorted by score, highest first
+    pub matches: Vec<TitleMatch>,
+}
+
+/// JSON Schema documents of every result type, keyed by the type's name
+#[cfg(feature = "schema")]
+pub fn json_schemas() -> Vec<(&'static str, schemars::Schema)> {
+    use crate::anime::hianime::SelfTestReport;
+
+    macro_rules! schemas {
+        ($($ty:ty),* $(,)?) => {
+            vec![$((stringify!($ty), schemars::schema_for!($ty))),*]
+        };
+    }
+
+    return schemas!(
+        ScrapedHomePage,
+        ScrapedSearchSuggestion,
+        ScrapedAZList,
+        ScrapedQtipInfo,
+        ScrapedCategoryAnime,
+        ScrapedProducerAnime,
+        ScrapedGenreAnime,
+        ScrapedAnimeEpisodes,
+        ScrapedEpisodeServers,
+        ScrapedSchedule,
+        ScrapedAnimeInfo,
+        ScrapedSearchResult,
+        ScrapedCharacterInfo,
+        ScrapedPersonInfo,
+        ScrapedAnimeCharacters,
+        ScrapedFranchise,
+        ScrapedIdMapping,
+        ScrapedExternalIds,
+        ScrapedTitleMatches,
+        SelfTestReport,
+    );
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ScrapedAnimeInfo, ScrapedEpisodeServers, ScrapedSearchResult};
+    use crate::{
+        anime::hianime::{
+            types::{EpisodeInfo, OtherInfoValue, Season},
+            SearchFilter, SearchFilters,
+        },
+        SchemaVersion,
+    };
+    use serde::{de::DeserializeOwned, Serialize};
+
+    fn assert_round_trip<T: Serialize + DeserializeOwned>(data: &T) {
+        let json = serde_json::to_value(data).unwrap();
+        let parsed = serde_json::from_value::<T>(json.clone()).unwrap();
+        assert_eq!(serde_json::to_value(&parsed).unwrap(), json);
+    }
+
+    // cargo test --lib -- anime::hianime::parsers::types::test --show-output
+    #[test]
+    fn test_round_trip() {
+        let mut info = ScrapedAnimeInfo::default();
+        info.anime.id = "steinsgate-3".parse().ok();
+        info.anime.seasons.push(Season {
+            id: "steinsgate-0-92".parse().ok(),
+            is_current: false,
+            ..Default::default()
+        });
+        info.anime.other_info.insert(
+            String::from("japanese"),
+            OtherInfoValue::Single(String::from("シュタインズ・ゲート")),
+        );
+        info.anime.other_info.insert(
+            String::from("genres"),
+            OtherInfoValue::Multiple(vec![String::from("Sci-Fi"), String::from("Thriller")]),
+        );
+        assert_round_trip(&info);
+
+        let search = ScrapedSearchResult {
+            search_query: String::from("monster"),
+            search_filter: SearchFilters::new(vec![
+                SearchFilter::Type(String::from("tv")),
+                SearchFilter::Genres(String::from("action,drama")),
+            ]),
+            ..Default::default()
+        };
+        assert_round_trip(&search);
+
+        let servers = ScrapedEpisodeServers {
+            schema_version: SchemaVersion::CURRENT,
+            anime_episode_id: "steinsgate-0-92?ep=2055".parse().unwrap(),
+            episode_number: Some(1),
+            sub: vec![EpisodeInfo {
+                server_name: Some(String::from("hd-1")),
+                server_id: Some(4),
+            }],
+            dub: vec![],
+            raw: vec![],
+        };
+        assert_round_trip(&servers);
+    }
+
+    #[test]
+    fn test_unversioned() {
+        let json = r#"{ "anime_episode_id": "steinsgate-0-92?ep=2055", "episode_number": 1, "sub": [], "dub": [], "raw": [] }"#;
+        let servers = serde_json::from_str::<ScrapedEpisodeServers>(json).unwrap();
+        assert_eq!(servers.schema_version.get(), 0);
+        assert!(!servers.schema_version.is_current());
+
+        assert!(ScrapedAnimeInfo::default().schema_version.is_current());
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn test_json_schemas() {
+        let schemas = super::json_schemas();
+        assert_eq!(schemas.len(), 20);
+
+        for (name, schema) in schemas {
+            let schema = schema.as_value();
+            assert!(
+                schema["properties"]["schema_version"].is_object(),
+                "{name} is missing its schema_version"
+            );
+        }
+
+        let (_, info) = super::json_schemas()
+            .into_iter()
+            .find(|(name, _)| *name == "ScrapedAnimeInfo")
+            .unwrap();
+        println!("{}", serde_json::to_string_pretty(&info).unwrap());
+        assert_eq!(info.as_value()["title"], "ScrapedAnimeInfo");
+    }
+}
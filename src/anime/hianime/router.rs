@@ -0,0 +1,446 @@
+use crate::{
+    anime::hianime::{
+        types::ANIME_CATEGORIES, utils::HiAnimeUtils, AnimeId, EpisodeId, SearchFilter,
+        SearchFilters, AZ_LIST_SORT_OPTIONS, SEARCH_PAGE_FILTERS,
+    },
+    error::EnmaResult,
+    EnmaError,
+};
+use reqwest::{StatusCode, Url};
+use std::{collections::HashMap, fmt::Display};
+
+const PROVIDER_PARSER: &'static str = "hianime:router";
+
+/// query param key, filter variant and the param's name to id map
+type IdMappedFilter = (
+    &'static str,
+    fn(String) -> SearchFilter,
+    &'static HashMap<&'static str, u8>,
+);
+
+/// a page of the source, as pointed at by one of its urls
+#[derive(Debug, Clone)]
+pub enum Route {
+    /// e.g. `/one-piece-100` or `/watch/one-piece-100`
+    Anime(AnimeId),
+    /// e.g. `/watch/steinsgate-3?ep=213`
+    Episode(EpisodeId),
+    /// e.g. `/genre/shounen?page=2`
+    Genre { name: String, page: Option<u16> },
+    /// e.g. `/producer/toei-animation`
+    Producer { name: String, page: Option<u16> },
+    /// e.g. `/most-popular`
+    Category { name: String, page: Option<u16> },
+    /// e.g. `/az-list/A`, `sort_option` is one of [`AZ_LIST_SORT_OPTIONS`]
+    AZList {
+        sort_option: String,
+        page: Option<u16>,
+    },
+    /// `/search?keyword=...` when `query` is set, `/filter?...` otherwise
+    Search {
+        query: Option<String>,
+        filters: SearchFilters,
+        page: Option<u16>,
+    },
+    /// e.g. `/character/monkey-d-luffy-1`
+    Character(String),
+    /// e.g. `/people/mayumi-tanaka-12`
+    Person(String),
+}
+
+/// turns source urls into [`Route`]s and back, for a configurable base domain
+///
+/// ### Example Usage
+/// ```rust
+/// use enma::anime::hianime::{Route, Router};
+///
+/// let router = Router::default();
+///
+/// let route = router.parse("https://hianime.to/watch/steinsgate-3?ep=213").unwrap();
+/// if let Route::Episode(episode_id) = &route {
+///     assert_eq!(episode_id.anime().to_string(), "steinsgate-3");
+/// }
+///
+/// assert_eq!(router.build(&route), "https://hianime.to/watch/steinsgate-3?ep=213");
+/// ```
+#[derive(Debug, Clone)]
+pub struct Router {
+    base_url: Url,
+}
+
+impl Default for Router {
+    fn default() -> Self {
+        return Router {
+            base_url: Url::parse(HiAnimeUtils::BaseUrl.value()).unwrap(),
+        };
+    }
+}
+
+impl Router {
+    /// creates a router for a mirror of the source, e.g. `https://hianime.nz`
+    pub fn new(base_url: &str) -> EnmaResult<Self> {
+        let base_url = Url::parse(base_url.trim())
+            .ok()
+            .filter(|url| url.host_str().is_some() && url.path() == "/")
+            .ok_or_else(|| Self::invalid_url_error(format!("invalid base url '{base_url}'")))?;
+
+        return Ok(Router { base_url });
+    }
+
+    /// the base url without a trailing `/`, e.g. `https://hianime.to`
+    pub fn base_url(&self) -> &str {
+        self.base_url.as_str().trim_end_matches('/')
+    }
+
+    /// parses an absolute url of the base domain or a path like `/one-piece-100`
+    pub fn parse(&self, url: &str) -> EnmaResult<Route> {
+        let raw_url = url.trim();
+        let url = match Url::parse(raw_url) {
+            Ok(url) => url,
+            Err(_) => self
+                .base_url
+                .join(&format!("/{}", raw_url.trim_start_matches('/')))
+                .map_err(|_| Self::invalid_url_error(format!("invalid url '{raw_url}'")))?,
+        };
+
+        if !self.is_base_domain(&url) {
+            return Err(Self::invalid_url_error(format!(
+                "'{raw_url}' doesn't belong to {}",
+                self.base_url()
+            )));
+        }
+
+        let query: HashMap<String, String> = url.query_pairs().into_owned().collect();
+        let page = query.get("page").and_then(|p| p.parse::<u16>().ok());
+        let segments = url
+            .path_segments()
+            .map(|s| s.filter(|s| !s.is_empty()).collect::<Vec<_>>())
+            .unwrap_or_default();
+
+        let route = match segments.as_slice() {
+            ["watch", anime_id] => {
+                let anime_id = Self::parse_anime_id(anime_id)?;
+                match query.get("ep") {
+                    Some(ep) => Route::Episode(EpisodeId::new(
+                        anime_id,
+                        ep.parse::<u32>().map_err(|_| {
+                            Self::invalid_url_error(format!("invalid episode in '{raw_url}'"))
+                        })?,
+                    )),
+                    None => Route::Anime(anime_id),
+                }
+            }
+            ["genre", name] => Route::Genre {
+                name: name.to_lowercase(),
+                page,
+            },
+            ["producer", name] => Route::Producer {
+                name: name.to_lowercase(),
+                page,
+            },
+            ["az-list"] => Route::AZList {
+                sort_option: String::from("all"),
+                page,
+            },
+            ["az-list", sort_option] => {
+                let sort_option = sort_option.to_lowercase();
+                if !AZ_LIST_SORT_OPTIONS.contains(sort_option.as_str()) {
+                    return Err(Self::invalid_url_error(format!(
+                        "invalid az list option in '{raw_url}'"
+                    )));
+                }
+                Route::AZList { sort_option, page }
+            }
+            ["search"] => Route::Search {
+                query: query.get("keyword").map(|s| s.trim().to_string()),
+                filters: Self::parse_search_filters(&query),
+                page,
+            },
+            ["filter"] => Route::Search {
+                query: None,
+                filters: Self::parse_search_filters(&query),
+                page,
+            },
+            ["character", id] => Route::Character(id.to_string()),
+            ["people", id] => Route::Person(id.to_string()),
+            [name] if ANIME_CATEGORIES.contains(name) => Route::Category {
+                name: name.to_string(),
+                page,
+            },
+            [anime_id] => Route::Anime(Self::parse_anime_id(anime_id)?),
+            _ => {
+                return Err(Self::invalid_url_error(format!(
+                    "'{raw_url}' doesn't point at a supported page"
+                )))
+            }
+        };
+
+        return Ok(route);
+    }
+
+    /// builds the canonical url of `route`
+    pub fn build(&self, route: &Route) -> String {
+        let base_url = self.base_url();
+        let with_page = |url: String, page: &Option<u16>| match page {
+            Some(page) if *page > 1 => format!("{url}?page={page}"),
+            _ => url,
+        };
+
+        match route {
+            Route::Anime(anime_id) => format!("{base_url}/{anime_id}"),
+            Route::Episode(episode_id) => format!("{base_url}/watch/{episode_id}"),
+            Route::Genre { name, page } => with_page(format!("{base_url}/genre/{name}"), page),
+            Route::Producer { name, page } => {
+                with_page(format!("{base_url}/producer/{name}"), page)
+            }
+            Route::Category { name, page } => with_page(format!("{base_url}/{name}"), page),
+            Route::AZList { sort_option, page } => {
+                let url = match sort_option.as_str() {
+                    "all" => format!("{base_url}/az-list"),
+                    "other" => format!("{base_url}/az-list/other"),
+                    letter => format!("{base_url}/az-list/{}", letter.to_uppercase()),
+                };
+                with_page(url, page)
+            }
+            Route::Search {
+                query,
+                filters,
+                page,
+            } => {
+                let mut params = vec![];
+                if let Some(query) = query {
+                    params.push(format!("keyword={}", urlencoding::encode(query)));
+                }
+                if !filters.is_empty() {
+                    params.push(filters.to_query_params());
+                }
+                if let Some(page) = page.filter(|p| *p > 1) {
+                    params.push(format!("page={page}"));
+                }
+
+                let path = if query.is_some() { "search" } else { "filter" };
+                match params.is_empty() {
+                    true => format!("{base_url}/{path}"),
+                    false => format!("{base_url}/{path}?{}", params.join("&")),
+                }
+            }
+            Route::Character(id) => format!("{base_url}/character/{id}"),
+            Route::Person(id) => format!("{base_url}/people/{id}"),
+        }
+    }
+
+    fn is_base_domain(&self, url: &Url) -> bool {
+        let strip_www = |host: &str| host.trim_start_matches("www.").to_lowercase();
+        return match (url.host_str(), self.base_url.host_str()) {
+            (Some(host), Some(base_host)) => strip_www(host) == strip_www(base_host),
+            _ => false,
+        };
+    }
+
+    fn parse_anime_id(anime_id: &str) -> EnmaResult<AnimeId> {
+        anime_id
+            .parse::<AnimeId>()
+            .map_err(|e| e.into_enma_error(PROVIDER_PARSER))
+    }
+
+    /// maps the source's query params, e.g. `type=2&genres=1,4`, back to
+    /// filters with readable values, e.g. `tv` and `action,comedy`
+    fn parse_search_filters(query: &HashMap<String, String>) -> SearchFilters {
+        let mut filters = vec![];
+
+        let id_maps: [IdMappedFilter; 6] = [
+            ("type", SearchFilter::Type, &SEARCH_PAGE_FILTERS.type_id_map),
+            (
+                "status",
+                SearchFilter::Status,
+                &SEARCH_PAGE_FILTERS.status_id_map,
+            ),
+            (
+                "rated",
+                SearchFilter::Rated,
+                &SEARCH_PAGE_FILTERS.rated_id_map,
+            ),
+            (
+                "score",
+                SearchFilter::Score,
+                &SEARCH_PAGE_FILTERS.score_id_map,
+            ),
+            (
+                "season",
+                SearchFilter::Season,
+                &SEARCH_PAGE_FILTERS.season_id_map,
+            ),
+            (
+                "language",
+                SearchFilter::Language,
+                &SEARCH_PAGE_FILTERS.language_id_map,
+            ),
+        ];
+        for (key, to_filter, id_map) in id_maps {
+            if let Some(name) = query.get(key).and_then(|v| Self::find_key(id_map, v)) {
+                filters.push(to_filter(name));
+            }
+        }
+
+        if let Some(name) = query
+            .get("sort")
+            .and_then(|v| Self::find_key(&SEARCH_PAGE_FILTERS.sort_id_map, v))
+        {
+            filters.push(SearchFilter::Sort(name));
+        }
+
+        if let Some(genres) = query.get("genres") {
+            let names = genres
+                .split(',')
+                .filter_map(|id| Self::find_key(&SEARCH_PAGE_FILTERS.genres_id_map, id.trim()))
+                .collect::<Vec<_>>();
+            if !names.is_empty() {
+                filters.push(SearchFilter::Genres(names.join(",")));
+            }
+        }
+
+        for (prefix, to_filter) in [
+            ("s", SearchFilter::StartDate as fn(String) -> SearchFilter),
+            ("e", SearchFilter::EndDate),
+        ] {
+            let part = |suffix: &str| {
+                query
+                    .get(&format!("{prefix}{suffix}"))
+                    .and_then(|v| v.parse::<u16>().ok())
+            };
+            if let (Some(year), Some(month), Some(day)) = (part("y"), part("m"), part("d")) {
+                filters.push(to_filter(format!("{year}-{month}-{day}")));
+            }
+        }
+
+        return SearchFilters::new(filters);
+    }
+
+    fn find_key<V: Display>(map: &HashMap<&'static str, V>, value: &str) -> Option<String> {
+        map.iter()
+            .find(|(_, v)| v.to_string() == value)
+            .map(|(k, _)| k.to_string())
+    }
+
+    fn invalid_url_error(message: String) -> EnmaError {
+        EnmaError::invalid_data_error(
+            PROVIDER_PARSER,
+            Some(message),
+            Some(StatusCode::BAD_REQUEST),
+        )
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Route, Router};
+    use crate::anime::hianime::SearchFilter;
+
+    // cargo test --lib -- anime::hianime::router::test --show-output
+    #[test]
+    fn test_parse_routes() {
+        let router = Router::default();
+
+        match router.parse("https://hianime.to/watch/steinsgate-3?ep=213") {
+            Ok(Route::Episode(id)) => assert_eq!(id.to_string(), "steinsgate-3?ep=213"),
+            route => panic!("unexpected route {route:?}"),
+        }
+        match router.parse("/one-piece-100") {
+            Ok(Route::Anime(id)) => assert_eq!(id.to_string(), "one-piece-100"),
+            route => panic!("unexpected route {route:?}"),
+        }
+        match router.parse("https://www.hianime.to/genre/shounen?page=3") {
+            Ok(Route::Genre { name, page }) => {
+                assert_eq!((name.as_str(), page), ("shounen", Some(3)))
+            }
+            route => panic!("unexpected route {route:?}"),
+        }
+        match router.parse("https://hianime.to/az-list/A") {
+            Ok(Route::AZList { sort_option, .. }) => assert_eq!(sort_option, "a"),
+            route => panic!("unexpected route {route:?}"),
+        }
+        assert!(matches!(
+            router.parse("/most-popular"),
+            Ok(Route::Category { .. })
+        ));
+        assert!(matches!(
+            router.parse("/people/mayumi-tanaka-12"),
+            Ok(Route::Person(id)) if id == "mayumi-tanaka-12"
+        ));
+
+        assert!(router.parse("https://example.com/one-piece-100").is_err());
+        assert!(router.parse("/watch/one-piece").is_err());
+        assert!(router.parse("/az-list/AB").is_err());
+    }
+
+    #[test]
+    fn test_parse_search_route() {
+        let router = Router::default();
+
+        let url = "https://hianime.to/search?keyword=one%20piece&type=2&genres=1,4&sy=2020&sm=1&sd=5&page=2";
+        let Ok(Route::Search {
+            query,
+            filters,
+            page,
+        }) = router.parse(url)
+        else {
+            panic!("expected a search route");
+        };
+
+        assert_eq!(query.as_deref(), Some("one piece"));
+        assert_eq!(page, Some(2));
+
+        let mut values = filters
+            .iter()
+            .map(|f| (f.to_string(), f.value()))
+            .collect::<Vec<_>>();
+        values.sort();
+        assert_eq!(
+            values,
+            vec![
+                (String::from("genres"), String::from("action,comedy")),
+                (String::from("start_date"), String::from("2020-1-5")),
+                (String::from("type"), String::from("tv")),
+            ]
+        );
+
+        let route = Route::Search {
+            query,
+            filters,
+            page,
+        };
+        assert_eq!(
+            router.build(&route),
+            "https://hianime.to/search?keyword=one%20piece&genres=1,4&sy=2020&sm=1&sd=5&type=2&page=2"
+        );
+        assert!(matches!(
+            router.parse("/filter?language=1"),
+            Ok(Route::Search { query: None, filters, .. })
+                if filters.iter().any(|f| *f == SearchFilter::Language(String::from("sub")))
+        ));
+    }
+
+    #[test]
+    fn test_build_routes() {
+        let router = Router::new("https://hianime.nz/").unwrap();
+
+        for path in [
+            "/one-piece-100",
+            "/watch/steinsgate-3?ep=213",
+            "/genre/shounen?page=2",
+            "/producer/toei-animation",
+            "/most-popular",
+            "/az-list",
+            "/az-list/other",
+            "/az-list/A",
+            "/character/monkey-d-luffy-1",
+            "/people/mayumi-tanaka-12",
+        ] {
+            let route = router.parse(path).unwrap();
+            assert_eq!(router.build(&route), format!("https://hianime.nz{path}"));
+        }
+
+        assert!(router.parse("https://hianime.to/one-piece-100").is_err());
+        assert!(Router::new("not a url").is_err());
+    }
+}
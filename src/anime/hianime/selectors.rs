@@ -0,0 +1,516 @@
+use crate::{EnmaError, EnmaResult};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use std::{path::Path, sync::Arc};
+
+const PROVIDER_PARSER: &'static str = "hianime:selectors";
+
+/// version of the layout of [`SelectorTable`], bumped whenever a selector is
+/// added, renamed or removed, so that a patch file written against another
+/// layout is rejected instead of silently ignored
+pub const SELECTORS_VERSION: u32 = 1;
+
+/// defines [`SelectorTable`] and its compiled counterpart from one list of
+/// `section { field: "default css" }` entries, every field of the table can
+/// then be overridden from a file without touching the parsers
+macro_rules! selector_table {
+    ($(
+        $(#[$section_doc:meta])*
+        $section:ident: $Section:ident {
+            $( $(#[$field_doc:meta])* $field:ident: $css:literal ),* $(,)?
+        }
+    )*) => {
+        /// css selectors the hianime parsers run, defaults to the ones matching
+        /// the site's current markup
+        ///
+        /// every field can be overridden from a TOML or JSON patch that only
+        /// lists the changed selectors, fields left out keep their defaults
+        #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+        #[serde(deny_unknown_fields)]
+        pub struct SelectorTable {
+            /// [`SELECTORS_VERSION`] the table was written against
+            #[serde(default)]
+            pub version: u32,
+            $(
+                $(#[$section_doc])*
+                #[serde(default)]
+                pub $section: $Section,
+            )*
+        }
+
+        impl Default for SelectorTable {
+            fn default() -> Self {
+                return SelectorTable {
+                    version: SELECTORS_VERSION,
+                    $( $section: $Section::default(), )*
+                };
+            }
+        }
+
+        $(
+            #[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+            #[serde(default, deny_unknown_fields)]
+            pub struct $Section {
+                $( $(#[$field_doc])* pub $field: String, )*
+            }
+
+            impl Default for $Section {
+                fn default() -> Self {
+                    return $Section {
+                        $( $field: String::from($css), )*
+                    };
+                }
+            }
+        )*
+
+        /// [`SelectorTable`] with every selector parsed, shared by the
+        /// scraper's parsers
+        pub(crate) mod compiled {
+            #[derive(Debug)]
+            pub(crate) struct Selectors {
+                $( pub $section: $Section, )*
+            }
+
+            $(
+                #[derive(Debug)]
+                pub(crate) struct $Section {
+                    $( pub $field: scraper::Selector, )*
+                }
+            )*
+        }
+
+        impl SelectorTable {
+            /// parses every selector of the table, failing on the first invalid one
+            pub(crate) fn compile(&self) -> EnmaResult<compiled::Selectors> {
+                if self.version != SELECTORS_VERSION {
+                    return Err(EnmaError::invalid_data_error(
+                        PROVIDER_PARSER,
+                        Some(format!(
+                            "selector table version {} isn't supported, expected version {SELECTORS_VERSION}",
+                            self.version
+                        )),
+                        None,
+                    ));
+                }
+
+                return Ok(compiled::Selectors {
+                    $(
+                        $section: compiled::$Section {
+                            $(
+                                $field: compile(
+                                    concat!(stringify!($section), ".", stringify!($field)),
+                                    &self.$section.$field,
+                                )?,
+                            )*
+                        },
+                    )*
+                });
+            }
+
+            /// parses the selectors of one section of the table alone, e.g.
+            /// `"az_list"`, unknown sections are skipped
+            #[cfg(feature = "bench")]
+            pub(crate) fn compile_section(&self, section: &str) -> EnmaResult<()> {
+                match section {
+                    $(
+                        stringify!($section) => {
+                            $(
+                                compile(
+                                    concat!(stringify!($section), ".", stringify!($field)),
+                                    &self.$section.$field,
+                                )?;
+                            )*
+                        }
+                    )*
+                    _ => {}
+                }
+                return Ok(());
+            }
+        }
+    };
+}
+
+/// built-in table, compiled once and shared by every [`super::Scraper`]
+/// that doesn't override its selectors
+pub(crate) static DEFAULT_SELECTORS: Lazy<Arc<compiled::Selectors>> = Lazy::new(|| {
+    return Arc::new(
+        SelectorTable::default()
+            .compile()
+            .expect("built-in selectors are valid"),
+    );
+});
+
+fn compile(key: &'static str, css: &str) -> EnmaResult<scraper::Selector> {
+    return scraper::Selector::parse(css).map_err(|e| {
+        EnmaError::invalid_data_error(
+            PROVIDER_PARSER,
+            Some(format!("invalid selector `{key}` = {css:?}: {e}")),
+            None,
+        )
+    });
+}
+
+impl SelectorTable {
+    /// reads a patch of the table from TOML, e.g.
+    /// ```toml
+    /// version = 1
+    ///
+    /// [home_page]
+    /// spotlight = "#slider .swiper-slide"
+    /// ```
+    pub fn from_toml(patch: &str) -> EnmaResult<Self> {
+        let table = toml::from_str::<SelectorTable>(patch).map_err(|e| {
+            EnmaError::invalid_data_error(PROVIDER_PARSER, Some(e.to_string()), None)
+        })?;
+        table.validate()?;
+
+        return Ok(table);
+    }
+
+    /// reads a patch of the table from JSON, the same shape as [`SelectorTable::from_toml`]
+    pub fn from_json(patch: &str) -> EnmaResult<Self> {
+        let table = serde_json::from_str::<SelectorTable>(patch).map_err(|e| {
+            EnmaError::invalid_data_error(PROVIDER_PARSER, Some(e.to_string()), None)
+        })?;
+        table.validate()?;
+
+        return Ok(table);
+    }
+
+    /// reads a patch of the table from a `.toml` or `.json` file
+    pub fn from_file(path: impl AsRef<Path>) -> EnmaResult<Self> {
+        let path = path.as_ref();
+        let patch = std::fs::read_to_string(path).map_err(|e| {
+            EnmaError::invalid_data_error(
+                PROVIDER_PARSER,
+                Some(format!("couldn't read {}: {e}", path.display())),
+                None,
+            )
+        })?;
+
+        return match path.extension().and_then(|ext| ext.to_str()) {
+            Some("toml") => Self::from_toml(&patch),
+            Some("json") => Self::from_json(&patch),
+            _ => Err(EnmaError::invalid_data_error(
+                PROVIDER_PARSER,
+                Some(format!(
+                    "{} isn't a .toml or .json selector table",
+                    path.display()
+                )),
+                None,
+            )),
+        };
+    }
+
+    /// checks the table's version and that every selector parses
+    pub fn validate(&self) -> EnmaResult<()> {
+        return self.compile().map(|_| ());
+    }
+}
+
+selector_table! {
+    home_page: HomePageSelectors {
+        spotlight: "#slider .swiper-wrapper .swiper-slide",
+        trending: "#trending-home .swiper-wrapper .swiper-slide",
+        latest_episode: "#main-content .block_area_home:nth-of-type(1) .tab-content .film_list-wrap .flw-item",
+        top_upcoming: "#main-content .block_area_home:nth-of-type(3) .tab-content .film_list-wrap .flw-item",
+        genres: "#main-sidebar .block_area.block_area_sidebar.block_area-genres .sb-genre-list li",
+        top10: "#main-sidebar .block_area-realtime [id^=\"top-viewed-\"]",
+        top_airing: "#anime-featured .row div:nth-of-type(1) .anif-block-ul ul li",
+        most_popular: "#anime-featured .row div:nth-of-type(2) .anif-block-ul ul li",
+        most_favorite: "#anime-featured .row div:nth-of-type(3) .anif-block-ul ul li",
+        latest_completed: "#anime-featured .row div:nth-of-type(4) .anif-block-ul ul li",
+    }
+
+    az_list: AZListSelectors {
+        animes: "#main-wrapper .tab-content .film_list-wrap .flw-item",
+    }
+
+    category: CategorySelectors {
+        name: "#main-content .block_area .block_area-header .cat-heading",
+        animes: "#main-content .tab-content .film_list-wrap .flw-item",
+        genres: "#main-sidebar .block_area.block_area_sidebar.block_area-genres .sb-genre-list li",
+        top10: "#main-sidebar .block_area-realtime [id^=\"top-viewed-\"]",
+    }
+
+    genre: GenreSelectors {
+        name: "#main-content .block_area .block_area-header .cat-heading",
+        animes: "#main-content .tab-content .film_list-wrap .flw-item",
+        genres: "#main-sidebar .block_area.block_area_sidebar.block_area-genres .sb-genre-list li",
+        top_airing: "#main-sidebar .block_area_sidebar:nth-child(2) .block_area-content .anif-block-ul ul li",
+    }
+
+    producer: ProducerSelectors {
+        name: "#main-content .block_area .block_area-header .cat-heading",
+        animes: "#main-content .tab-content .film_list-wrap .flw-item",
+        top10: "#main-sidebar .block_area-realtime [id^=\"top-viewed-\"]",
+        top_airing: "#main-sidebar .block_area_sidebar:nth-child(2) .block_area-content .anif-block-ul ul li",
+    }
+
+    search: SearchSelectors {
+        animes: "#main-content .tab-content .film_list-wrap .flw-item",
+        most_popular: "#main-sidebar .block_area.block_area_sidebar.block_area-realtime .anif-block-ul ul li",
+    }
+
+    /// the filter page [`super::Scraper::get_random_anime`] picks from
+    random: RandomSelectors {
+        animes: "#main-content .tab-content .film_list-wrap .flw-item",
+    }
+
+    search_suggestions: SearchSuggestionSelectors {
+        suggestions: ".nav-item:has(.film-poster)",
+        /// also carries the japanese name
+        name: ".srp-detail .film-name",
+        poster: ".film-poster .film-poster-img",
+        other_info: ".film-infor",
+    }
+
+    info: InfoSelectors {
+        id: ".anisc-detail .film-buttons a.btn-play",
+        /// also carries the japanese name
+        name: ".anisc-detail .film-name.dynamic-name",
+        description: ".anisc-detail .film-description .text",
+        poster: ".film-poster .film-poster-img",
+        /// json with the anime's mal and anilist ids
+        sync_data: "body #syncData",
+
+        seasons: "#main-content .os-list a.os-item",
+        season_title: ".title",
+        season_poster: ".season-poster",
+
+        rating: ".anis-content .film-stats .tick .tick-pg",
+        quality: ".anis-content .film-stats .tick .tick-quality",
+        sub_episodes: ".anis-content .film-stats .tick .tick-sub",
+        dub_episodes: ".anis-content .film-stats .tick .tick-dub",
+        other_stats: ".anis-content .film-stats .tick",
+
+        other_info: ".anisc-info-wrap .anisc-info .item:not(.w-hide)",
+        other_info_key: ".item-head",
+        other_info_value: "*:not(.item-head)",
+
+        promotional_videos: ".block_area.block_area-promotions .block_area-promotions-list .screen-items .item",
+        promotional_video_thumbnail: "img",
+
+        characters: ".block_area.block_area-actors .block-actors-content .bac-list-wrap .bac-item",
+        character_id: ".per-info.ltr .pi-avatar",
+        character_poster: ".per-info.ltr .pi-avatar img",
+        character_name: ".per-info.ltr .pi-detail a",
+        character_cast: ".per-info.ltr .pi-detail .pi-cast",
+        voice_actor_id: ".per-info.rtl .pi-avatar",
+        voice_actor_poster: ".per-info.rtl .pi-avatar img",
+        voice_actor_name: ".per-info.rtl .pi-detail a",
+        voice_actor_cast: ".per-info.rtl .pi-detail .pi-cast",
+
+        related_animes: "#main-sidebar .block_area.block_area_sidebar.block_area-realtime:nth-of-type(1) .anif-block-ul ul li",
+        most_popular: "#main-sidebar .block_area.block_area_sidebar.block_area-realtime:nth-of-type(2) .anif-block-ul ul li",
+        recommended: "#main-content .block_area.block_area_category .tab-content .flw-item",
+    }
+
+    qtip: QtipSelectors {
+        id: ".pre-qtip-button a.btn-play",
+        name: ".pre-qtip-title",
+        mal_score: ".pre-qtip-detail .pqd-li",
+        quality: ".tick .tick-quality",
+        anime_type: ".badge.badge-quality",
+        description: ".pre-qtip-description",
+        sub_episodes: ".tick .tick-sub",
+        dub_episodes: ".tick .tick-dub",
+        other_info: ".pre-qtip-content .pre-qtip-line",
+        other_info_key: ".stick",
+        other_info_value: ".stick-text",
+    }
+
+    episodes: EpisodesSelectors {
+        episodes: ".detail-infor-content .ss-list a",
+    }
+
+    episode_servers: EpisodeServersSelectors {
+        episode_number: ".server-notice strong b",
+        sub: ".ps_-block.ps_-block-sub.servers-sub .ps__-list .server-item",
+        dub: ".ps_-block.ps_-block-sub.servers-dub .ps__-list .server-item",
+        raw: ".ps_-block.ps_-block-sub.servers-raw .ps__-list .server-item",
+        server_name: "a",
+    }
+
+    schedule: ScheduleSelectors {
+        animes: "li",
+        id: "li a.tsl-link",
+        /// also carries the japanese name
+        name: "li a .film-name.dynamic-name",
+        episode_number: "li a .fd-play button.btn-play",
+        time: "li a.tsl-link .time",
+    }
+
+    anime_characters: AnimeCharactersSelectors {
+        characters: ".bac-list-wrap .bac-item",
+        character: ".per-info.ltr",
+        voice_actor: ".per-info.rtl",
+        /// voice actors beyond the first one, only rendered as avatars
+        other_voice_actors: ".per-info.per-info-xx .pix-list .pi-avatar",
+        avatar: ".pi-avatar",
+        poster: ".pi-avatar img",
+        name: ".pi-detail .pi-name a",
+        cast: ".pi-detail .pi-cast",
+        other_voice_actor_poster: "img",
+    }
+
+    character: CharacterSelectors {
+        name: ".actor-page-wrap .apw-detail .name",
+        jname: ".actor-page-wrap .apw-detail .sub-name",
+        poster: ".actor-page-wrap .avatar-circle img",
+        bio: ".actor-page-wrap .apw-detail #bio .bio",
+
+        voice_actors: ".actor-page-wrap .apw-detail .sub-box-list .per-info",
+        voice_actor_id: ".pi-avatar",
+        voice_actor_poster: ".pi-avatar img",
+        voice_actor_name: ".pi-detail .pi-name a",
+        voice_actor_language: ".pi-detail .pi-cast",
+
+        animeography: "#main-content .block_area-actors .anif-block-ul ul li",
+        animeography_id: ".film-detail .film-name .dynamic-name",
+        animeography_poster: ".film-poster .film-poster-img",
+        animeography_info: ".fd-infor .fdi-item",
+    }
+
+    person: PersonSelectors {
+        name: ".actor-page-wrap .apw-detail .name",
+        jname: ".actor-page-wrap .apw-detail .sub-name",
+        poster: ".actor-page-wrap .avatar-circle img",
+        bio: ".actor-page-wrap .apw-detail #bio .bio",
+
+        roles: "#main-content .block_area-actors .block-actors-content .bac-list-wrap .bac-item",
+        anime_id: ".per-info.ltr .pi-avatar",
+        anime_poster: ".per-info.ltr .pi-avatar img",
+        anime_name: ".per-info.ltr .pi-detail .pi-name a",
+        anime_info: ".per-info.ltr .pi-detail .pi-cast",
+        character_id: ".per-info.rtl .pi-avatar",
+        character_poster: ".per-info.rtl .pi-avatar img",
+        character_name: ".per-info.rtl .pi-detail .pi-name a",
+        character_role: ".per-info.rtl .pi-detail .pi-cast",
+    }
+
+    /// fields of the anime cards of the listing pages
+    anime_card: AnimeCardSelectors {
+        /// also carries the id and the japanese name
+        name: ".film-detail .film-name .dynamic-name",
+        poster: ".film-poster .film-poster-img",
+        duration: ".film-detail .fd-infor .fdi-item.fdi-duration",
+        anime_type: ".film-detail .fd-infor .fdi-item:nth-of-type(1)",
+        rating: ".film-poster .tick-rate",
+        sub_episodes: ".film-poster .tick-sub",
+        dub_episodes: ".film-poster .tick-dub",
+    }
+
+    /// fields of the compact anime cards of the sidebars and featured lists
+    sidebar_card: SidebarCardSelectors {
+        /// also carries the id and the japanese name
+        name: ".film-detail .film-name .dynamic-name",
+        poster: ".film-poster .film-poster-img",
+        anime_type: ".fd-infor .tick .fdi-item",
+        sub_episodes: ".fd-infor .tick .tick-sub",
+        dub_episodes: ".fd-infor .tick .tick-dub",
+    }
+
+    top10_card: Top10CardSelectors {
+        today: "#top-viewed-day ul li",
+        week: "#top-viewed-week ul li",
+        month: "#top-viewed-month ul li",
+        /// also carries the id and the japanese name
+        name: ".film-detail .film-name .dynamic-name",
+        rank: ".film-number span",
+        poster: ".film-poster .film-poster-img",
+        sub_episodes: ".fd-infor .tick .tick-sub",
+        dub_episodes: ".fd-infor .tick .tick-dub",
+    }
+
+    spotlight_card: SpotlightCardSelectors {
+        id: ".deslide-item-content .desi-buttons a",
+        /// also carries the japanese name
+        name: ".deslide-item-content .desi-head-title.dynamic-name",
+        poster: ".deslide-cover .deslide-cover-img .film-poster-img",
+        rank: ".deslide-item-content .desi-sub-text",
+        description: ".deslide-item-content .desi-description",
+        sub_episodes: ".deslide-item-content .sc-detail .scd-item .tick-item.tick-sub",
+        dub_episodes: ".deslide-item-content .sc-detail .scd-item .tick-item.tick-dub",
+        other_info: ".deslide-item-content .sc-detail .scd-item",
+    }
+
+    trending_card: TrendingCardSelectors {
+        id: ".item .film-poster",
+        /// also carries the japanese name
+        name: ".item .number .film-title.dynamic-name",
+        poster: ".item .film-poster .film-poster-img",
+        rank: ".item .number span",
+    }
+
+    pagination: PaginationSelectors {
+        items: ".pagination > li",
+        active: ".pagination li.active",
+        last: ".pagination > .page-item a[title=\"Last\"]",
+        next: ".pagination > .page-item a[title=\"Next\"]",
+        active_link: ".pagination > .page-item.active a",
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SelectorTable, SELECTORS_VERSION};
+
+    // cargo test --lib -- anime::hianime::selectors::test --show-output
+    #[test]
+    fn test_default_selectors_compile() {
+        let table = SelectorTable::default();
+        assert_eq!(table.version, SELECTORS_VERSION);
+        table.validate().unwrap();
+    }
+
+    #[test]
+    fn test_selector_patches() {
+        let table = SelectorTable::from_toml(
+            r##"
+            version = 1
+
+            [home_page]
+            spotlight = "#slider .swiper-slide"
+            "##,
+        )
+        .unwrap();
+        assert_eq!(table.home_page.spotlight, "#slider .swiper-slide");
+        // left out selectors keep their defaults
+        assert_eq!(
+            table.home_page.trending,
+            SelectorTable::default().home_page.trending
+        );
+        assert_eq!(table.info, SelectorTable::default().info);
+
+        let json =
+            SelectorTable::from_json(r#"{"version":1,"az_list":{"animes":".flw-item"}}"#).unwrap();
+        assert_eq!(json.az_list.animes, ".flw-item");
+
+        // round trips through its own serialization
+        let full = toml::to_string(&SelectorTable::default()).unwrap();
+        assert_eq!(
+            SelectorTable::from_toml(&full).unwrap(),
+            SelectorTable::default()
+        );
+    }
+
+    #[test]
+    fn test_invalid_selector_patches() {
+        for (patch, message) in [
+            (
+                "version = 1\n[home_page]\nspotlight = \"#slider >> .item\"",
+                "home_page.spotlight",
+            ),
+            ("version = 1\n[home_page]\nspotlite = \".item\"", "spotlite"),
+            ("version = 1\n[home]\nspotlight = \".item\"", "home"),
+            ("[home_page]\nspotlight = \".item\"", "version 0"),
+            ("version = 2", "version 2"),
+        ] {
+            let err = SelectorTable::from_toml(patch).unwrap_err();
+            let details = err.details();
+            println!("{}", details.message);
+            assert!(details.message.contains(message), "{patch}");
+        }
+    }
+}
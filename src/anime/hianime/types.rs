@@ -1,11 +1,14 @@
 use once_cell::sync::Lazy;
 use std::collections::{HashMap, HashSet};
 
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Serialize, Debug, Default)]
+use super::{AnimeId, EpisodeId};
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Anime {
-    pub id: Option<String>,
+    pub id: Option<AnimeId>,
     pub name: Option<String>,
     pub poster: Option<String>,
     /// japanese name
@@ -17,22 +20,25 @@ pub struct Anime {
     pub episodes: Episodes,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Episodes {
     pub sub: Option<u16>,
     pub dub: Option<u16>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Top10AnimesWithPeriod {
     pub today: Vec<Top10Anime>,
     pub week: Vec<Top10Anime>,
     pub month: Vec<Top10Anime>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Top10Anime {
-    pub id: Option<String>,
+    pub id: Option<AnimeId>,
     pub name: Option<String>,
     pub poster: Option<String>,
     pub jname: Option<String>,
@@ -47,8 +53,8 @@ pub enum Top10AnimePeriod {
 }
 
 impl Top10AnimePeriod {
-    pub fn from(period: &String) -> Self {
-        match period.as_str() {
+    pub fn from(period: &str) -> Self {
+        match period {
             "day" => Self::Day,
             "week" => Self::Week,
             "month" => Self::Month,
@@ -57,9 +63,10 @@ impl Top10AnimePeriod {
     }
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct MostPopularAnime {
-    pub id: Option<String>,
+    pub id: Option<AnimeId>,
     pub name: Option<String>,
     pub poster: Option<String>,
     pub jname: Option<String>,
@@ -68,9 +75,10 @@ pub struct MostPopularAnime {
     pub episodes: Episodes,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct SpotlightAnime {
-    pub id: Option<String>,
+    pub id: Option<AnimeId>,
     pub name: Option<String>,
     pub poster: Option<String>,
     pub jname: Option<String>,
@@ -82,9 +90,10 @@ pub struct SpotlightAnime {
     pub episodes: Episodes,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct TrendingAnime {
-    pub id: Option<String>,
+    pub id: Option<AnimeId>,
     pub name: Option<String>,
     pub poster: Option<String>,
     pub jname: Option<String>,
@@ -100,25 +109,64 @@ pub type TopAiringAnime = MostPopularAnime;
 pub type MostFavoriteAnime = MostPopularAnime;
 pub type LatestCompletedAnime = MostPopularAnime;
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct Season {
-    pub id: Option<String>,
+    pub id: Option<AnimeId>,
     pub name: Option<String>,
     pub poster: Option<String>,
     pub title: Option<String>,
     pub is_current: bool,
 }
 
-#[derive(Debug, Serialize)]
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FranchiseNode {
+    pub id: AnimeId,
+    pub name: Option<String>,
+    /// japanese name
+    pub jname: Option<String>,
+    pub poster: Option<String>,
+    #[serde(rename = "type")]
+    pub anime_type: Option<String>,
+    pub aired: Option<String>,
+    pub premiered: Option<String>,
+    /// start of airing, in `YYYY-MM-DD` format
+    pub air_date: Option<String>,
+    /// number of hops from the starting anime
+    pub depth: u8,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(rename_all = "snake_case")]
+pub enum FranchiseRelation {
+    Season,
+    Prequel,
+    Sequel,
+    Related,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct FranchiseEdge {
+    pub from: AnimeId,
+    pub to: AnimeId,
+    pub relation: FranchiseRelation,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 #[serde(untagged)] // Serialize without including a tag in JSON
 pub enum OtherInfoValue {
     Single(String),
     Multiple(Vec<String>),
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AnimeDetailedInfo {
-    pub id: Option<String>,
+    pub id: Option<AnimeId>,
     pub name: Option<String>,
     pub jname: Option<String>,
     pub poster: Option<String>,
@@ -133,7 +181,8 @@ pub struct AnimeDetailedInfo {
     pub characters_voice_actors: Vec<AnimeCharactersVoiceActors>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AnimeDetailedStats {
     pub quality: Option<String>,
     pub duration: Option<String>,
@@ -143,20 +192,23 @@ pub struct AnimeDetailedStats {
     pub episodes: Episodes,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AnimePromotionalVideo {
     pub title: Option<String>,
     pub source: Option<String>,
     pub thumbnail: Option<String>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AnimeCharactersVoiceActors {
     pub character: AnimeCharacter,
     pub voice_actor: AnimeCharacter,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AnimeCharacter {
     pub id: Option<String>,
     pub name: Option<String>,
@@ -164,24 +216,147 @@ pub struct AnimeCharacter {
     pub cast: Option<String>,
 }
 
-#[derive(Serialize, Debug, Default)]
-pub struct AnimeSearchSuggestion {
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AnimeCharacterWithVoiceActors {
+    /// `cast` holds the character's role, e.g. `Main` or `Supporting`
+    pub character: AnimeCharacter,
+    /// `cast` holds the voice actor's language, when shown
+    pub voice_actors: Vec<AnimeCharacter>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CharacterDetailedInfo {
     pub id: Option<String>,
     pub name: Option<String>,
+    /// japanese name
+    pub jname: Option<String>,
+    pub poster: Option<String>,
+    pub description: Option<String>,
+    /// `key: value` lines from the character's bio, like age or height
+    pub about: HashMap<String, String>,
+    pub animeography: Vec<CharacterAnimeography>,
+    pub voice_actors: Vec<CharacterVoiceActor>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CharacterAnimeography {
+    pub id: Option<AnimeId>,
+    pub name: Option<String>,
+    pub poster: Option<String>,
+    /// japanese name
+    pub jname: Option<String>,
+    /// character's role in the anime, e.g. `Main` or `Supporting`
+    pub role: Option<String>,
+    #[serde(rename = "type")]
+    pub anime_type: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CharacterVoiceActor {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub poster: Option<String>,
+    pub language: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct PersonDetailedInfo {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    /// japanese name
+    pub jname: Option<String>,
+    pub poster: Option<String>,
+    pub description: Option<String>,
+    pub birth_date: Option<String>,
+    pub birth_place: Option<String>,
+    pub language: Option<String>,
+    /// `key: value` lines from the person's bio
+    pub about: HashMap<String, String>,
+    pub voice_acting_roles: Vec<VoiceActingRole>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VoiceActingRole {
+    pub anime: VoiceActingRoleAnime,
+    pub character: VoiceActingRoleCharacter,
+    /// character's role in the anime, e.g. `Main` or `Supporting`
+    pub role: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VoiceActingRoleAnime {
+    pub id: Option<AnimeId>,
+    pub name: Option<String>,
+    pub poster: Option<String>,
+    /// extra info shown next to the anime, like its type and year
+    pub info: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct VoiceActingRoleCharacter {
+    pub id: Option<String>,
+    pub name: Option<String>,
+    pub poster: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct IdMatch {
+    pub anime_id: AnimeId,
+    pub name: Option<String>,
+    /// japanese name
+    pub jname: Option<String>,
+    /// `1.0` when the anime's sync ids matched, a title similarity based guess otherwise
+    pub confidence: f32,
+    /// whether the anime's sync ids were checked and matched
+    pub verified: bool,
+}
+
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct TitleMatch {
+    pub anime_id: AnimeId,
+    pub name: Option<String>,
+    /// japanese name
+    pub jname: Option<String>,
+    #[serde(rename = "type")]
+    pub anime_type: Option<String>,
+    pub episodes: Episodes,
+    /// how well the name or japanese name matched the query, in `0.0..=1.0`
+    pub title_score: f32,
+    /// `title_score` adjusted by the given hints, in `0.0..=1.0`
+    pub score: f32,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct AnimeSearchSuggestion {
+    pub id: Option<AnimeId>,
+    pub name: Option<String>,
     pub poster: Option<String>,
     pub jname: Option<String>,
     pub other_info: Vec<String>,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct AnimeEpisode {
     pub title: Option<String>,
     pub number: Option<u16>,
-    pub episode_id: Option<String>,
+    pub episode_id: Option<EpisodeId>,
     pub is_filler: bool,
 }
 
-#[derive(Serialize, Debug)]
+#[derive(Serialize, Deserialize, Debug)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct EpisodeInfo {
     pub server_name: Option<String>,
     pub server_id: Option<u16>,
@@ -212,16 +387,16 @@ pub static ANIME_CATEGORIES: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     .collect();
 });
 
-#[allow(dead_code)] // TODO -> remove this
 pub static ANIME_SERVERS: Lazy<HashSet<&'static str>> = Lazy::new(|| {
     return ["hd-1", "hd-2", "megacloud", "streamsb", "streamtape"]
         .into_iter()
         .collect();
 });
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct QtipAnime {
-    pub id: Option<String>,
+    pub id: Option<AnimeId>,
     pub name: Option<String>,
     pub mal_score: Option<f64>,
     pub quality: Option<String>,
@@ -237,9 +412,10 @@ pub struct QtipAnime {
     pub genres: Vec<String>,
 }
 
-#[derive(Serialize, Debug, Default)]
+#[derive(Serialize, Deserialize, Debug, Default)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
 pub struct ScheduledAnime {
-    pub id: Option<String>,
+    pub id: Option<AnimeId>,
     pub name: Option<String>,
     pub jname: Option<String>,
     pub time: Option<String>,
@@ -249,8 +425,7 @@ pub struct ScheduledAnime {
     pub seconds_until_airing: i32,
 }
 
-// #[derive(Debug, Serialize, )]
-#[allow(dead_code)] // TODO -> remove this
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Server {
     VidStreaming,
     MegaCloud,
@@ -266,8 +441,27 @@ pub enum Server {
     FileMoon,
 }
 
-#[allow(dead_code)] // TODO -> remove this
 impl Server {
+    pub const ALL: [Server; 12] = [
+        Server::VidStreaming,
+        Server::MegaCloud,
+        Server::StreamSB,
+        Server::StreamTape,
+        Server::VidCloud,
+        Server::AsianLoad,
+        Server::GogoCDN,
+        Server::MixDrop,
+        Server::UpCloud,
+        Server::VizCloud,
+        Server::MyCloud,
+        Server::FileMoon,
+    ];
+
+    /// the server of a `value()`, e.g. `Server::VidStreaming` for `hd-1`
+    pub fn from_value(value: &str) -> Option<Self> {
+        return Self::ALL.into_iter().find(|server| server.value() == value);
+    }
+
     pub fn value(&self) -> &'static str {
         match self {
             Server::VidStreaming => "hd-1",
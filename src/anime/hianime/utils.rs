@@ -1,10 +1,20 @@
-use super::types::{
-    Anime, Episodes, MostPopularAnime, SpotlightAnime, Top10Anime, Top10AnimePeriod,
-    Top10AnimesWithPeriod, TrendingAnime,
+use super::{
+    health::{self, Health},
+    selectors::compiled,
+    types::{
+        Anime, Episodes, MostPopularAnime, SpotlightAnime, Top10Anime, Top10AnimePeriod,
+        Top10AnimesWithPeriod, TrendingAnime,
+    },
+    AnimeId,
 };
+use crate::{error::EnmaResult, utils::EnmaClient};
+use reqwest::{header::HeaderMap, Client};
 use scraper::{Html, Selector};
+use serde::{de::DeserializeOwned, Serialize};
 use serde_json::Value;
+use std::{collections::HashMap, future::Future};
 
+#[allow(clippy::enum_variant_names)]
 pub enum HiAnimeUtils {
     BaseUrl,
     HomeUrl,
@@ -19,6 +29,12 @@ pub enum HiAnimeUtils {
     EpisodeListUrl,
     EpisodeServersUrl,
     ScheduleUrl,
+    CharacterUrl,
+    PeopleUrl,
+    CharacterListUrl,
+    RandomUrl,
+    FilterUrl,
+    AnilistGraphqlUrl,
 }
 
 impl HiAnimeUtils {
@@ -36,24 +52,58 @@ impl HiAnimeUtils {
             HiAnimeUtils::EpisodeListUrl => "https://hianime.to/ajax/v2/episode/list",
             HiAnimeUtils::EpisodeServersUrl => "https://hianime.to/ajax/v2/episode/servers",
             HiAnimeUtils::ScheduleUrl => "https://hianime.to/ajax/schedule/list",
+            HiAnimeUtils::CharacterUrl => "https://hianime.to/character",
+            HiAnimeUtils::PeopleUrl => "https://hianime.to/people",
+            HiAnimeUtils::CharacterListUrl => "https://hianime.to/ajax/character/list",
+            HiAnimeUtils::RandomUrl => "https://hianime.to/random",
+            HiAnimeUtils::FilterUrl => "https://hianime.to/filter",
+            HiAnimeUtils::AnilistGraphqlUrl => "https://graphql.anilist.co",
         }
     }
 
-    pub fn extract_trending_anime(document: &Html, selector: &Selector) -> Vec<TrendingAnime> {
+    /// records the number of parsed items on the current parser's span
+    pub fn record_items(count: usize) {
+        tracing::Span::current().record("items", count);
+    }
+
+    /// warns when the selector of `field`, which normally matches elements on
+    /// the page, matched none, likely because the source's markup changed
+    pub fn warn_if_empty(field: &'static str, count: usize) {
+        if count == 0 {
+            tracing::warn!(field, "selector matched no elements");
+        }
+    }
+
+    /// warns about every field of `data` that [`Health`] expects filled but
+    /// came back empty
+    pub fn warn_unhealthy<T: Health>(data: &T) {
+        for (field, count) in data.selector_counts() {
+            HiAnimeUtils::warn_if_empty(field, count);
+        }
+        for field in health::empty_fields(data) {
+            tracing::warn!(field, "required field came back empty");
+        }
+    }
+
+    pub fn extract_trending_anime(
+        document: &Html,
+        selector: &Selector,
+        card: &compiled::TrendingCardSelectors,
+    ) -> Vec<TrendingAnime> {
         let mut anime: Vec<TrendingAnime> = Vec::with_capacity(10);
 
-        let id_selector = &Selector::parse(".item .film-poster").unwrap();
-        let name_selector = &Selector::parse(".item .number .film-title.dynamic-name").unwrap();
+        let id_selector = &card.id;
+        let name_selector = &card.name;
         let jname_selector = name_selector;
-        let poster_selector = &Selector::parse(".item .film-poster .film-poster-img").unwrap();
-        let rank_selector = &Selector::parse(".item .number span").unwrap();
+        let poster_selector = &card.poster;
+        let rank_selector = &card.rank;
 
         for el in document.select(selector) {
             let id = el
                 .select(id_selector)
                 .next()
                 .and_then(|el| el.value().attr("href"))
-                .map(|s| s[1..].trim().to_string());
+                .and_then(AnimeId::from_href);
 
             let name = el
                 .select(name_selector)
@@ -71,8 +121,7 @@ impl HiAnimeUtils {
                 .select(rank_selector)
                 .next()
                 .and_then(|el| el.text().next())
-                .map(|s| s.trim().parse::<u32>().ok())
-                .flatten();
+                .and_then(|s| s.trim().parse::<u32>().ok());
 
             let poster = el
                 .select(poster_selector)
@@ -92,31 +141,29 @@ impl HiAnimeUtils {
         return anime;
     }
 
-    pub fn extract_animes(document: &Html, selector: &Selector) -> Vec<Anime> {
+    pub fn extract_animes(
+        document: &Html,
+        selector: &Selector,
+        card: &compiled::AnimeCardSelectors,
+    ) -> Vec<Anime> {
         let mut anime = vec![];
 
-        let id_selector = &Selector::parse(".film-detail .film-name .dynamic-name").unwrap();
+        let id_selector = &card.name;
         let name_selector = id_selector;
         let jname_selector = id_selector;
-        let poster_selector = &Selector::parse(".film-poster .film-poster-img").unwrap();
-        let duration_selector =
-            &Selector::parse(".film-detail .fd-infor .fdi-item.fdi-duration").unwrap();
-        let anime_type_selector =
-            &Selector::parse(".film-detail .fd-infor .fdi-item:nth-of-type(1)").unwrap();
-        let rating_selector = &Selector::parse(".film-poster .tick-rate").unwrap();
-        let sub_episodes_selector = &Selector::parse(".film-poster .tick-sub").unwrap();
-        let dub_episodes_selector = &Selector::parse(".film-poster .tick-dub").unwrap();
+        let poster_selector = &card.poster;
+        let duration_selector = &card.duration;
+        let anime_type_selector = &card.anime_type;
+        let rating_selector = &card.rating;
+        let sub_episodes_selector = &card.sub_episodes;
+        let dub_episodes_selector = &card.dub_episodes;
 
         for el in document.select(selector) {
             let id = el
                 .select(id_selector)
                 .next()
                 .and_then(|el| el.attr("href"))
-                .map(|s| {
-                    s[1..]
-                        .split_once("?ref=search")
-                        .map_or_else(|| s[1..].to_string(), |(part, _)| part.to_string())
-                });
+                .and_then(AnimeId::from_href);
 
             let name = el
                 .select(name_selector)
@@ -158,16 +205,14 @@ impl HiAnimeUtils {
                 .select(dub_episodes_selector)
                 .next()
                 .and_then(|el| el.text().next())
-                .map(|s| s.split_whitespace().last())
-                .flatten()
+                .and_then(|s| s.split_whitespace().last())
                 .and_then(|s| s.parse::<u16>().ok());
 
             let sub = el
                 .select(sub_episodes_selector)
                 .next()
                 .and_then(|el| el.text().next())
-                .map(|s| s.split_whitespace().last())
-                .flatten()
+                .and_then(|s| s.split_whitespace().last())
                 .and_then(|s| s.parse::<u16>().ok());
 
             anime.push(Anime {
@@ -188,23 +233,24 @@ impl HiAnimeUtils {
     pub fn extract_most_popular_anime(
         document: &Html,
         selector: &Selector,
+        card: &compiled::SidebarCardSelectors,
     ) -> Vec<MostPopularAnime> {
         let mut anime = vec![];
 
-        let id_selector = &Selector::parse(".film-detail .film-name .dynamic-name").unwrap();
+        let id_selector = &card.name;
         let name_selector = id_selector;
         let jname_selector = id_selector;
-        let poster_selector = &Selector::parse(".film-poster .film-poster-img").unwrap();
-        let anime_type_selector = &Selector::parse(".fd-infor .tick .fdi-item").unwrap();
-        let sub_episodes_selector = &Selector::parse(".fd-infor .tick .tick-sub").unwrap();
-        let dub_episodes_selector = &Selector::parse(".fd-infor .tick .tick-dub").unwrap();
+        let poster_selector = &card.poster;
+        let anime_type_selector = &card.anime_type;
+        let sub_episodes_selector = &card.sub_episodes;
+        let dub_episodes_selector = &card.dub_episodes;
 
         for el in document.select(selector) {
             let id = el
                 .select(id_selector)
                 .next()
                 .and_then(|el| el.value().attr("href"))
-                .map(|s| s[1..].trim().to_string());
+                .and_then(AnimeId::from_href);
 
             let name = el
                 .select(name_selector)
@@ -234,16 +280,14 @@ impl HiAnimeUtils {
                 .select(dub_episodes_selector)
                 .next()
                 .and_then(|el| el.text().next())
-                .map(|s| s.split_whitespace().last())
-                .flatten()
+                .and_then(|s| s.split_whitespace().last())
                 .and_then(|s| s.parse::<u16>().ok());
 
             let sub = el
                 .select(sub_episodes_selector)
                 .next()
                 .and_then(|el| el.text().next())
-                .map(|s| s.split_whitespace().last())
-                .flatten()
+                .and_then(|s| s.split_whitespace().last())
                 .and_then(|s| s.parse::<u16>().ok());
 
             anime.push(MostPopularAnime {
@@ -259,24 +303,27 @@ impl HiAnimeUtils {
         return anime;
     }
 
-    fn extract_top10_anime(document: &Html, period: &String) -> Vec<Top10Anime> {
-        let selector = &Selector::parse(format!("#top-viewed-{} ul li", period).as_str()).unwrap();
+    fn extract_top10_anime(
+        document: &Html,
+        selector: &Selector,
+        card: &compiled::Top10CardSelectors,
+    ) -> Vec<Top10Anime> {
         let mut anime = Vec::with_capacity(10);
 
-        let id_selector = &Selector::parse(".film-detail .film-name .dynamic-name").unwrap();
+        let id_selector = &card.name;
         let name_selector = id_selector;
         let jname_selector = id_selector;
-        let rank_selector = &Selector::parse(".film-number span").unwrap();
-        let poster_selector = &Selector::parse(".film-poster .film-poster-img").unwrap();
-        let sub_episodes_selector = &Selector::parse(".fd-infor .tick .tick-sub").unwrap();
-        let dub_episodes_selector = &Selector::parse(".fd-infor .tick .tick-dub").unwrap();
+        let rank_selector = &card.rank;
+        let poster_selector = &card.poster;
+        let sub_episodes_selector = &card.sub_episodes;
+        let dub_episodes_selector = &card.dub_episodes;
 
         for el in document.select(selector) {
             let id = el
                 .select(id_selector)
                 .next()
                 .and_then(|el| el.value().attr("href"))
-                .map(|s| s[1..].trim().to_string());
+                .and_then(AnimeId::from_href);
 
             let name = el
                 .select(name_selector)
@@ -294,8 +341,7 @@ impl HiAnimeUtils {
                 .select(rank_selector)
                 .next()
                 .and_then(|el| el.text().next())
-                .map(|s| s.trim().parse::<u32>().ok())
-                .flatten();
+                .and_then(|s| s.trim().parse::<u32>().ok());
 
             let poster = el
                 .select(poster_selector)
@@ -328,25 +374,28 @@ impl HiAnimeUtils {
         return anime;
     }
 
-    pub fn extract_top10_animes(document: &Html, selector: &Selector) -> Top10AnimesWithPeriod {
+    pub fn extract_top10_animes(
+        document: &Html,
+        selector: &Selector,
+        card: &compiled::Top10CardSelectors,
+    ) -> Top10AnimesWithPeriod {
         let mut top10_animes = Top10AnimesWithPeriod::default();
 
         for el in document.select(selector) {
             let period = el
                 .attr("id")
-                .map(|s| s.split("-").last().map(|s| s.to_string()))
-                .flatten();
+                .and_then(|s| s.split("-").last().map(|s| s.to_string()));
 
             if let Some(time_period) = period {
                 match Top10AnimePeriod::from(&time_period) {
                     Top10AnimePeriod::Day => {
-                        top10_animes.today = Self::extract_top10_anime(&document, &time_period)
+                        top10_animes.today = Self::extract_top10_anime(document, &card.today, card)
                     }
                     Top10AnimePeriod::Week => {
-                        top10_animes.week = Self::extract_top10_anime(&document, &time_period)
+                        top10_animes.week = Self::extract_top10_anime(document, &card.week, card)
                     }
                     Top10AnimePeriod::Month => {
-                        top10_animes.month = Self::extract_top10_anime(&document, &time_period)
+                        top10_animes.month = Self::extract_top10_anime(document, &card.month, card)
                     }
                 }
             }
@@ -355,33 +404,29 @@ impl HiAnimeUtils {
         return top10_animes;
     }
 
-    pub fn extract_spotlight_animes(document: &Html, selector: &Selector) -> Vec<SpotlightAnime> {
+    pub fn extract_spotlight_animes(
+        document: &Html,
+        selector: &Selector,
+        card: &compiled::SpotlightCardSelectors,
+    ) -> Vec<SpotlightAnime> {
         let mut anime = Vec::with_capacity(10);
 
-        let id_selector = &Selector::parse(".deslide-item-content .desi-buttons a").unwrap();
-        let name_selector =
-            &Selector::parse(".deslide-item-content .desi-head-title.dynamic-name").unwrap();
+        let id_selector = &card.id;
+        let name_selector = &card.name;
         let jname_selector = name_selector;
-        let poster_selector =
-            &Selector::parse(".deslide-cover .deslide-cover-img .film-poster-img").unwrap();
-        let rank_selector = &Selector::parse(".deslide-item-content .desi-sub-text").unwrap();
-        let description_selector =
-            &Selector::parse(".deslide-item-content .desi-description").unwrap();
-        let sub_episodes_selector =
-            &Selector::parse(".deslide-item-content .sc-detail .scd-item .tick-item.tick-sub")
-                .unwrap();
-        let dub_episodes_selector =
-            &Selector::parse(".deslide-item-content .sc-detail .scd-item .tick-item.tick-dub")
-                .unwrap();
-        let other_info_selector =
-            &Selector::parse(".deslide-item-content .sc-detail .scd-item").unwrap();
+        let poster_selector = &card.poster;
+        let rank_selector = &card.rank;
+        let description_selector = &card.description;
+        let sub_episodes_selector = &card.sub_episodes;
+        let dub_episodes_selector = &card.dub_episodes;
+        let other_info_selector = &card.other_info;
 
         for el in document.select(selector) {
             let id = el
                 .select(id_selector)
                 .last()
                 .and_then(|el| el.value().attr("href"))
-                .map(|s| s[1..].trim().to_string());
+                .and_then(AnimeId::from_href);
 
             let name = el
                 .select(name_selector)
@@ -411,9 +456,8 @@ impl HiAnimeUtils {
                 .select(rank_selector)
                 .next()
                 .and_then(|el| el.text().next())
-                .map(|s| s.split_whitespace().next())
-                .flatten()
-                .and_then(|s| s[1..].parse::<u32>().ok());
+                .and_then(|s| s.split_whitespace().next())
+                .and_then(|s| s.trim_start_matches('#').parse::<u32>().ok());
 
             let sub = el
                 .select(sub_episodes_selector)
@@ -469,10 +513,42 @@ impl HiAnimeUtils {
         }
     }
 
+    /// splits a character/people bio into its description and its `key: value` lines <br/>
+    /// 0th -> description, 1st -> about fields
+    pub fn extract_bio(
+        document: &Html,
+        bio_selector: &Selector,
+    ) -> (Option<String>, HashMap<String, String>) {
+        let mut description = vec![];
+        let mut about = HashMap::new();
+
+        if let Some(el) = document.select(bio_selector).next() {
+            for line in el.text().map(|s| s.trim()).filter(|s| !s.is_empty()) {
+                match line.split_once(':') {
+                    Some((key, value))
+                        if !key.trim().is_empty()
+                            && key.len() <= 30
+                            && !value.trim().is_empty() =>
+                    {
+                        about.insert(key.trim().to_lowercase(), value.trim().to_string());
+                    }
+                    _ => description.push(line),
+                }
+            }
+        }
+
+        let description = if description.is_empty() {
+            None
+        } else {
+            Some(description.join("\n"))
+        };
+
+        return (description, about);
+    }
+
     /// 0th -> mal_id, 1st -> anilist_id
-    pub fn get_mal_anilist_id(document: &Html) -> (Option<u32>, Option<u32>) {
+    pub fn get_mal_anilist_id(document: &Html, selector: &Selector) -> (Option<u32>, Option<u32>) {
         let mut ids = (None, None);
-        let selector = &Selector::parse("body #syncData").unwrap();
 
         if let Some(el) = document.select(selector).next() {
             let json_str = el.text().next().unwrap_or_default();
@@ -480,20 +556,18 @@ impl HiAnimeUtils {
 
             ids.0 = parsed_json["mal_id"]
                 .as_str()
-                .map(|s| s.parse::<u32>().ok())
-                .flatten();
+                .and_then(|s| s.parse::<u32>().ok());
             ids.1 = parsed_json["anilist_id"]
                 .as_str()
-                .map(|s| s.parse::<u32>().ok())
-                .flatten();
+                .and_then(|s| s.parse::<u32>().ok());
         }
 
         return ids;
     }
 
-    pub fn has_next_page(document: &Html) -> bool {
-        let pagination_selector = &Selector::parse(".pagination > li").unwrap();
-        let active_selector = &Selector::parse(".pagination li.active").unwrap();
+    pub fn has_next_page(document: &Html, pagination: &compiled::PaginationSelectors) -> bool {
+        let pagination_selector = &pagination.items;
+        let active_selector = &pagination.active;
 
         if document.select(pagination_selector).count() == 0 {
             return false;
@@ -502,45 +576,156 @@ impl HiAnimeUtils {
             return false;
         }
 
-        if let Some(last_li) = document.select(pagination_selector).last() {
+        if let Some(last_li) = document.select(pagination_selector).next_back() {
             return !last_li.value().classes().any(|class| class == "active");
         }
         false
     }
 
-    pub fn get_total_pages(document: &Html) -> u16 {
-        let pagination_last_selector =
-            Selector::parse(r#".pagination > .page-item a[title="Last"]"#).unwrap();
-        let pagination_next_selector =
-            Selector::parse(r#".pagination > .page-item a[title="Next"]"#).unwrap();
-        let pagination_active_selector =
-            Selector::parse(".pagination > .page-item.active a").unwrap();
+    pub fn get_total_pages(document: &Html, pagination: &compiled::PaginationSelectors) -> u16 {
+        let pagination_last_selector = &pagination.last;
+        let pagination_next_selector = &pagination.next;
+        let pagination_active_selector = &pagination.active_link;
 
+        // ajax paginations carry the page number in `data-page` instead of `href`
         let last_page = document
-            .select(&pagination_last_selector)
-            .filter_map(|el| el.value().attr("href"))
-            .flat_map(|href| href.split('=').last())
+            .select(pagination_last_selector)
+            .filter_map(|el| el.value().attr("href").or(el.value().attr("data-page")))
+            .flat_map(|href| href.split('=').next_back())
             .next();
 
         let next_page = document
-            .select(&pagination_next_selector)
-            .filter_map(|el| el.value().attr("href"))
-            .flat_map(|href| href.split('=').last())
+            .select(pagination_next_selector)
+            .filter_map(|el| el.value().attr("href").or(el.value().attr("data-page")))
+            .flat_map(|href| href.split('=').next_back())
             .next();
 
         let active_page = document
-            .select(&pagination_active_selector)
+            .select(pagination_active_selector)
             .flat_map(|el| el.text().collect::<Vec<_>>())
             .next()
-            .map(|s| s.parse::<u16>().ok())
-            .flatten();
+            .and_then(|s| s.parse::<u16>().ok());
 
-        let total_pages = last_page
+        return last_page
             .or(next_page)
             .and_then(|page| page.parse::<u16>().ok())
             .or(active_page)
             .unwrap_or(1);
+    }
+}
+
+/// http client of the [`super::Scraper`], the unit tests point it at a local
+/// server standing in for hianime and anilist
+#[derive(Debug, Clone)]
+pub(crate) struct HiAnimeClient {
+    client: Client,
+    #[cfg(test)]
+    origin: Option<String>,
+}
+
+impl HiAnimeClient {
+    pub fn new(client: Client) -> Self {
+        return Self {
+            client,
+            #[cfg(test)]
+            origin: None,
+        };
+    }
+
+    /// sends the requests meant for hianime to `origin`, and the ones meant
+    /// for anilist to `{origin}/anilist`
+    #[cfg(test)]
+    pub fn with_origin(mut self, origin: String) -> Self {
+        self.origin = Some(origin);
+        return self;
+    }
+
+    fn url(&self, url: String) -> String {
+        #[cfg(test)]
+        if let Some(origin) = &self.origin {
+            if let Some(path) = url.strip_prefix(HiAnimeUtils::BaseUrl.value()) {
+                return format!("{origin}{path}");
+            }
+            if let Some(path) = url.strip_prefix(HiAnimeUtils::AnilistGraphqlUrl.value()) {
+                return format!("{origin}/anilist{path}");
+            }
+        }
+        return url;
+    }
+}
+
+impl EnmaClient for HiAnimeClient {
+    fn get_html(
+        &self,
+        url: String,
+        headers: Option<HeaderMap>,
+        provider_parser: &'static str,
+    ) -> impl Future<Output = EnmaResult<String>> + Send {
+        return self
+            .client
+            .get_html(self.url(url), headers, provider_parser);
+    }
+
+    fn get_json<T: DeserializeOwned + Send>(
+        &self,
+        url: String,
+        headers: Option<HeaderMap>,
+        provider_parser: &'static str,
+    ) -> impl Future<Output = EnmaResult<T>> + Send {
+        return self
+            .client
+            .get_json(self.url(url), headers, provider_parser);
+    }
+
+    fn post_json<T: DeserializeOwned + Send, B: Serialize + Sync + ?Sized>(
+        &self,
+        url: String,
+        body: &B,
+        headers: Option<HeaderMap>,
+        provider_parser: &'static str,
+    ) -> impl Future<Output = EnmaResult<T>> + Send {
+        return self
+            .client
+            .post_json(self.url(url), body, headers, provider_parser);
+    }
+
+    fn get_bytes(
+        &self,
+        url: String,
+        headers: Option<HeaderMap>,
+        provider_parser: &'static str,
+    ) -> impl Future<Output = EnmaResult<Vec<u8>>> + Send {
+        return self
+            .client
+            .get_bytes(self.url(url), headers, provider_parser);
+    }
+
+    fn get_final_url(
+        &self,
+        url: String,
+        headers: Option<HeaderMap>,
+        provider_parser: &'static str,
+    ) -> impl Future<Output = EnmaResult<String>> + Send {
+        return self
+            .client
+            .get_final_url(self.url(url), headers, provider_parser);
+    }
+}
 
-        total_pages
+#[cfg(test)]
+mod test {
+    use super::HiAnimeUtils;
+    use tracing_test::traced_test;
+
+    // cargo test --lib -- anime::hianime::utils::test --show-output
+    #[test]
+    #[traced_test]
+    fn test_warn_if_empty() {
+        HiAnimeUtils::warn_if_empty("spotlight_animes", 2);
+        assert!(!logs_contain("selector matched no elements"));
+
+        HiAnimeUtils::warn_if_empty("spotlight_animes", 0);
+        assert!(logs_contain("selector matched no elements"));
+        assert!(logs_contain("field=\"spotlight_animes\""));
     }
 }
@@ -0,0 +1,752 @@
+use crate::{
+    anime::SubtitleTrack,
+    error::{EnmaError, EnmaResult},
+    utils::{EnmaClient, EnmaUtils},
+};
+use reqwest::{header::HeaderMap, Client};
+use serde::{Deserialize, Serialize};
+use std::fmt::Write;
+
+const PROVIDER_PARSER: &'static str = "anime:subtitles";
+
+/// language codes [`select_track`] also matches by their english name, as
+/// the servers usually label their tracks with the latter
+const LANGUAGES: &[(&str, &str)] = &[
+    ("ar", "arabic"),
+    ("de", "german"),
+    ("en", "english"),
+    ("es", "spanish"),
+    ("fr", "french"),
+    ("id", "indonesian"),
+    ("it", "italian"),
+    ("ja", "japanese"),
+    ("ko", "korean"),
+    ("ms", "malay"),
+    ("pt", "portuguese"),
+    ("ru", "russian"),
+    ("th", "thai"),
+    ("tr", "turkish"),
+    ("vi", "vietnamese"),
+    ("zh", "chinese"),
+];
+
+/// header of the ASS files [`Subtitles::to_ass`] writes, with the one style
+/// every dialogue line uses
+const ASS_HEADER: &'static str = "[Script Info]
+ScriptType: v4.00+
+PlayResX: 1920
+PlayResY: 1080
+WrapStyle: 0
+ScaledBorderAndShadow: yes
+
+[V4+ Styles]
+Format: Name, Fontname, Fontsize, PrimaryColour, SecondaryColour, OutlineColour, BackColour, Bold, Italic, Underline, StrikeOut, ScaleX, ScaleY, Spacing, Angle, BorderStyle, Outline, Shadow, Alignment, MarginL, MarginR, MarginV, Encoding
+Style: Default,Arial,72,&H00FFFFFF,&H000000FF,&H00000000,&H80000000,0,0,0,0,100,100,0,0,1,3,1,2,60,60,50,1
+
+[Events]
+Format: Layer, Start, End, Style, Name, MarginL, MarginR, MarginV, Effect, Text
+";
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Subtitles {
+    pub cues: Vec<Cue>,
+    /// css of the WebVTT `STYLE` blocks
+    pub styles: Vec<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct Cue {
+    pub id: Option<String>,
+    /// in milliseconds
+    pub start: u64,
+    /// in milliseconds
+    pub end: u64,
+    pub settings: CueSettings,
+    /// text with its styling tags, e.g. `<i>`, `<c.yellow>` or `<v Luffy>`
+    pub text: String,
+}
+
+/// the WebVTT cue settings, as written after the cue's timings
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+pub struct CueSettings {
+    pub vertical: Option<String>,
+    pub line: Option<String>,
+    pub position: Option<String>,
+    pub size: Option<String>,
+    pub align: Option<String>,
+    pub region: Option<String>,
+}
+
+impl Subtitles {
+    /// parses WebVTT or, when it lacks the `WEBVTT` header, SRT
+    pub fn parse(subtitles: &str) -> EnmaResult<Self> {
+        let subtitles = subtitles
+            .trim_start_matches('\u{feff}')
+            .replace("\r\n", "\n")
+            .replace('\r', "\n");
+        let is_vtt = subtitles.starts_with("WEBVTT");
+
+        let mut blocks: Vec<Vec<&str>> = vec![];
+        let mut block = vec![];
+        for line in subtitles.lines() {
+            match line.trim().is_empty() {
+                true if !block.is_empty() => blocks.push(std::mem::take(&mut block)),
+                true => (),
+                false => block.push(line),
+            }
+        }
+        if !block.is_empty() {
+            blocks.push(block);
+        }
+
+        let mut res = Subtitles::default();
+        // the header block of WebVTT holds no cue
+        for block in blocks.iter().skip(is_vtt as usize) {
+            let first = block[0].trim();
+            if is_vtt && (first == "NOTE" || first.starts_with("NOTE ") || first == "REGION") {
+                continue;
+            }
+            if is_vtt && first == "STYLE" {
+                res.styles.push(block[1..].join("\n"));
+                continue;
+            }
+
+            let Some(timings_at) = block.iter().position(|line| line.contains("-->")) else {
+                continue;
+            };
+            if let Some(cue) = parse_cue(block, timings_at) {
+                res.cues.push(cue);
+            }
+        }
+
+        if !is_vtt && res.cues.is_empty() {
+            return Err(EnmaError::parsing_error(
+                PROVIDER_PARSER,
+                Some(String::from("neither WebVTT nor SRT subtitles")),
+                None,
+            ));
+        }
+
+        return Ok(res);
+    }
+
+    /// moves every cue by `offset` milliseconds, cues that'd end before the
+    /// start of the video are dropped
+    pub fn shift(&mut self, offset: i64) {
+        self.cues.retain_mut(|cue| {
+            cue.start = cue.start.saturating_add_signed(offset);
+            cue.end = cue.end.saturating_add_signed(offset);
+            return cue.end > 0;
+        });
+    }
+
+    pub fn to_vtt(&self) -> String {
+        let mut res = String::from("WEBVTT\n");
+        for style in self.styles.iter() {
+            let _ = write!(res, "\nSTYLE\n{style}\n");
+        }
+
+        for cue in self.cues.iter() {
+            res.push('\n');
+            if let Some(id) = &cue.id {
+                let _ = writeln!(res, "{id}");
+            }
+            let _ = writeln!(
+                res,
+                "{} --> {}{}\n{}",
+                format_timestamp(cue.start, '.'),
+                format_timestamp(cue.end, '.'),
+                cue.settings.to_vtt(),
+                cue.text
+            );
+        }
+
+        return res;
+    }
+
+    /// SRT keeps the bold, italic and underline tags, the other WebVTT tags
+    /// and the cue settings are dropped
+    pub fn to_srt(&self) -> String {
+        let mut res = String::new();
+        for (i, cue) in self.cues.iter().enumerate() {
+            let _ = write!(
+                res,
+                "{}{}\n{} --> {}\n{}\n",
+                if i == 0 { "" } else { "\n" },
+                i + 1,
+                format_timestamp(cue.start, ','),
+                format_timestamp(cue.end, ','),
+                cue.srt_text()
+            );
+        }
+
+        return res;
+    }
+
+    /// bold, italic and underline tags become override tags, the voice of
+    /// `<v>` tags the dialogue's name and the `line` and `align` settings its
+    /// alignment, other tags and settings are dropped
+    pub fn to_ass(&self) -> String {
+        let mut res = String::from(ASS_HEADER);
+        for cue in self.cues.iter() {
+            let (name, text) = cue.ass_text();
+            let alignment = match cue.settings.ass_alignment() {
+                2 => String::new(),
+                n => format!("{{\\an{n}}}"),
+            };
+            let _ = writeln!(
+                res,
+                "Dialogue: 0,{},{},Default,{name},0,0,0,,{alignment}{text}",
+                format_ass_timestamp(cue.start),
+                format_ass_timestamp(cue.end),
+            );
+        }
+
+        return res;
+    }
+}
+
+impl Cue {
+    /// text without any tag
+    pub fn plain_text(&self) -> String {
+        let mut res = String::new();
+        for token in visible_tokens(&self.text) {
+            if let Token::Text(text) = token {
+                res.push_str(&text);
+            }
+        }
+        return res;
+    }
+
+    fn srt_text(&self) -> String {
+        let mut res = String::new();
+        for token in visible_tokens(&self.text) {
+            match token {
+                Token::Text(text) => res.push_str(&text),
+                Token::Open(tag @ ("b" | "i" | "u"), _) => {
+                    let _ = write!(res, "<{tag}>");
+                }
+                Token::Close(tag @ ("b" | "i" | "u")) => {
+                    let _ = write!(res, "</{tag}>");
+                }
+                _ => (),
+            }
+        }
+        return res;
+    }
+
+    /// the speaker, if any, and the text
+    fn ass_text(&self) -> (String, String) {
+        let mut name = String::new();
+        let mut res = String::new();
+        for token in visible_tokens(&self.text) {
+            match token {
+                // braces would otherwise open an override block
+                Token::Text(text) => res.push_str(
+                    &text
+                        .replace('{', "\\{")
+                        .replace('}', "\\}")
+                        .replace('\n', "\\N"),
+                ),
+                Token::Open(tag @ ("b" | "i" | "u"), _) => {
+                    let _ = write!(res, "{{\\{tag}1}}");
+                }
+                Token::Close(tag @ ("b" | "i" | "u")) => {
+                    let _ = write!(res, "{{\\{tag}0}}");
+                }
+                Token::Open("v", voice) if name.is_empty() => name = voice.replace(',', " "),
+                _ => (),
+            }
+        }
+        return (name, res);
+    }
+}
+
+impl CueSettings {
+    fn to_vtt(&self) -> String {
+        let mut res = String::new();
+        let settings = [
+            ("vertical", &self.vertical),
+            ("line", &self.line),
+            ("position", &self.position),
+            ("size", &self.size),
+            ("align", &self.align),
+            ("region", &self.region),
+        ];
+        for (key, value) in settings {
+            if let Some(value) = value {
+                let _ = write!(res, " {key}:{value}");
+            }
+        }
+        return res;
+    }
+
+    /// numpad style alignment of ASS, bottom center unless the cue sits in
+    /// the upper half or isn't centered
+    fn ass_alignment(&self) -> u8 {
+        let column = match self.align.as_deref() {
+            Some("start" | "left") => 1,
+            Some("end" | "right") => 3,
+            _ => 2,
+        };
+
+        let line = self.line.as_deref().and_then(|l| l.split(',').next());
+        let top = match line.map(|l| l.trim()) {
+            Some(line) => match line.strip_suffix('%') {
+                Some(percent) => percent.parse::<f32>().is_ok_and(|p| p < 50.0),
+                // positive line numbers count from the top
+                None => line.parse::<i32>().is_ok_and(|n| n >= 0),
+            },
+            None => false,
+        };
+
+        return if top { column + 6 } else { column };
+    }
+}
+
+/// picks the track to show by default, the first one in the language of
+/// `preferences`, tried in order, else the one the server marks as default,
+/// else the first one
+///
+/// a preference matches the tracks labeled with it, e.g. `pt-BR`, or a
+/// subtag of it, e.g. `en` matches `en-US`, and, for the common languages,
+/// with its english name, e.g. `en` matches `English - CC`
+pub fn select_track<'a>(
+    tracks: &'a [SubtitleTrack],
+    preferences: &[&str],
+) -> Option<&'a SubtitleTrack> {
+    return preferences
+        .iter()
+        .find_map(|preference| {
+            tracks
+                .iter()
+                .find(|track| matches_language(&track.lang, preference))
+        })
+        .or_else(|| tracks.iter().find(|track| track.default))
+        .or(tracks.first());
+}
+
+fn matches_language(lang: &str, preference: &str) -> bool {
+    let lang = lang.trim().to_lowercase();
+    let preference = preference.trim().to_lowercase();
+    if preference.is_empty() {
+        return false;
+    }
+
+    let english_name = LANGUAGES
+        .iter()
+        .find(|(code, _)| *code == preference)
+        .map(|(_, name)| name.to_string());
+
+    return std::iter::once(preference).chain(english_name).any(|name| {
+        match lang.strip_prefix(&name) {
+            Some(rest) => rest.is_empty() || rest.starts_with(|c: char| !c.is_alphanumeric()),
+            None => false,
+        }
+    });
+}
+
+/// fetches subtitle tracks and parses them
+#[derive(Debug, Clone)]
+pub struct SubtitlesClient {
+    client: Client,
+}
+
+impl Default for SubtitlesClient {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+impl SubtitlesClient {
+    pub fn new() -> Self {
+        return Self {
+            client: EnmaUtils::new_http_client(None),
+        };
+    }
+
+    /// same as [`SubtitlesClient::new`] but every request carries `headers`,
+    /// e.g. the `Referer` the track's server expects
+    pub fn with_headers(headers: HeaderMap) -> Self {
+        return Self {
+            client: EnmaUtils::new_http_client(Some(headers)),
+        };
+    }
+
+    /// ### Example Usage
+    /// ```rust
+    /// async fn get_data() {
+    ///     use enma::anime::{
+    ///         subtitles::{self, SubtitlesClient},
+    ///         SubtitleTrack,
+    ///     };
+    ///     let client = SubtitlesClient::new();
+    ///
+    ///     let tracks = [SubtitleTrack {
+    ///         url: String::from("https://cdn.example.com/subs/eng-2.vtt"),
+    ///         lang: String::from("English"),
+    ///         default: true,
+    ///     }];
+    ///     let track = subtitles::select_track(&tracks, &["en"]).unwrap();
+    ///
+    ///     match client.fetch(track).await {
+    ///         Ok(mut subtitles) => {
+    ///             subtitles.shift(-1500);
+    ///             println!("{}", subtitles.to_srt())
+    ///         }
+    ///         Err(e) => eprintln!("error: {e}"),
+    ///     }
+    /// }
+    ///  ```
+    #[tracing::instrument(name = "anime:subtitles:fetch", skip(self))]
+    pub async fn fetch(&self, track: &SubtitleTrack) -> EnmaResult<Subtitles> {
+        // unlike `get_html`, an error status isn't parsed as an empty track
+        let subtitles = self
+            .client
+            .get_bytes(track.url.clone(), None, PROVIDER_PARSER)
+            .await?;
+
+        return Subtitles::parse(&String::from_utf8_lossy(&subtitles));
+    }
+
+    /// fetches every track, in order
+    pub async fn fetch_all(&self, tracks: &[SubtitleTrack]) -> EnmaResult<Vec<Subtitles>> {
+        let mut res = Vec::with_capacity(tracks.len());
+        for track in tracks {
+            res.push(self.fetch(track).await?);
+        }
+        return Ok(res);
+    }
+}
+
+fn parse_cue(block: &[&str], timings_at: usize) -> Option<Cue> {
+    // at most an id precedes the timings
+    if timings_at > 1 {
+        return None;
+    }
+
+    let (start, rest) = block[timings_at].split_once("-->")?;
+    let mut rest = rest.split_whitespace();
+    let mut cue = Cue {
+        id: block[..timings_at].first().map(|id| id.trim().to_string()),
+        start: parse_timestamp(start.trim())?,
+        end: parse_timestamp(rest.next()?)?,
+        text: block[timings_at + 1..].join("\n"),
+        ..Default::default()
+    };
+
+    for setting in rest {
+        let Some((key, value)) = setting.split_once(':') else {
+            continue;
+        };
+        let value = Some(value.to_string());
+        match key {
+            "vertical" => cue.settings.vertical = value,
+            "line" => cue.settings.line = value,
+            "position" => cue.settings.position = value,
+            "size" => cue.settings.size = value,
+            "align" => cue.settings.align = value,
+            "region" => cue.settings.region = value,
+            _ => (),
+        }
+    }
+
+    return Some(cue);
+}
+
+/// `[hh:]mm:ss.ttt`, or with a comma as in SRT, in milliseconds
+fn parse_timestamp(timestamp: &str) -> Option<u64> {
+    let (rest, millis) = timestamp.split_once(['.', ','])?;
+    let mut parts = rest.rsplit(':');
+
+    let seconds = parts.next()?.parse::<u64>().ok().filter(|s| *s < 60)?;
+    let minutes = parts.next()?.parse::<u64>().ok().filter(|m| *m < 60)?;
+    let hours = match parts.next() {
+        Some(hours) => hours.parse::<u64>().ok()?,
+        None => 0,
+    };
+    if parts.next().is_some() || millis.len() != 3 {
+        return None;
+    }
+    let millis = millis.parse::<u64>().ok()?;
+
+    // hours are unbounded, a timestamp past u64::MAX milliseconds is malformed
+    return hours
+        .checked_mul(3_600_000)?
+        .checked_add((minutes * 60 + seconds) * 1000 + millis);
+}
+
+fn format_timestamp(millis: u64, separator: char) -> String {
+    let (hours, minutes, seconds) = (millis / 3_600_000, millis / 60_000 % 60, millis / 1000 % 60);
+    return format!(
+        "{hours:02}:{minutes:02}:{seconds:02}{separator}{:03}",
+        millis % 1000
+    );
+}
+
+/// `h:mm:ss.cc`, ASS counts in centiseconds
+fn format_ass_timestamp(millis: u64) -> String {
+    let (hours, minutes, seconds) = (millis / 3_600_000, millis / 60_000 % 60, millis / 1000 % 60);
+    return format!(
+        "{hours}:{minutes:02}:{seconds:02}.{:02}",
+        millis % 1000 / 10
+    );
+}
+
+enum Token<'a> {
+    /// text with its character references decoded
+    Text(String),
+    /// tag name, e.g. `c` for `<c.yellow>`, and annotation, e.g. the voice
+    /// of `<v Luffy>`
+    Open(&'a str, &'a str),
+    Close(&'a str),
+}
+
+/// the tokens of a cue's text, without the ruby text shown above the base
+/// text, which no other format can place
+fn visible_tokens(text: &str) -> Vec<Token<'_>> {
+    let mut in_ruby_text = false;
+    return tokens(text)
+        .into_iter()
+        .filter(|token| match token {
+            Token::Open("rt", _) => {
+                in_ruby_text = true;
+                return false;
+            }
+            Token::Close("rt") => {
+                in_ruby_text = false;
+                return false;
+            }
+            _ => !in_ruby_text,
+        })
+        .collect();
+}
+
+fn tokens(text: &str) -> Vec<Token<'_>> {
+    let mut res = vec![];
+    let mut rest = text;
+
+    while !rest.is_empty() {
+        let Some(tag_at) = rest.find('<') else {
+            res.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        if tag_at > 0 {
+            res.push(Token::Text(decode_entities(&rest[..tag_at])));
+            rest = &rest[tag_at..];
+            continue;
+        }
+
+        let Some(tag_end) = rest.find('>') else {
+            res.push(Token::Text(decode_entities(rest)));
+            break;
+        };
+        let tag = &rest[1..tag_end];
+        rest = &rest[tag_end + 1..];
+
+        let (closing, tag) = match tag.strip_prefix('/') {
+            Some(tag) => (true, tag),
+            None => (false, tag),
+        };
+        let (name, annotation) = tag
+            .split_once(|c: char| c.is_whitespace())
+            .unwrap_or((tag, ""));
+        // drops the classes of e.g. `<c.yellow.bg_blue>`
+        let name = name.split('.').next().unwrap_or_default();
+
+        // karaoke timestamps, e.g. `<00:01.500>`, aren't tags
+        if name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()) {
+            continue;
+        }
+        res.push(match closing {
+            true => Token::Close(name),
+            false => Token::Open(name, annotation.trim()),
+        });
+    }
+
+    return res;
+}
+
+fn decode_entities(text: &str) -> String {
+    return text
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&nbsp;", "\u{a0}")
+        .replace("&lrm;", "\u{200e}")
+        .replace("&rlm;", "\u{200f}")
+        .replace("&amp;", "&");
+}
+
+#[cfg(test)]
+mod test {
+    use super::{select_track, Subtitles, SubtitlesClient};
+    use crate::{
+        anime::SubtitleTrack,
+        test_utils::{fixture, Routes},
+        EnmaError,
+    };
+
+    fn track(lang: &str, default: bool) -> SubtitleTrack {
+        return SubtitleTrack {
+            url: format!("https://cdn.example.com/subs/{lang}.vtt"),
+            lang: lang.to_string(),
+            default,
+        };
+    }
+
+    // cargo test --features subtitles --lib -- anime::subtitles::test --show-output
+    #[test]
+    fn test_parse_vtt() {
+        let subtitles = Subtitles::parse(&fixture("subtitles/episode.vtt")).unwrap();
+        println!("{subtitles:#?}");
+
+        assert_eq!(
+            subtitles.styles,
+            vec!["::cue(.yellow) {\n  color: yellow;\n}"]
+        );
+        assert_eq!(subtitles.cues.len(), 4);
+
+        let intro = &subtitles.cues[0];
+        assert_eq!(intro.id.as_deref(), Some("intro"));
+        assert_eq!((intro.start, intro.end), (1000, 4500));
+        assert_eq!(intro.settings.line.as_deref(), Some("0"));
+        assert_eq!(intro.settings.align.as_deref(), Some("start"));
+        assert_eq!(intro.settings.position.as_deref(), Some("10%"));
+
+        let voiced = &subtitles.cues[1];
+        assert_eq!(voiced.id, None);
+        assert_eq!(voiced.plain_text(), "Gum-Gum & Pistol!\nZoro >_<");
+
+        assert_eq!(subtitles.cues[2].plain_text(), "海賊王");
+        assert_eq!(subtitles.cues[3].start, 3_723_004);
+        assert_eq!(subtitles.cues[3].plain_text(), "Late line");
+    }
+
+    #[test]
+    fn test_convert() {
+        let subtitles = Subtitles::parse(&fixture("subtitles/episode.vtt")).unwrap();
+
+        let srt = subtitles.to_srt();
+        println!("{srt}");
+        assert_eq!(srt, fixture("subtitles/episode.srt"));
+
+        let ass = subtitles.to_ass();
+        println!("{ass}");
+        assert_eq!(ass, fixture("subtitles/episode.ass"));
+
+        // the srt reads back to the same cues, bar the dropped tags
+        let from_srt = Subtitles::parse(&srt).unwrap();
+        assert_eq!(from_srt.to_srt(), srt);
+        assert_eq!(from_srt.cues[0].id.as_deref(), Some("1"));
+
+        let vtt = subtitles.to_vtt();
+        println!("{vtt}");
+        assert_eq!(Subtitles::parse(&vtt).unwrap(), subtitles);
+        // literal braces don't open an override block
+        let braces = Subtitles::parse("WEBVTT\n\n00:01.000 --> 00:02.000\n<i>{sighs}</i> fine }{")
+            .unwrap()
+            .to_ass();
+        assert!(
+            braces.ends_with(",,{\\i1}\\{sighs\\}{\\i0} fine \\}\\{\n"),
+            "{braces}"
+        );
+    }
+
+    #[test]
+    fn test_shift() {
+        let mut subtitles = Subtitles::parse(&fixture("subtitles/episode.vtt")).unwrap();
+
+        subtitles.shift(1500);
+        assert_eq!(subtitles.cues[0].start, 2500);
+        assert_eq!(subtitles.cues[3].end, 3_726_499);
+
+        // the intro now ends before the video starts
+        subtitles.shift(-6500);
+        assert_eq!(subtitles.cues.len(), 3);
+        assert_eq!(
+            (subtitles.cues[0].start, subtitles.cues[0].end),
+            (250, 3000)
+        );
+        assert!(subtitles
+            .to_srt()
+            .starts_with("1\n00:00:00,250 --> 00:00:03,000\n"));
+    }
+
+    #[test]
+    fn test_parse_invalid_subtitles() {
+        assert!(Subtitles::parse("").is_err());
+        assert!(Subtitles::parse("<html><body>404</body></html>").is_err());
+        assert!(Subtitles::parse("1\n00:00:01,000 --> later\nhello").is_err());
+        // a WebVTT file without cues is valid, malformed cues are skipped
+        let subtitles = Subtitles::parse("WEBVTT\n\n00:61.000 --> 00:62.000\nhello").unwrap();
+        assert_eq!(subtitles, Subtitles::default());
+
+        // hours overflowing the milliseconds skip the cue instead of panicking
+        let subtitles = Subtitles::parse(
+            "WEBVTT\n\n99999999999999999:00:00.000 --> 99999999999999999:00:01.000\nlost\n\n\
+             00:01.000 --> 00:02.000\nkept",
+        )
+        .unwrap();
+        assert_eq!(subtitles.cues.len(), 1);
+        assert_eq!(subtitles.cues[0].plain_text(), "kept");
+        assert!(
+            Subtitles::parse("1\n5124095576030:25:51,616 --> 5124095576030:25:51,617\nlost")
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_select_track() {
+        let tracks = [
+            track("Arabic", false),
+            track("English - CC", true),
+            track("Portuguese - Brazilian Portuguese", false),
+            track("pt-BR", false),
+        ];
+        let lang = |preferences: &[&str]| select_track(&tracks, preferences).map(|t| &t.lang[..]);
+
+        assert_eq!(lang(&["en"]), Some("English - CC"));
+        assert_eq!(lang(&["english"]), Some("English - CC"));
+        assert_eq!(lang(&["pt-br", "en"]), Some("pt-BR"));
+        assert_eq!(lang(&["pt"]), Some("Portuguese - Brazilian Portuguese"));
+        assert_eq!(lang(&["ja", "ar"]), Some("Arabic"));
+        // "e" isn't "en"
+        assert_eq!(lang(&["e"]), Some("English - CC"));
+        assert_eq!(lang(&["ja"]), Some("English - CC"));
+        assert_eq!(lang(&[]), Some("English - CC"));
+
+        assert_eq!(
+            select_track(&[track("Arabic", false)], &["ja"]).map(|t| &t.lang[..]),
+            Some("Arabic")
+        );
+        assert_eq!(select_track(&[], &["en"]), None);
+    }
+
+    #[tokio::test]
+    async fn test_fetch() {
+        let server = Routes::new()
+            .route("/subs/eng-2.vtt", fixture("subtitles/episode.vtt"))
+            .serve()
+            .await;
+
+        let track = SubtitleTrack {
+            url: server.url("/subs/eng-2.vtt"),
+            lang: String::from("English"),
+            default: true,
+        };
+        let subtitles = SubtitlesClient::new().fetch(&track).await.unwrap();
+        assert_eq!(subtitles.cues.len(), 4);
+
+        let missing = SubtitleTrack {
+            url: server.url("/subs/missing.vtt"),
+            ..track
+        };
+        match SubtitlesClient::new().fetch(&missing).await {
+            Err(EnmaError::SrcFetchError { details }) => assert_eq!(details.status, 404),
+            other => panic!("expected a fetch error, got {other:?}"),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+use enma::anime::hianime;
+use std::net::SocketAddr;
+
+mod response;
+mod routes;
+
+/// same default port as aniwatch-api
+const DEFAULT_PORT: u16 = 4000;
+
+#[tokio::main]
+async fn main() -> std::io::Result<()> {
+    let host = std::env::var("ENMA_HOST").unwrap_or_else(|_| String::from("127.0.0.1"));
+    let port = std::env::var("ENMA_PORT")
+        .ok()
+        .and_then(|port| port.parse::<u16>().ok())
+        .unwrap_or(DEFAULT_PORT);
+
+    let addr = format!("{host}:{port}")
+        .parse::<SocketAddr>()
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidInput, e))?;
+
+    let app = routes::router(hianime::Scraper::new());
+    let listener = tokio::net::TcpListener::bind(addr).await?;
+    println!("enma-server listening on http://{}", listener.local_addr()?);
+
+    return axum::serve(listener, app).await;
+}
@@ -0,0 +1,103 @@
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Response},
+    Json,
+};
+use enma::{anime::hianime::AniwatchJson, EnmaError, EnmaResult};
+use serde::Serialize;
+use serde_json::Value;
+
+/// aniwatch-api's response envelope, `{ "status": 200, "data": .. }` on
+/// success and `{ "status": 404, "message": ".." }` on failure, the data
+/// being in aniwatch's json shape
+#[derive(Debug, Serialize)]
+#[serde(untagged)]
+pub enum Envelope {
+    Data { status: u16, data: Value },
+    Error { status: u16, message: String },
+}
+
+impl Envelope {
+    pub fn from_result<T: AniwatchJson>(result: EnmaResult<T>) -> Self {
+        let data = match result {
+            Ok(data) => data.to_aniwatch_json(),
+            Err(e) => return Envelope::from_error(&e),
+        };
+
+        return match data {
+            Ok(data) => Envelope::Data {
+                status: StatusCode::OK.as_u16(),
+                data,
+            },
+            Err(e) => Envelope::from_error(&EnmaError::misc_error(
+                "enma:server",
+                Some(format!("failed to serialize response: {e}")),
+                None,
+            )),
+        };
+    }
+
+    pub fn from_error(err: &EnmaError) -> Self {
+        let details = err.details();
+        return Envelope::Error {
+            status: status_code(details.status).as_u16(),
+            message: details.message.clone(),
+        };
+    }
+
+    fn status(&self) -> StatusCode {
+        match self {
+            Envelope::Data { .. } => StatusCode::OK,
+            Envelope::Error { status, .. } => status_code(*status),
+        }
+    }
+}
+
+impl IntoResponse for Envelope {
+    fn into_response(self) -> Response {
+        return (self.status(), Json(self)).into_response();
+    }
+}
+
+/// errors carry the source's own status when it failed to respond properly,
+/// anything that isn't a valid error status is reported as a 500
+fn status_code(status: u16) -> StatusCode {
+    return StatusCode::from_u16(status)
+        .ok()
+        .filter(|s| s.is_client_error() || s.is_server_error())
+        .unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+}
+
+#[cfg(test)]
+mod test {
+    use super::Envelope;
+    use axum::{http::StatusCode, response::IntoResponse};
+    use enma::{anime::hianime::ScrapedAnimeEpisodes, EnmaError};
+    use reqwest::StatusCode as SrcStatusCode;
+
+    // cargo test --features server --bin enma-server -- response::test --show-output
+    #[test]
+    fn test_envelope() {
+        let ok = Envelope::from_result(Ok(ScrapedAnimeEpisodes::default()));
+        assert_eq!(
+            serde_json::to_value(&ok).unwrap(),
+            serde_json::json!({ "status": 200, "data": { "totalEpisodes": null, "episodes": [] } })
+        );
+        assert_eq!(ok.into_response().status(), StatusCode::OK);
+
+        let err = EnmaError::src_fetch_error(
+            "hianime:get_info",
+            Some(String::from("not found")),
+            Some(SrcStatusCode::NOT_FOUND),
+        );
+        let err = Envelope::from_error(&err);
+        let json = serde_json::to_value(&err).unwrap();
+        assert_eq!(json["status"], 404);
+        assert!(json["message"].as_str().unwrap().contains("not found"));
+        assert_eq!(err.into_response().status(), StatusCode::NOT_FOUND);
+
+        let err = EnmaError::misc_error("hianime:get_info", None, None);
+        let err = Envelope::from_error(&err).into_response();
+        assert_eq!(err.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    }
+}
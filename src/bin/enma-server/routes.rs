@@ -0,0 +1,297 @@
+use axum::{
+    extract::{Path, Query, State},
+    response::IntoResponse,
+    routing::get,
+    Router,
+};
+use chrono::{Datelike, NaiveDate};
+use enma::{
+    anime::hianime::{self, AniwatchJson, SearchFilter, SearchFilters},
+    EnmaError, EnmaResult,
+};
+use reqwest::StatusCode;
+use std::collections::HashMap;
+
+use crate::response::Envelope;
+
+const PROVIDER_PARSER: &'static str = "enma:server";
+
+/// query keys of [`SearchFilter`], same as aniwatch-api's search route
+const SEARCH_FILTER_KEYS: [&'static str; 10] = [
+    "type",
+    "status",
+    "rated",
+    "score",
+    "season",
+    "language",
+    "start_date",
+    "end_date",
+    "sort",
+    "genres",
+];
+
+type Scraper = State<hianime::Scraper>;
+type Params = Query<HashMap<String, String>>;
+
+/// aniwatch-api's `/api/v2/hianime` route layout on top of [`hianime::Scraper`]
+pub fn router(scraper: hianime::Scraper) -> Router {
+    let hianime = Router::new()
+        .route("/home", get(home))
+        .route("/azlist/{sort_option}", get(az_list))
+        .route("/qtip/{anime_id}", get(qtip))
+        .route("/category/{name}", get(category))
+        .route("/genre/{name}", get(genre))
+        .route("/producer/{name}", get(producer))
+        .route("/schedule", get(schedule))
+        .route("/search", get(search))
+        .route("/search/suggestion", get(search_suggestion))
+        .route("/anime/{anime_id}", get(info))
+        .route("/anime/{anime_id}/episodes", get(episodes))
+        .route("/anime/{anime_id}/characters", get(characters))
+        .route("/episode/servers", get(episode_servers))
+        .route("/character/{character_id}", get(character))
+        .route("/person/{person_id}", get(person))
+        .with_state(scraper);
+
+    return Router::new()
+        .route("/health", get(|| async { "daijoubu" }))
+        .nest("/api/v2/hianime", hianime)
+        .fallback(not_found);
+}
+
+async fn home(State(hianime): Scraper) -> impl IntoResponse {
+    Envelope::from_result(hianime.get_home_page().await)
+}
+
+async fn az_list(
+    State(hianime): Scraper,
+    Path(sort_option): Path<String>,
+    Query(params): Params,
+) -> impl IntoResponse {
+    respond(async {
+        let page = page(&params)?;
+        hianime.get_az_list(&sort_option, page).await
+    })
+    .await
+}
+
+async fn qtip(State(hianime): Scraper, Path(anime_id): Path<String>) -> impl IntoResponse {
+    Envelope::from_result(hianime.get_qtip_info(&anime_id).await)
+}
+
+async fn category(
+    State(hianime): Scraper,
+    Path(name): Path<String>,
+    Query(params): Params,
+) -> impl IntoResponse {
+    respond(async {
+        let page = page(&params)?;
+        hianime.get_category_anime(&name, page).await
+    })
+    .await
+}
+
+async fn genre(
+    State(hianime): Scraper,
+    Path(name): Path<String>,
+    Query(params): Params,
+) -> impl IntoResponse {
+    respond(async {
+        let page = page(&params)?;
+        hianime.get_genre_anime(&name, page).await
+    })
+    .await
+}
+
+async fn producer(
+    State(hianime): Scraper,
+    Path(name): Path<String>,
+    Query(params): Params,
+) -> impl IntoResponse {
+    respond(async {
+        let page = page(&params)?;
+        hianime.get_producer_anime(&name, page).await
+    })
+    .await
+}
+
+async fn schedule(State(hianime): Scraper, Query(params): Params) -> impl IntoResponse {
+    respond(async {
+        let date = required(&params, "date")?;
+        let date = NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| bad_request(format!("invalid date '{date}', expected YYYY-MM-DD")))?;
+
+        let (year, month, day) = (date.year() as u16, date.month() as u8, date.day() as u8);
+        hianime.get_schedule(year, month, day).await
+    })
+    .await
+}
+
+async fn search(State(hianime): Scraper, Query(params): Params) -> impl IntoResponse {
+    respond(async {
+        let query = required(&params, "q")?;
+        let page = page(&params)?;
+
+        let filters = SEARCH_FILTER_KEYS
+            .into_iter()
+            .filter_map(|key| {
+                let value = params.get(key).filter(|v| !v.trim().is_empty())?;
+                Some(SearchFilter::from_raw(key, value.clone()))
+            })
+            .collect::<Vec<_>>();
+        let filters = (!filters.is_empty()).then(|| SearchFilters::new(filters));
+
+        hianime.get_search_results(query, page, filters).await
+    })
+    .await
+}
+
+async fn search_suggestion(State(hianime): Scraper, Query(params): Params) -> impl IntoResponse {
+    respond(async {
+        let query = required(&params, "q")?;
+        hianime.get_search_suggestions(query).await
+    })
+    .await
+}
+
+async fn info(State(hianime): Scraper, Path(anime_id): Path<String>) -> impl IntoResponse {
+    Envelope::from_result(hianime.get_info(&anime_id).await)
+}
+
+async fn episodes(State(hianime): Scraper, Path(anime_id): Path<String>) -> impl IntoResponse {
+    Envelope::from_result(hianime.get_anime_episodes(&anime_id).await)
+}
+
+async fn characters(
+    State(hianime): Scraper,
+    Path(anime_id): Path<String>,
+    Query(params): Params,
+) -> impl IntoResponse {
+    respond(async {
+        let page = page(&params)?;
+        hianime.get_anime_characters(&anime_id, page).await
+    })
+    .await
+}
+
+async fn episode_servers(State(hianime): Scraper, Query(params): Params) -> impl IntoResponse {
+    respond(async {
+        let episode_id = required(&params, "animeEpisodeId")?;
+        hianime.get_episode_servers(episode_id).await
+    })
+    .await
+}
+
+async fn character(State(hianime): Scraper, Path(character_id): Path<String>) -> impl IntoResponse {
+    Envelope::from_result(hianime.get_character(&character_id).await)
+}
+
+async fn person(State(hianime): Scraper, Path(person_id): Path<String>) -> impl IntoResponse {
+    Envelope::from_result(hianime.get_person(&person_id).await)
+}
+
+async fn not_found() -> impl IntoResponse {
+    let err = EnmaError::invalid_data_error(
+        PROVIDER_PARSER,
+        Some(String::from("route not found")),
+        Some(StatusCode::NOT_FOUND),
+    );
+    return Envelope::from_error(&err);
+}
+
+async fn respond<T: AniwatchJson>(
+    result: impl std::future::Future<Output = EnmaResult<T>>,
+) -> Envelope {
+    return Envelope::from_result(result.await);
+}
+
+fn page(params: &HashMap<String, String>) -> EnmaResult<Option<u16>> {
+    let Some(page) = params.get("page").filter(|p| !p.trim().is_empty()) else {
+        return Ok(None);
+    };
+
+    return match page.trim().parse::<u16>() {
+        Ok(page) if page > 0 => Ok(Some(page)),
+        _ => Err(bad_request(format!(
+            "invalid page '{page}', expected a positive number"
+        ))),
+    };
+}
+
+fn required<'a>(params: &'a HashMap<String, String>, key: &str) -> EnmaResult<&'a str> {
+    return params
+        .get(key)
+        .map(|v| v.trim())
+        .filter(|v| !v.is_empty())
+        .ok_or_else(|| bad_request(format!("missing required query param '{key}'")));
+}
+
+fn bad_request(message: String) -> EnmaError {
+    EnmaError::invalid_data_error(
+        PROVIDER_PARSER,
+        Some(message),
+        Some(StatusCode::BAD_REQUEST),
+    )
+}
+
+#[cfg(test)]
+mod test {
+    use super::router;
+    use enma::anime::hianime;
+
+    async fn serve() -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        let app = router(hianime::Scraper::new());
+        tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+
+        return format!("http://{addr}");
+    }
+
+    // cargo test --features server --bin enma-server -- routes::test --include-ignored --show-output
+    #[tokio::test]
+    async fn test_error_statuses() {
+        let base_url = serve().await;
+
+        // every one of these fails before reaching the source
+        for (path, status) in [
+            ("/api/v2/hianime/anime/one-piece", 400),
+            ("/api/v2/hianime/episode/servers", 400),
+            (
+                "/api/v2/hianime/episode/servers?animeEpisodeId=one-piece-100",
+                400,
+            ),
+            ("/api/v2/hianime/search?page=2", 400),
+            ("/api/v2/hianime/search?q=monster&page=zero", 400),
+            ("/api/v2/hianime/schedule?date=2024-13-01", 400),
+            ("/api/v2/hianime/unknown", 404),
+        ] {
+            let res = reqwest::get(format!("{base_url}{path}")).await.unwrap();
+            assert_eq!(res.status().as_u16(), status, "{path}");
+
+            let body = res.json::<serde_json::Value>().await.unwrap();
+            println!("{path} {body}");
+            assert_eq!(body["status"], status, "{path}");
+            assert!(body["message"].is_string(), "{path}");
+            assert!(body.get("data").is_none(), "{path}");
+        }
+    }
+
+    #[tokio::test]
+    #[ignore = "hits the live site"]
+    async fn test_home_route() {
+        let base_url = serve().await;
+
+        let res = reqwest::get(format!("{base_url}/api/v2/hianime/home"))
+            .await
+            .unwrap();
+        let status = res.status().as_u16();
+        let body = res.json::<serde_json::Value>().await.unwrap();
+
+        assert_eq!(body["status"], status);
+        match status {
+            200 => assert!(body["data"]["spotlightAnimes"].is_array()),
+            _ => eprintln!("{body}"),
+        }
+    }
+}
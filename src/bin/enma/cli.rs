@@ -0,0 +1,155 @@
+use clap::{Args, Parser, Subcommand, ValueEnum};
+use enma::anime::hianime::{SearchFilter, SearchFilters};
+
+/// command line access to the hianime scraper
+#[derive(Parser, Debug)]
+#[command(name = "enma", version, about)]
+#[command(after_help = "Exit codes:
+  0  success
+  1  miscellaneous error
+  2  invalid command line usage
+  3  failed to fetch from the source
+  4  failed to parse the source's response
+  5  failed to parse the scraped data
+  6  invalid input, e.g. a malformed anime id")]
+pub struct Cli {
+    /// how the scraped data gets printed
+    #[arg(short, long, value_enum, global = true, default_value_t = OutputFormat::Pretty)]
+    pub output: OutputFormat,
+
+    #[command(subcommand)]
+    pub command: Command,
+}
+
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// indented json
+    Pretty,
+    /// single line json
+    Compact,
+    /// human readable tables
+    Table,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// spotlight, trending, top 10 and other home page animes
+    Home,
+
+    /// search animes by name, optionally filtered
+    Search {
+        query: String,
+        #[arg(short, long)]
+        page: Option<u16>,
+        #[command(flatten)]
+        filters: Box<SearchFilterArgs>,
+    },
+
+    /// detailed info of an anime, e.g. `enma info one-piece-100`
+    Info { anime_id: String },
+
+    /// episode list of an anime, e.g. `enma episodes steinsgate-3`
+    Episodes { anime_id: String },
+
+    /// servers of an episode, e.g. `enma servers "steinsgate-0-92?ep=2055"`
+    Servers { episode_id: String },
+
+    /// estimated airing schedule of a day
+    Schedule {
+        /// in `YYYY-MM-DD` format, today if omitted
+        date: Option<String>,
+    },
+
+    /// animes sorted alphabetically, e.g. `enma az-list a`
+    AzList {
+        /// `all`, `other`, `0-9` or a letter
+        #[arg(default_value = "all")]
+        sort_option: String,
+        #[arg(short, long)]
+        page: Option<u16>,
+    },
+
+    /// animes of a genre, e.g. `enma genre shounen`
+    Genre {
+        name: String,
+        #[arg(short, long)]
+        page: Option<u16>,
+    },
+
+    /// animes of a producer, e.g. `enma producer toei-animation`
+    Producer {
+        name: String,
+        #[arg(short, long)]
+        page: Option<u16>,
+    },
+
+    /// animes of a category, e.g. `enma category most-popular`
+    Category {
+        name: String,
+        #[arg(short, long)]
+        page: Option<u16>,
+    },
+
+    /// tooltip info of an anime, e.g. `enma qtip attack-on-titan-112`
+    Qtip { anime_id: String },
+}
+
+/// every [`SearchFilter`] as a flag
+#[derive(Args, Debug, Default)]
+pub struct SearchFilterArgs {
+    /// e.g. `tv`, `movie`, `ova`
+    #[arg(long = "type")]
+    pub anime_type: Option<String>,
+    /// e.g. `finished-airing`, `currently-airing`
+    #[arg(long)]
+    pub status: Option<String>,
+    /// e.g. `pg-13`, `r`
+    #[arg(long)]
+    pub rated: Option<String>,
+    /// e.g. `good`, `masterpiece`
+    #[arg(long)]
+    pub score: Option<String>,
+    /// e.g. `spring`, `fall`
+    #[arg(long)]
+    pub season: Option<String>,
+    /// `sub`, `dub` or `sub-&-dub`
+    #[arg(long)]
+    pub language: Option<String>,
+    /// in `YYYY-M-D` format
+    #[arg(long)]
+    pub start_date: Option<String>,
+    /// in `YYYY-M-D` format
+    #[arg(long)]
+    pub end_date: Option<String>,
+    /// e.g. `recently-added`, `score`
+    #[arg(long)]
+    pub sort: Option<String>,
+    /// comma separated, e.g. `action,comedy`
+    #[arg(long)]
+    pub genres: Option<String>,
+}
+
+impl SearchFilterArgs {
+    pub fn into_filters(self) -> Option<SearchFilters> {
+        let filters = [
+            self.anime_type.map(SearchFilter::Type),
+            self.status.map(SearchFilter::Status),
+            self.rated.map(SearchFilter::Rated),
+            self.score.map(SearchFilter::Score),
+            self.season.map(SearchFilter::Season),
+            self.language.map(SearchFilter::Language),
+            self.start_date.map(SearchFilter::StartDate),
+            self.end_date.map(SearchFilter::EndDate),
+            self.sort.map(SearchFilter::Sort),
+            self.genres.map(SearchFilter::Genres),
+        ]
+        .into_iter()
+        .flatten()
+        .collect::<Vec<_>>();
+
+        if filters.is_empty() {
+            return None;
+        }
+        return Some(SearchFilters::new(filters));
+    }
+}
@@ -0,0 +1,124 @@
+use chrono::{Datelike, NaiveDate, Utc};
+use clap::Parser;
+use enma::{anime::hianime, EnmaError, EnmaResult};
+use reqwest::StatusCode;
+use serde::Serialize;
+use std::process::ExitCode;
+
+mod cli;
+mod output;
+
+use cli::{Cli, Command, OutputFormat};
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    match run(cli.command, cli.output).await {
+        Ok(out) => {
+            println!("{out}");
+            ExitCode::SUCCESS
+        }
+        // the library only logs errors through tracing, which the cli doesn't subscribe to
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::from(exit_code(&e))
+        }
+    }
+}
+
+async fn run(command: Command, format: OutputFormat) -> EnmaResult<String> {
+    let hianime = hianime::Scraper::new();
+
+    return match command {
+        Command::Home => print(hianime.get_home_page().await?, format),
+        Command::Search {
+            query,
+            page,
+            filters,
+        } => print(
+            hianime
+                .get_search_results(&query, page, filters.into_filters())
+                .await?,
+            format,
+        ),
+        Command::Info { anime_id } => print(hianime.get_info(&anime_id).await?, format),
+        Command::Episodes { anime_id } => {
+            print(hianime.get_anime_episodes(&anime_id).await?, format)
+        }
+        Command::Servers { episode_id } => {
+            print(hianime.get_episode_servers(&episode_id).await?, format)
+        }
+        Command::Schedule { date } => {
+            let date = parse_date(date.as_deref())?;
+            let (year, month, day) = (date.year() as u16, date.month() as u8, date.day() as u8);
+            print(hianime.get_schedule(year, month, day).await?, format)
+        }
+        Command::AzList { sort_option, page } => {
+            print(hianime.get_az_list(&sort_option, page).await?, format)
+        }
+        Command::Genre { name, page } => print(hianime.get_genre_anime(&name, page).await?, format),
+        Command::Producer { name, page } => {
+            print(hianime.get_producer_anime(&name, page).await?, format)
+        }
+        Command::Category { name, page } => {
+            print(hianime.get_category_anime(&name, page).await?, format)
+        }
+        Command::Qtip { anime_id } => print(hianime.get_qtip_info(&anime_id).await?, format),
+    };
+}
+
+fn print<T: Serialize>(data: T, format: OutputFormat) -> EnmaResult<String> {
+    output::render(&data, format).map_err(|e| {
+        EnmaError::misc_error(
+            "enma:cli",
+            Some(format!("failed to render output: {e}")),
+            None,
+        )
+    })
+}
+
+fn parse_date(date: Option<&str>) -> EnmaResult<NaiveDate> {
+    let Some(date) = date else {
+        return Ok(Utc::now().date_naive());
+    };
+
+    return NaiveDate::parse_from_str(date.trim(), "%Y-%m-%d").map_err(|_| {
+        EnmaError::invalid_data_error(
+            "enma:cli",
+            Some(format!("invalid date '{date}', expected YYYY-MM-DD")),
+            Some(StatusCode::BAD_REQUEST),
+        )
+    });
+}
+
+/// keep in sync with the exit codes listed in [`Cli`]'s help
+fn exit_code(err: &EnmaError) -> u8 {
+    match err {
+        // `EnmaError::invalid_data_error` builds a `MiscError`, only its
+        // message prefix tells it apart
+        EnmaError::MiscError { details } if details.message.starts_with("InvalidDataError: ") => 6,
+        EnmaError::MiscError { .. } => 1,
+        EnmaError::SrcFetchError { .. } => 3,
+        EnmaError::SrcParseError { .. } => 4,
+        EnmaError::ParsingError { .. } => 5,
+        EnmaError::InvalidDataError { .. } => 6,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{exit_code, parse_date};
+    use enma::EnmaError;
+
+    // cargo test --features cli --bin enma -- test --show-output
+    #[test]
+    fn test_exit_code() {
+        assert_eq!(exit_code(&parse_date(Some("yesterday")).unwrap_err()), 6);
+        assert_eq!(exit_code(&EnmaError::misc_error("enma:cli", None, None)), 1);
+        assert_eq!(
+            exit_code(&EnmaError::src_fetch_error("enma:cli", None, None)),
+            3
+        );
+    }
+}
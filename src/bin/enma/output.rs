@@ -0,0 +1,187 @@
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::cli::OutputFormat;
+
+/// widest a table cell gets before being cut off
+const MAX_CELL_WIDTH: usize = 48;
+
+pub fn render<T: Serialize>(data: &T, format: OutputFormat) -> serde_json::Result<String> {
+    return match format {
+        OutputFormat::Pretty => serde_json::to_string_pretty(data),
+        OutputFormat::Compact => serde_json::to_string(data),
+        OutputFormat::Table => Ok(render_table(&serde_json::to_value(data)?)),
+    };
+}
+
+/// prints scalar fields as `key: value` lines and every list of objects as
+/// its own table, nested objects are flattened into dotted keys
+fn render_table(value: &Value) -> String {
+    let mut fields = vec![];
+    let mut tables = vec![];
+    collect_sections(None, value, &mut fields, &mut tables);
+
+    let mut out = String::new();
+    if !fields.is_empty() {
+        let key_width = fields
+            .iter()
+            .map(|(k, _)| k.len())
+            .max()
+            .unwrap_or_default();
+        for (key, value) in fields {
+            out.push_str(&format!("{key:<key_width$}  {value}\n"));
+        }
+    }
+
+    for (title, rows) in tables {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str(&format!("{title} ({})\n", rows.len()));
+        out.push_str(&format_rows(&rows));
+    }
+
+    return out.trim_end().to_string();
+}
+
+type Fields = Vec<(String, String)>;
+
+fn collect_sections(
+    prefix: Option<&str>,
+    value: &Value,
+    fields: &mut Fields,
+    tables: &mut Vec<(String, Vec<Fields>)>,
+) {
+    let key = |k: &str| match prefix {
+        Some(prefix) => format!("{prefix}.{k}"),
+        None => k.to_string(),
+    };
+
+    match value {
+        Value::Object(map) => {
+            for (k, v) in map {
+                collect_sections(Some(&key(k)), v, fields, tables);
+            }
+        }
+        Value::Array(items) if items.iter().any(|v| v.is_object()) => {
+            let rows = items
+                .iter()
+                .filter_map(|v| v.as_object())
+                .map(flatten_row)
+                .collect();
+            tables.push((prefix.unwrap_or("items").to_string(), rows));
+        }
+        scalar => fields.push((prefix.unwrap_or("value").to_string(), cell(scalar))),
+    }
+}
+
+fn flatten_row(map: &Map<String, Value>) -> Fields {
+    let mut row = vec![];
+    for (k, v) in map {
+        match v {
+            Value::Object(inner) => {
+                for (inner_k, inner_v) in flatten_row(inner) {
+                    row.push((format!("{k}.{inner_k}"), inner_v));
+                }
+            }
+            value => row.push((k.clone(), cell(value))),
+        }
+    }
+    return row;
+}
+
+fn cell(value: &Value) -> String {
+    let text = match value {
+        Value::Null => String::from("-"),
+        Value::String(s) => s.clone(),
+        Value::Array(items) => items.iter().map(cell).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    };
+
+    let text = text.replace(['\n', '\r'], " ");
+    if text.chars().count() > MAX_CELL_WIDTH {
+        let cut = text.chars().take(MAX_CELL_WIDTH - 1).collect::<String>();
+        return format!("{cut}…");
+    }
+    return text;
+}
+
+fn format_rows(rows: &[Fields]) -> String {
+    // columns in order of first appearance
+    let mut columns: Vec<&str> = vec![];
+    for row in rows {
+        for (k, _) in row {
+            if !columns.contains(&k.as_str()) {
+                columns.push(k);
+            }
+        }
+    }
+
+    let value_of = |row: &Fields, column: &str| {
+        row.iter()
+            .find(|(k, _)| k == column)
+            .map(|(_, v)| v.clone())
+            .unwrap_or_else(|| String::from("-"))
+    };
+    let widths = columns
+        .iter()
+        .map(|column| {
+            rows.iter()
+                .map(|row| value_of(row, column).chars().count())
+                .chain([column.len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect::<Vec<_>>();
+
+    let line = |cells: Vec<String>| {
+        cells
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| {
+                let pad = width.saturating_sub(cell.chars().count());
+                format!("{cell}{}", " ".repeat(pad))
+            })
+            .collect::<Vec<_>>()
+            .join("  ")
+            .trim_end()
+            .to_string()
+    };
+
+    let mut out = line(columns.iter().map(|c| c.to_string()).collect()) + "\n";
+    out.push_str(&line(widths.iter().map(|w| "-".repeat(*w)).collect()));
+    out.push('\n');
+    for row in rows {
+        out.push_str(&line(columns.iter().map(|c| value_of(row, c)).collect()));
+        out.push('\n');
+    }
+    return out;
+}
+
+#[cfg(test)]
+mod test {
+    use super::render_table;
+    use serde_json::json;
+
+    // cargo test --features cli --bin enma -- output::test --show-output
+    #[test]
+    fn test_render_table() {
+        let value = json!({
+            "total_pages": 2,
+            "animes": [
+                { "id": "one-piece-100", "name": "One Piece", "episodes": { "sub": 1100, "dub": null } },
+                { "id": "steinsgate-3", "name": "Steins;Gate", "episodes": { "sub": 24, "dub": 24 } },
+            ],
+        });
+
+        let table = render_table(&value);
+        println!("{table}");
+
+        let lines = table.lines().collect::<Vec<_>>();
+        assert_eq!(lines[0], "total_pages  2");
+        assert_eq!(lines[2], "animes (2)");
+        assert!(lines[3].starts_with("episodes.dub  episodes.sub  id"));
+        assert!(lines[5].contains("one-piece-100"));
+        assert!(lines[5].starts_with("-  "));
+    }
+}
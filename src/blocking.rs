@@ -0,0 +1,5 @@
+/// blocking version of [`crate::anime::hianime::Scraper`]
+///
+/// example import: `use enma::blocking::hianime;`
+#[cfg(feature = "hianime")]
+pub mod hianime;
@@ -0,0 +1,182 @@
+use crate::{
+    anime::hianime::{
+        self, FranchiseOptions, ScrapedAZList, ScrapedAnimeCharacters, ScrapedAnimeEpisodes,
+        ScrapedAnimeInfo, ScrapedCategoryAnime, ScrapedCharacterInfo, ScrapedEpisodeServers,
+        ScrapedExternalIds, ScrapedFranchise, ScrapedGenreAnime, ScrapedHomePage, ScrapedIdMapping,
+        ScrapedPersonInfo, ScrapedProducerAnime, ScrapedQtipInfo, ScrapedSchedule,
+        ScrapedSearchResult, ScrapedSearchSuggestion, ScrapedTitleMatches, SearchFilters,
+        SelectorTable, SelfTestReport, TitleHints,
+    },
+    EnmaResult,
+};
+use std::path::Path;
+use tokio::runtime::{Builder, Runtime};
+
+/// blocking hianime scraper, every method blocks the current thread on the
+/// matching method of the async [`hianime::Scraper`] and returns the same data
+///
+/// it drives its own runtime, so it must not be used from within an async
+/// context, that'd panic as tokio doesn't allow nested runtimes
+///
+/// ### Example Usage
+/// ```rust
+/// fn get_data() {
+///     use enma::blocking::hianime;
+///     let hianime = hianime::Scraper::new();
+///
+///     match hianime.get_info("steinsgate-3") {
+///         Ok(data) => println!("{data:#?}"),
+///         Err(e) => eprintln!("error: {e}"),
+///     }
+/// }
+/// ```
+#[derive(Debug)]
+pub struct Scraper {
+    inner: hianime::Scraper,
+    runtime: Runtime,
+}
+
+impl Scraper {
+    /// # Panics
+    /// when the internal runtime can't be created, same as
+    /// [`tokio::runtime::Runtime::new`]
+    pub fn new() -> Self {
+        let runtime = Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .expect("failed to create the blocking scraper's runtime");
+
+        return Self {
+            inner: hianime::Scraper::new(),
+            runtime,
+        };
+    }
+
+    /// same as [`hianime::Scraper::with_selectors`]
+    pub fn with_selectors(mut self, table: SelectorTable) -> EnmaResult<Self> {
+        self.inner = self.inner.with_selectors(table)?;
+        return Ok(self);
+    }
+
+    /// same as [`hianime::Scraper::with_id_cache_file`]
+    #[cfg(feature = "cache")]
+    pub fn with_id_cache_file(mut self, path: impl Into<std::path::PathBuf>) -> Self {
+        self.inner = self.inner.with_id_cache_file(path);
+        return self;
+    }
+
+    /// blocking version of [`hianime::Scraper::self_test`]
+    pub fn self_test(&self) -> SelfTestReport {
+        return self.runtime.block_on(self.inner.self_test());
+    }
+
+    /// same as [`hianime::Scraper::self_test_fixtures`]
+    pub fn self_test_fixtures(&self, dir: impl AsRef<Path>) -> SelfTestReport {
+        return self.inner.self_test_fixtures(dir);
+    }
+}
+
+impl Default for Scraper {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
+macro_rules! blocking_methods {
+    ($(fn $name:ident(&self $(, $arg:ident: $ty:ty)*) -> $ret:ty;)*) => {
+        impl Scraper {
+            $(
+                #[doc = concat!("blocking version of [`hianime::Scraper::", stringify!($name), "`]")]
+                pub fn $name(&self $(, $arg: $ty)*) -> EnmaResult<$ret> {
+                    self.runtime.block_on(self.inner.$name($($arg),*))
+                }
+            )*
+        }
+    };
+}
+
+blocking_methods! {
+    fn get_home_page(&self) -> ScrapedHomePage;
+    fn get_search_results(&self, query: &str, page_number: Option<u16>, filters: Option<SearchFilters>) -> ScrapedSearchResult;
+    fn get_search_suggestions(&self, query: &str) -> ScrapedSearchSuggestion;
+    fn get_info(&self, anime_id: &str) -> ScrapedAnimeInfo;
+    fn get_random_anime(&self, filters: Option<SearchFilters>) -> ScrapedAnimeInfo;
+    fn get_qtip_info(&self, anime_id: &str) -> ScrapedQtipInfo;
+    fn get_anime_episodes(&self, anime_id: &str) -> ScrapedAnimeEpisodes;
+    fn get_episode_servers(&self, anime_episode_id: &str) -> ScrapedEpisodeServers;
+    fn get_anime_characters(&self, anime_id: &str, page_number: Option<u16>) -> ScrapedAnimeCharacters;
+    fn get_character(&self, character_id: &str) -> ScrapedCharacterInfo;
+    fn get_person(&self, person_id: &str) -> ScrapedPersonInfo;
+    fn get_franchise(&self, anime_id: &str, options: Option<FranchiseOptions>) -> ScrapedFranchise;
+    fn get_schedule(&self, year: u16, month: u8, day: u8) -> ScrapedSchedule;
+    fn get_az_list(&self, sort_option: &str, page_number: Option<u16>) -> ScrapedAZList;
+    fn get_genre_anime(&self, genre_name: &str, page_number: Option<u16>) -> ScrapedGenreAnime;
+    fn get_producer_anime(&self, producer_name: &str, page_number: Option<u16>) -> ScrapedProducerAnime;
+    fn get_category_anime(&self, category_name: &str, page_number: Option<u16>) -> ScrapedCategoryAnime;
+    fn find_by_mal_id(&self, mal_id: u32) -> ScrapedIdMapping;
+    fn find_by_anilist_id(&self, anilist_id: u32) -> ScrapedIdMapping;
+    fn get_external_ids(&self, anime_id: &str) -> ScrapedExternalIds;
+    fn resolve_title(&self, query: &str, hints: Option<TitleHints>) -> ScrapedTitleMatches;
+}
+
+#[cfg(test)]
+mod test {
+    use super::Scraper;
+    use crate::{anime::hianime::SelectorTable, EnmaError};
+
+    // cargo test --features blocking --lib -- blocking::hianime::test --include-ignored --show-output
+    #[test]
+    fn test_invalid_id() {
+        let hianime = Scraper::new();
+
+        match hianime.get_info("steinsgate") {
+            Err(EnmaError::MiscError { details }) => assert_eq!(details.status, 400),
+            other => panic!("expected an invalid data error, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_self_test_fixtures() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/hianime");
+        let report = Scraper::new().self_test_fixtures(dir);
+
+        assert_eq!(report.parsers.len(), 15);
+        assert!(report.is_healthy());
+    }
+
+    #[test]
+    fn test_with_selectors() {
+        let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/fixtures/hianime");
+        let mut table = SelectorTable::default();
+        table.az_list.animes = String::from("#main-wrapper .film-item");
+        let report = Scraper::new()
+            .with_selectors(table)
+            .unwrap()
+            .self_test_fixtures(dir);
+
+        let unhealthy = report
+            .unhealthy()
+            .map(|p| p.parser.as_str())
+            .collect::<Vec<_>>();
+        assert_eq!(unhealthy, vec!["hianime:get_az_list"]);
+
+        let mut table = SelectorTable::default();
+        table.az_list.animes = String::from("#main-wrapper >>");
+        assert!(Scraper::new().with_selectors(table).is_err());
+    }
+
+    #[test]
+    #[ignore = "hits the live site"]
+    fn test_get_category_anime() {
+        let hianime = Scraper::new();
+
+        match hianime.get_category_anime("most-popular", Some(2)) {
+            Ok(data) => {
+                println!("{data:#?}");
+                assert_eq!(data.current_page, 2);
+                assert!(!data.animes.is_empty());
+            }
+            Err(e) => eprintln!("error {}", e),
+        }
+    }
+}
@@ -48,7 +48,14 @@ impl ErrorDetails {
             status,
         };
 
-        eprintln!("{err}");
+        // a library shouldn't write to stderr, subscribers pick it up instead
+        #[cfg(any(feature = "hianime", feature = "extractors", feature = "subtitles"))]
+        tracing::debug!(
+            provider_parser = err.provider_parser,
+            status = err.status,
+            message = %err.message,
+            "enma error"
+        );
         return err;
     }
 }
@@ -5,19 +5,38 @@
     html_logo_url = "https://raw.githubusercontent.com/ghoshRitesh12/aniwatch-api/refs/heads/main/public/img/hianime_v2.png"
 )]
 #![doc(issue_tracker_base_url = "https://github.com/DaioEnma/enma.rs/issues")]
-#![doc = include_str!("../README.md")]
+#![cfg_attr(feature = "hianime", doc = include_str!("../README.md"))]
 
+#[cfg(any(feature = "hianime", feature = "extractors", feature = "subtitles"))]
+#[cfg_attr(not(feature = "hianime"), allow(dead_code))]
 mod utils;
 
 /// namespace for anime scraper providers
 ///
 /// example import: `use enma::anime;`
+#[cfg(any(feature = "hianime", feature = "extractors", feature = "subtitles"))]
 pub mod anime;
 
 /// namespace for manga scraper providers
 ///
 /// example import: `use enma::manga;`
+#[cfg(feature = "mangareader")]
 pub mod manga;
 
+/// blocking versions of the scrapers, behind the `blocking` feature
+///
+/// example import: `use enma::blocking;`
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
 mod error;
 pub use error::{EnmaError, EnmaResult};
+
+mod schema;
+pub use schema::{SchemaVersion, SCHEMA_VERSION};
+
+#[cfg(all(
+    test,
+    any(feature = "hianime", feature = "extractors", feature = "subtitles")
+))]
+mod test_utils;
@@ -4,6 +4,12 @@ pub struct Scraper {}
 const SRC_BASE_URL: &str = "https://mangareader.to";
 const SRC_HOME_URL: &str = "https://mangareader.to/home";
 
+impl Default for Scraper {
+    fn default() -> Self {
+        return Self::new();
+    }
+}
+
 impl Scraper {
     pub fn new() -> Scraper {
         println!("{}{}", SRC_BASE_URL, SRC_HOME_URL);
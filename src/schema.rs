@@ -0,0 +1,70 @@
+use serde::{Deserialize, Serialize};
+
+/// version of the JSON shape of the result types, bumped whenever a field is
+/// renamed, removed or changes its type
+pub const SCHEMA_VERSION: u32 = 1;
+
+/// marks which [`SCHEMA_VERSION`] a result was serialized with, so cached
+/// results can be told apart from ones of an incompatible version
+///
+/// new results always carry the current version, results serialized before
+/// the marker existed deserialize as version `0`
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "schema", derive(schemars::JsonSchema))]
+#[serde(transparent)]
+pub struct SchemaVersion(u32);
+
+impl SchemaVersion {
+    pub const CURRENT: SchemaVersion = SchemaVersion(SCHEMA_VERSION);
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+
+    pub fn is_current(&self) -> bool {
+        *self == Self::CURRENT
+    }
+
+    /// version of results serialized before the marker existed, usable as
+    /// `#[serde(default = "SchemaVersion::unversioned")]`
+    pub fn unversioned() -> Self {
+        SchemaVersion(0)
+    }
+}
+
+impl Default for SchemaVersion {
+    fn default() -> Self {
+        Self::CURRENT
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{SchemaVersion, SCHEMA_VERSION};
+    use serde::Deserialize;
+
+    #[derive(Deserialize)]
+    struct Cached {
+        #[serde(default = "SchemaVersion::unversioned")]
+        schema_version: SchemaVersion,
+    }
+
+    // cargo test --lib -- schema::test --show-output
+    #[test]
+    fn test_schema_version() {
+        assert!(SchemaVersion::default().is_current());
+        assert_eq!(
+            serde_json::to_string(&SchemaVersion::CURRENT).unwrap(),
+            SCHEMA_VERSION.to_string()
+        );
+
+        let cached = serde_json::from_str::<Cached>("{}").unwrap();
+        assert_eq!(cached.schema_version.get(), 0);
+        assert!(!cached.schema_version.is_current());
+
+        let cached =
+            serde_json::from_str::<Cached>(&format!(r#"{{"schema_version":{SCHEMA_VERSION}}}"#))
+                .unwrap();
+        assert!(cached.schema_version.is_current());
+    }
+}
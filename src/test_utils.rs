@@ -0,0 +1,212 @@
+//! helpers shared by the unit tests hitting a local http server
+
+// each feature set only compiles the tests using some of the helpers
+#![allow(dead_code)]
+
+use reqwest::StatusCode;
+use std::{
+    collections::HashMap,
+    net::SocketAddr,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    io::{AsyncReadExt, AsyncWriteExt},
+    net::{TcpListener, TcpStream},
+};
+
+/// contents of `fixtures/{path}`
+pub(crate) fn fixture(path: &str) -> String {
+    return String::from_utf8(fixture_bytes(path)).unwrap();
+}
+
+pub(crate) fn fixture_bytes(path: &str) -> Vec<u8> {
+    let path = std::path::Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("fixtures")
+        .join(path);
+    return std::fs::read(path).unwrap();
+}
+
+/// the responses of a [`TestServer`], keyed by request target; a target
+/// with a query string only matches that exact query, one without matches
+/// any query
+#[derive(Debug, Default)]
+pub(crate) struct Routes {
+    responses: HashMap<String, (StatusCode, Vec<u8>)>,
+    failures: HashMap<String, usize>,
+    ignore_ranges: bool,
+}
+
+impl Routes {
+    pub fn new() -> Self {
+        return Self::default();
+    }
+
+    pub fn route(self, target: &str, body: impl Into<Vec<u8>>) -> Self {
+        return self.route_with_status(target, StatusCode::OK, body);
+    }
+
+    pub fn route_with_status(
+        mut self,
+        target: &str,
+        status: StatusCode,
+        body: impl Into<Vec<u8>>,
+    ) -> Self {
+        self.responses
+            .insert(target.to_string(), (status, body.into()));
+        return self;
+    }
+
+    /// answers the first `times` requests of `target` with a 503
+    pub fn failing(mut self, target: &str, times: usize) -> Self {
+        self.failures.insert(target.to_string(), times);
+        return self;
+    }
+
+    /// answers range requests with the whole body, like a server not
+    /// supporting ranges
+    pub fn ignoring_ranges(mut self) -> Self {
+        self.ignore_ranges = true;
+        return self;
+    }
+
+    /// serves the routes on a random local port until the test ends
+    pub async fn serve(self) -> TestServer {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let server = TestServer {
+            addr: listener.local_addr().unwrap(),
+            hits: Arc::default(),
+        };
+
+        let routes = Arc::new(self);
+        let hits = server.hits.clone();
+        tokio::spawn(async move {
+            loop {
+                let (socket, _) = listener.accept().await.unwrap();
+                tokio::spawn(routes.clone().respond(socket, hits.clone()));
+            }
+        });
+
+        return server;
+    }
+
+    async fn respond(self: Arc<Self>, mut socket: TcpStream, hits: Hits) {
+        let Some(head) = read_request(&mut socket).await else {
+            return;
+        };
+        let target = head.split(' ').nth(1).unwrap_or_default().to_string();
+        let path = target.split('?').next().unwrap_or_default().to_string();
+        let range = head.lines().find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            if !name.eq_ignore_ascii_case("range") {
+                return None;
+            }
+            let (start, end) = value.trim().strip_prefix("bytes=")?.split_once('-')?;
+            return Some((start.parse::<usize>().ok()?, end.parse::<usize>().ok()?));
+        });
+
+        let hit = {
+            let mut hits = hits.lock().unwrap();
+            let hit = hits.entry(path.clone()).or_default();
+            *hit += 1;
+            *hit
+        };
+
+        let response = self
+            .responses
+            .get(&target)
+            .or_else(|| self.responses.get(&path));
+        let failures = self.failures.get(&target).or(self.failures.get(&path));
+        let (status, body) = match response {
+            Some(_) if failures.is_some_and(|&times| hit <= times) => {
+                (StatusCode::SERVICE_UNAVAILABLE, Vec::new())
+            }
+            Some((StatusCode::OK, body)) if self.ignore_ranges => (StatusCode::OK, body.clone()),
+            Some((StatusCode::OK, body)) => match range {
+                Some((start, end)) if start <= end && end < body.len() => {
+                    (StatusCode::PARTIAL_CONTENT, body[start..=end].to_vec())
+                }
+                Some(_) => (StatusCode::RANGE_NOT_SATISFIABLE, Vec::new()),
+                None => (StatusCode::OK, body.clone()),
+            },
+            Some((status, body)) => (*status, body.clone()),
+            None => (StatusCode::NOT_FOUND, Vec::new()),
+        };
+
+        let head = format!(
+            "HTTP/1.1 {status}\r\ncontent-length: {}\r\nconnection: close\r\n\r\n",
+            body.len()
+        );
+        let _ = socket.write_all(head.as_bytes()).await;
+        let _ = socket.write_all(&body).await;
+    }
+}
+
+type Hits = Arc<Mutex<HashMap<String, usize>>>;
+
+/// a running [`Routes`] server
+#[derive(Debug, Clone)]
+pub(crate) struct TestServer {
+    addr: SocketAddr,
+    hits: Hits,
+}
+
+impl TestServer {
+    /// the server's base url, e.g. `http://127.0.0.1:41234`
+    pub fn base(&self) -> String {
+        return format!("http://{}", self.addr);
+    }
+
+    pub fn url(&self, target: &str) -> String {
+        return format!("{}{target}", self.base());
+    }
+
+    /// how many times `path` has been requested, whatever its query
+    pub fn hits(&self, path: &str) -> usize {
+        return self
+            .hits
+            .lock()
+            .unwrap()
+            .get(path)
+            .copied()
+            .unwrap_or_default();
+    }
+}
+
+/// reads the head of the request and skips its body, so that closing the
+/// socket doesn't reset the connection before the client reads the response
+async fn read_request(socket: &mut TcpStream) -> Option<String> {
+    let mut req = Vec::new();
+    let mut buf = [0; 1024];
+    let head_end = loop {
+        if let Some(i) = req.windows(4).position(|w| w == b"\r\n\r\n") {
+            break i + 4;
+        }
+        let n = socket.read(&mut buf).await.ok()?;
+        if n == 0 {
+            return None;
+        }
+        req.extend_from_slice(&buf[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&req[..head_end]).to_string();
+    let content_length = head
+        .lines()
+        .find_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            return name
+                .eq_ignore_ascii_case("content-length")
+                .then(|| value.trim().parse::<usize>().ok())?;
+        })
+        .unwrap_or_default();
+
+    let mut body_read = req.len() - head_end;
+    while body_read < content_length {
+        let n = socket.read(&mut buf).await.ok()?;
+        if n == 0 {
+            break;
+        }
+        body_read += n;
+    }
+
+    return Some(head);
+}
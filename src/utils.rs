@@ -2,12 +2,19 @@
 
 use crate::error::{EnmaError, EnmaResult};
 use reqwest::{
-    header::{HeaderMap, HeaderValue, ACCEPT, ACCEPT_ENCODING, USER_AGENT},
-    Client, StatusCode,
+    header::{HeaderMap, HeaderValue, ACCEPT, ACCEPT_ENCODING, RANGE, USER_AGENT},
+    Client, Response, StatusCode,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::hash_map::RandomState,
+    future::Future,
+    hash::{BuildHasher, Hasher},
+    time::{Instant, SystemTime, UNIX_EPOCH},
 };
-use serde::de::DeserializeOwned;
 use urlencoding::{decode, encode};
 
+#[allow(clippy::enum_variant_names)]
 pub enum EnmaUtils {
     AcceptHeader,
     UserAgentHeader,
@@ -35,14 +42,17 @@ impl EnmaUtils {
                 ACCEPT,
                 HeaderValue::from_static(EnmaUtils::AcceptHeader.value()),
             ),
-            (
-                ACCEPT_ENCODING,
-                HeaderValue::from_static(EnmaUtils::AcceptEncodingHeader.value()),
-            ),
         ]
         .into_iter()
         .collect();
 
+        // only ask for compressed responses when they can be decompressed
+        #[cfg(feature = "compression")]
+        headers.insert(
+            ACCEPT_ENCODING,
+            HeaderValue::from_static(EnmaUtils::AcceptEncodingHeader.value()),
+        );
+
         if let Some(r_headers) = req_headers {
             for (k, v) in r_headers {
                 if let Some(name) = k {
@@ -51,10 +61,11 @@ impl EnmaUtils {
             }
         }
 
-        return Client::builder()
-            .gzip(true)
-            .brotli(true)
-            .deflate(true)
+        let builder = Client::builder();
+        #[cfg(feature = "compression")]
+        let builder = builder.gzip(true).brotli(true).deflate(true);
+
+        return builder
             .default_headers(headers)
             .build()
             .expect("Could not initialize HTTP client");
@@ -101,31 +112,86 @@ impl EnmaUtils {
 
         Ok(decoded_str)
     }
+
+    /// returns a pseudo random index in `0..upper`, good enough for sampling
+    /// but not for anything security related
+    pub fn random_index(upper: usize) -> usize {
+        if upper <= 1 {
+            return 0;
+        }
+
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or_default();
+
+        let mut hasher = RandomState::new().build_hasher();
+        hasher.write_u64(nanos);
+
+        return (hasher.finish() % upper as u64) as usize;
+    }
 }
 
+/// http client the scrapers fetch with, its futures are `Send` so that
+/// scraper calls can be spawned onto multi-threaded runtimes
 pub trait EnmaClient {
-    async fn get_html(
+    fn get_html(
         &self,
         url: String,
         headers: Option<HeaderMap>,
         provider_parser: &'static str,
-    ) -> EnmaResult<String>;
+    ) -> impl Future<Output = EnmaResult<String>> + Send;
 
-    async fn get_json<T: DeserializeOwned>(
+    fn get_json<T: DeserializeOwned + Send>(
         &self,
         url: String,
         headers: Option<HeaderMap>,
         provider_parser: &'static str,
-    ) -> EnmaResult<T>;
+    ) -> impl Future<Output = EnmaResult<T>> + Send;
+
+    fn post_json<T: DeserializeOwned + Send, B: Serialize + Sync + ?Sized>(
+        &self,
+        url: String,
+        body: &B,
+        headers: Option<HeaderMap>,
+        provider_parser: &'static str,
+    ) -> impl Future<Output = EnmaResult<T>> + Send;
+
+    /// raw body of `url`, unlike the other methods a non 2xx response is an error,
+    /// so is a range request answered with anything but a 206
+    #[cfg_attr(
+        not(any(feature = "extractors", feature = "subtitles")),
+        allow(dead_code)
+    )]
+    fn get_bytes(
+        &self,
+        url: String,
+        headers: Option<HeaderMap>,
+        provider_parser: &'static str,
+    ) -> impl Future<Output = EnmaResult<Vec<u8>>> + Send;
+
+    /// follows the redirects of `url` and returns the url it finally resolved to
+    fn get_final_url(
+        &self,
+        url: String,
+        headers: Option<HeaderMap>,
+        provider_parser: &'static str,
+    ) -> impl Future<Output = EnmaResult<String>> + Send;
 }
 
 impl EnmaClient for Client {
+    #[tracing::instrument(
+        name = "get_html",
+        skip(self, headers),
+        fields(status, latency_ms, bytes)
+    )]
     async fn get_html(
         &self,
         url: String,
         headers: Option<HeaderMap>,
         provider_parser: &'static str,
     ) -> EnmaResult<String> {
+        let started = Instant::now();
         let response = match self
             .get(url)
             .headers(headers.unwrap_or_default())
@@ -133,13 +199,15 @@ impl EnmaClient for Client {
             .await
         {
             Ok(resp) => resp,
-            Err(_) => return Err(EnmaError::src_fetch_error(provider_parser, None, None)),
+            Err(e) => return Err(fetch_error(provider_parser, e)),
         };
+        record_response(&response, started);
 
         let html = response
             .text()
             .await
             .map_err(|_| EnmaError::src_parse_error(provider_parser, None, None))?;
+        record_body(html.len(), started);
 
         if html.is_empty() {
             return Err(EnmaError::src_parse_error(provider_parser, None, None));
@@ -148,12 +216,18 @@ impl EnmaClient for Client {
         return Ok(html);
     }
 
-    async fn get_json<T: DeserializeOwned>(
+    #[tracing::instrument(
+        name = "get_json",
+        skip(self, headers),
+        fields(status, latency_ms, bytes)
+    )]
+    async fn get_json<T: DeserializeOwned + Send>(
         &self,
         url: String,
         headers: Option<HeaderMap>,
         provider_parser: &'static str,
     ) -> EnmaResult<T> {
+        let started = Instant::now();
         let response = match self
             .get(url)
             .headers(headers.unwrap_or_default())
@@ -161,14 +235,223 @@ impl EnmaClient for Client {
             .await
         {
             Ok(resp) => resp,
-            Err(_) => return Err(EnmaError::src_fetch_error(provider_parser, None, None)),
+            Err(e) => return Err(fetch_error(provider_parser, e)),
         };
+        record_response(&response, started);
 
-        let data = response
-            .json::<T>()
+        let body = response
+            .bytes()
             .await
             .map_err(|_| EnmaError::src_parse_error(provider_parser, None, None))?;
+        record_body(body.len(), started);
+
+        let data = serde_json::from_slice::<T>(&body)
+            .map_err(|_| EnmaError::src_parse_error(provider_parser, None, None))?;
 
         Ok(data)
     }
+
+    #[tracing::instrument(
+        name = "post_json",
+        skip(self, body, headers),
+        fields(status, latency_ms, bytes)
+    )]
+    async fn post_json<T: DeserializeOwned + Send, B: Serialize + Sync + ?Sized>(
+        &self,
+        url: String,
+        body: &B,
+        headers: Option<HeaderMap>,
+        provider_parser: &'static str,
+    ) -> EnmaResult<T> {
+        let started = Instant::now();
+        let response = match self
+            .post(url)
+            .headers(headers.unwrap_or_default())
+            .json(body)
+            .send()
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) => return Err(fetch_error(provider_parser, e)),
+        };
+        record_response(&response, started);
+
+        if !response.status().is_success() {
+            return Err(EnmaError::src_fetch_error(
+                provider_parser,
+                None,
+                Some(response.status()),
+            ));
+        }
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(|_| EnmaError::src_parse_error(provider_parser, None, None))?;
+        record_body(body.len(), started);
+
+        let data = serde_json::from_slice::<T>(&body)
+            .map_err(|_| EnmaError::src_parse_error(provider_parser, None, None))?;
+
+        Ok(data)
+    }
+
+    #[tracing::instrument(
+        name = "get_bytes",
+        skip(self, headers),
+        fields(status, latency_ms, bytes)
+    )]
+    async fn get_bytes(
+        &self,
+        url: String,
+        headers: Option<HeaderMap>,
+        provider_parser: &'static str,
+    ) -> EnmaResult<Vec<u8>> {
+        let started = Instant::now();
+        let headers = headers.unwrap_or_default();
+        let ranged = headers.contains_key(RANGE);
+        let response = match self.get(url).headers(headers).send().await {
+            Ok(resp) => resp,
+            Err(e) => return Err(fetch_error(provider_parser, e)),
+        };
+        record_response(&response, started);
+
+        if !response.status().is_success() {
+            return Err(EnmaError::src_fetch_error(
+                provider_parser,
+                None,
+                Some(response.status()),
+            ));
+        }
+
+        // a server ignoring the range sends the whole resource instead
+        if ranged && response.status() != StatusCode::PARTIAL_CONTENT {
+            return Err(EnmaError::src_fetch_error(
+                provider_parser,
+                Some(String::from("range request wasn't answered with a range")),
+                Some(response.status()),
+            ));
+        }
+
+        let body = response
+            .bytes()
+            .await
+            .map_err(|_| EnmaError::src_parse_error(provider_parser, None, None))?;
+        record_body(body.len(), started);
+
+        return Ok(body.to_vec());
+    }
+
+    #[tracing::instrument(
+        name = "get_final_url",
+        skip(self, headers),
+        fields(status, latency_ms)
+    )]
+    async fn get_final_url(
+        &self,
+        url: String,
+        headers: Option<HeaderMap>,
+        provider_parser: &'static str,
+    ) -> EnmaResult<String> {
+        let started = Instant::now();
+        let response = match self
+            .get(url)
+            .headers(headers.unwrap_or_default())
+            .send()
+            .await
+        {
+            Ok(resp) => resp,
+            Err(e) => return Err(fetch_error(provider_parser, e)),
+        };
+        record_response(&response, started);
+
+        if !response.status().is_success() {
+            return Err(EnmaError::src_fetch_error(
+                provider_parser,
+                None,
+                Some(response.status()),
+            ));
+        }
+
+        return Ok(response.url().to_string());
+    }
+}
+
+fn fetch_error(provider_parser: &'static str, err: reqwest::Error) -> EnmaError {
+    tracing::warn!(error = %err, "request failed");
+    return EnmaError::src_fetch_error(provider_parser, None, err.status());
+}
+
+/// records the status and the time it took to get the response headers on
+/// the current request's span
+fn record_response(response: &Response, started: Instant) {
+    let span = tracing::Span::current();
+    span.record("status", response.status().as_u16());
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+}
+
+/// records the body size and the time it took to get the whole response on
+/// the current request's span
+fn record_body(bytes: usize, started: Instant) {
+    let span = tracing::Span::current();
+    span.record("bytes", bytes);
+    span.record("latency_ms", started.elapsed().as_millis() as u64);
+    tracing::debug!("fetched");
+}
+
+#[cfg(test)]
+mod test {
+    use super::EnmaClient;
+    use crate::{test_utils::Routes, EnmaError};
+    use reqwest::StatusCode;
+    use tracing_test::traced_test;
+
+    const BODY: &'static str = "<html><body>enma</body></html>";
+
+    // cargo test --lib -- utils::test --show-output
+    #[tokio::test]
+    #[traced_test]
+    async fn test_get_html_span() {
+        let server = Routes::new().route("/home", BODY).serve().await;
+
+        let client = reqwest::Client::new();
+        let html = client
+            .get_html(server.url("/home"), None, "hianime:test")
+            .await
+            .unwrap();
+
+        assert_eq!(html, BODY);
+        assert!(logs_contain("get_html"));
+        assert!(logs_contain("status=200"));
+        assert!(logs_contain(&format!("bytes={}", BODY.len())));
+        assert!(logs_contain("provider_parser=\"hianime:test\""));
+    }
+
+    #[tokio::test]
+    async fn test_post_json_status() {
+        let server = Routes::new()
+            .route("/graphql", r#"{"data": 1}"#)
+            .route_with_status(
+                "/limited",
+                StatusCode::TOO_MANY_REQUESTS,
+                r#"{"errors": []}"#,
+            )
+            .serve()
+            .await;
+
+        let client = reqwest::Client::new();
+        let data = client
+            .post_json::<serde_json::Value, _>(server.url("/graphql"), &1, None, "anilist")
+            .await
+            .unwrap();
+        assert_eq!(data["data"], 1);
+
+        // an error status is reported as such rather than as a parse error
+        let err = client
+            .post_json::<serde_json::Value, _>(server.url("/limited"), &1, None, "anilist")
+            .await
+            .unwrap_err();
+        assert!(matches!(err, EnmaError::SrcFetchError { .. }), "{err:?}");
+        assert!(err.to_string().contains("429"), "{err}");
+    }
 }